@@ -1,7 +1,15 @@
-use clap::{Parser, Subcommand};
+use anyhow::Context;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::ffi::OsString;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::config::{OutputFormat, TableStyle};
+use crate::config::{
+    FailOn, HistoryOutputFormat, OutputFormat, SortBy, StatusFilter, TableStyle, VersionsFormat,
+};
+use crate::project_config::{ProjectConfig, ProjectConfigUsage};
 
 const NOT_PROVIDED: &str = "<NOT PROVIDED>";
 
@@ -13,6 +21,71 @@ pub struct Args {
     /// Output debug information without doing anything
     #[arg(long = "debug", global = true)]
     pub debug: bool,
+    // set when options were read from the project config file
+    #[arg(skip)]
+    pub project_config: Option<ProjectConfigUsage>,
+}
+
+impl Args {
+    // like Args::parse, but with the options in the project config file, if there's one, used as
+    // defaults
+    pub fn parse_with_project_config() -> anyhow::Result<Self> {
+        parse_from(std::env::args_os(), ProjectConfig::discover)
+    }
+}
+
+fn parse_from<I, T, F>(args: I, get_project_config: F) -> anyhow::Result<Args>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+    F: FnOnce() -> anyhow::Result<Option<ProjectConfig>>,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let command = Args::command();
+    let matches = command.clone().get_matches_from(&args);
+
+    // the project config file only has options for run, so it's not read for other subcommands
+    let project_config = match matches.subcommand_name() {
+        Some("run") => get_project_config()?,
+        _ => None,
+    };
+    let Some(project_config) = project_config else {
+        return Ok(from_matches(command, &matches));
+    };
+
+    let run = command
+        .find_subcommand("run")
+        .cloned()
+        .context("couldn't find the run subcommand")?;
+    let (run, applied) = project_config.apply_to_run(run)?;
+    let command = command.mut_subcommand("run", |_| run);
+    let matches = command.clone().get_matches_from(&args);
+
+    let options = applied
+        .into_iter()
+        .filter(|(_, id)| {
+            matches
+                .subcommand_matches("run")
+                .and_then(|run| run.value_source(id.as_str()))
+                != Some(ValueSource::CommandLine)
+        })
+        .map(|(key, _)| key)
+        .collect();
+
+    let mut parsed = from_matches(command, &matches);
+    parsed.project_config = Some(ProjectConfigUsage {
+        path: project_config.path,
+        options,
+    });
+
+    Ok(parsed)
+}
+
+// exits on errors, the same way Args::parse does
+fn from_matches(mut command: clap::Command, matches: &ArgMatches) -> Args {
+    Args::from_arg_matches(matches)
+        .map_err(|e| e.format(&mut command))
+        .unwrap_or_else(|e| e.exit())
 }
 
 #[derive(Subcommand, Debug)]
@@ -20,26 +93,48 @@ pub enum EnveeCommand {
     /// Show results based on a versions file
     #[command(name = "run")]
     Run {
-        /// Path to the versions file
-        #[arg(
-            long = "versions",
-            short = 'V',
-            value_name = "PATH",
-            default_value = "versions.toml"
-        )]
-        versions_file_path: PathBuf,
+        #[command(flatten)]
+        versions_args: VersionsArgs,
         /// Only validate versions file
         #[arg(long = "validate-only")]
         only_validate_versions: bool,
-        /// Skip fetching and showing commit logs
-        #[arg(long = "no-commit-logs", short = 'C')]
-        no_commit_logs: bool,
+        /// Browse results and commit logs in an interactive terminal UI
+        #[arg(
+            long = "interactive",
+            short = 'i',
+            conflicts_with_all = ["only_validate_versions", "output_format", "fail_on"]
+        )]
+        interactive: bool,
+        #[command(flatten)]
+        commit_log_args: CommitLogArgs,
         /// Output format
         #[arg(long = "output-format", short = 'o', default_value_t = OutputFormat::Stdout, value_name = "FORMAT")]
         output_format: OutputFormat,
         /// Regex to use for filtering apps
         #[arg(long = "filter", short = 'f', value_name = "REGEX")]
         app_filter: Option<String>,
+        /// Only show apps with these sync statuses (comma separated)
+        #[arg(long = "status", value_name = "STATUS", value_delimiter = ',')]
+        status_filter: Vec<StatusFilter>,
+        /// Exit with a non-zero code if any of these conditions are met (comma separated)
+        #[arg(long = "fail-on", value_name = "CONDITION", value_delimiter = ',')]
+        fail_on: Vec<FailOn>,
+        /// Attribute to sort apps by
+        #[arg(long = "sort-by", default_value_t = SortBy::App, value_name = "ATTRIBUTE")]
+        sort_by: SortBy,
+        /// Record the results in this history file, and show how long apps have been out of sync
+        #[arg(
+            long = "history",
+            value_name = "PATH",
+            conflicts_with = "only_validate_versions"
+        )]
+        history_path: Option<PathBuf>,
+        /// Add a job summary, annotations, and step outputs when running in GitHub Actions (turned on automatically if GITHUB_ACTIONS is "true")
+        #[arg(
+            long = "github-actions",
+            conflicts_with_all = ["only_validate_versions", "interactive"]
+        )]
+        github_actions: bool,
         /// Table style for stdout output
         #[arg(long = "stdout-table-style", default_value_t = TableStyle::Utf8, value_name="STRING")]
         stdout_table_style: TableStyle,
@@ -59,23 +154,290 @@ pub enum EnveeCommand {
         /// Path to custom HTML template file
         #[arg(long = "html-template", value_name = "PATH")]
         html_template_path: Option<PathBuf>,
+        /// Title for Markdown report
+        #[arg(
+            long = "markdown-title",
+            value_name = "STRING",
+            default_value = "envee"
+        )]
+        markdown_title: String,
+    },
+    /// Serve an HTML report based on a versions file, refreshing it periodically
+    #[command(name = "serve")]
+    Serve {
+        #[command(flatten)]
+        versions_args: VersionsArgs,
+        /// Also refresh the report as soon as any of the versions files change
+        #[arg(long = "watch")]
+        watch: bool,
+        /// How often to refresh the report (eg. "30s", "5m")
+        #[arg(
+            long = "refresh-interval",
+            value_name = "DURATION",
+            default_value = "5m",
+            value_parser = humantime::parse_duration
+        )]
+        refresh_interval: Duration,
+        /// Address to listen on
+        #[arg(long = "host", value_name = "IP", default_value = "127.0.0.1")]
+        host: IpAddr,
+        /// Port to listen on
+        #[arg(
+            long = "port",
+            short = 'p',
+            value_name = "NUMBER",
+            default_value_t = 8080
+        )]
+        port: u16,
+        #[command(flatten)]
+        commit_log_args: CommitLogArgs,
+        /// Regex to use for filtering apps
+        #[arg(long = "filter", short = 'f', value_name = "REGEX")]
+        app_filter: Option<String>,
+        /// Only show apps with these sync statuses (comma separated)
+        #[arg(long = "status", value_name = "STATUS", value_delimiter = ',')]
+        status_filter: Vec<StatusFilter>,
+        /// Attribute to sort apps by
+        #[arg(long = "sort-by", default_value_t = SortBy::App, value_name = "ATTRIBUTE")]
+        sort_by: SortBy,
+        /// Record the results of every refresh in this history file, and show how long apps have
+        /// been out of sync
+        #[arg(long = "history", value_name = "PATH")]
+        history_path: Option<PathBuf>,
+        /// Title for HTML report
+        #[arg(long = "html-title", value_name = "STRING", default_value = "envee")]
+        html_title: String,
+        /// Path to custom HTML template file
+        #[arg(long = "html-template", value_name = "PATH")]
+        html_template_path: Option<PathBuf>,
+    },
+    /// Show what changed between two versions files, eg. yesterday's and today's
+    #[command(name = "diff")]
+    Diff {
+        /// Versions file to compare against, or - for stdin
+        #[arg(value_name = "OLD")]
+        old_path: PathBuf,
+        /// Versions file with the changes, or - for stdin
+        #[arg(value_name = "NEW")]
+        new_path: PathBuf,
+        /// Format of the versions files (detected from the file extension by default)
+        #[arg(long = "versions-format", value_name = "FORMAT")]
+        versions_format: Option<VersionsFormat>,
+        /// Read OLD (and the files it points to) as it was at this git revision
+        #[arg(long = "old-git-ref", value_name = "REV")]
+        old_git_ref: Option<String>,
+        /// Read NEW (and the files it points to) as it was at this git revision
+        #[arg(long = "new-git-ref", value_name = "REV")]
+        new_git_ref: Option<String>,
+        /// Git repository to read versions files from when a git revision is used [default: .]
+        #[arg(long = "versions-repo", value_name = "PATH")]
+        repo: Option<PathBuf>,
+        #[command(flatten)]
+        commit_log_args: CommitLogArgs,
+        /// Output format
+        #[arg(long = "output-format", short = 'o', default_value_t = OutputFormat::Stdout, value_name = "FORMAT")]
+        output_format: OutputFormat,
+        /// Regex to use for filtering apps
+        #[arg(long = "filter", short = 'f', value_name = "REGEX")]
+        app_filter: Option<String>,
+        /// Table style for stdout output
+        #[arg(long = "stdout-table-style", default_value_t = TableStyle::Utf8, value_name="STRING")]
+        stdout_table_style: TableStyle,
+        /// Whether to use output text to stdout without color
+        #[arg(long = "stdout-plain")]
+        stdout_plain_output: bool,
+        /// Path for the HTML output file
+        #[arg(
+            long = "html-output",
+            value_name = "PATH",
+            default_value = "envee-diff.html"
+        )]
+        html_output_path: PathBuf,
+        /// Title for HTML report
+        #[arg(
+            long = "html-title",
+            value_name = "STRING",
+            default_value = "envee diff"
+        )]
+        html_title: String,
+        /// Title for Markdown report
+        #[arg(
+            long = "markdown-title",
+            value_name = "STRING",
+            default_value = "envee diff"
+        )]
+        markdown_title: String,
+    },
+    /// Show version timelines, drift durations, and deployment frequencies from a history file
+    #[command(name = "history")]
+    History {
+        /// History file written to by `run --history` or `serve --history`
+        #[arg(long = "history", value_name = "PATH")]
+        history_path: PathBuf,
+        /// Only consider changes recorded within this long (eg. "7d", "4weeks")
+        #[arg(
+            long = "since",
+            value_name = "DURATION",
+            value_parser = humantime::parse_duration
+        )]
+        since: Option<Duration>,
+        /// Output format
+        #[arg(long = "output-format", short = 'o', default_value_t = HistoryOutputFormat::Stdout, value_name = "FORMAT")]
+        output_format: HistoryOutputFormat,
+        /// Regex to use for filtering apps
+        #[arg(long = "filter", short = 'f', value_name = "REGEX")]
+        app_filter: Option<String>,
+        /// Table style for stdout output
+        #[arg(long = "stdout-table-style", default_value_t = TableStyle::Utf8, value_name="STRING")]
+        stdout_table_style: TableStyle,
+        /// Whether to use output text to stdout without color
+        #[arg(long = "stdout-plain")]
+        stdout_plain_output: bool,
     },
 }
 
+// flags that decide where versions are read from, shared by the subcommands that read a single set
+// of versions
+#[derive(clap::Args, Debug)]
+pub struct VersionsArgs {
+    /// Path to a versions file, a directory of them, or - for stdin (repeatable; files are merged)
+    #[arg(
+        long = "versions",
+        short = 'V',
+        value_name = "PATH",
+        default_value = "versions.toml"
+    )]
+    pub paths: Vec<PathBuf>,
+    /// Order of environments (comma separated), overriding envs in the versions files
+    #[arg(long = "envs", value_name = "ENV", value_delimiter = ',')]
+    pub envs: Vec<String>,
+    /// Format of the versions files (detected from the file extension by default)
+    #[arg(long = "versions-format", value_name = "FORMAT")]
+    pub format: Option<VersionsFormat>,
+    /// Read versions files (and the files they point to) as they were at this git revision
+    #[arg(long = "versions-git-ref", value_name = "REV")]
+    pub git_ref: Option<String>,
+    /// Git repository to read versions files from when --versions-git-ref is used [default: .]
+    #[arg(long = "versions-repo", value_name = "PATH", requires = "git_ref")]
+    pub repo: Option<PathBuf>,
+}
+
+impl VersionsArgs {
+    fn debug_info(&self) -> String {
+        format!(
+            r#"versions files:                       {}
+envs:                                 {}
+versions format:                      {}
+versions git ref:                     {}
+versions repo:                        {}"#,
+            join_or_not_provided(
+                &self
+                    .paths
+                    .iter()
+                    .map(|p| p.to_string_lossy())
+                    .collect::<Vec<_>>()
+            ),
+            join_or_not_provided(&self.envs),
+            self.format
+                .map(|f| f.to_string())
+                .unwrap_or(NOT_PROVIDED.to_string()),
+            self.git_ref.as_deref().unwrap_or(NOT_PROVIDED),
+            self.repo
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or(NOT_PROVIDED.to_string()),
+        )
+    }
+}
+
+// flags that decide how commit logs are fetched, shared by the subcommands that fetch them
+#[derive(clap::Args, Debug)]
+pub struct CommitLogArgs {
+    /// Skip fetching and showing commit logs
+    #[arg(long = "no-commit-logs", short = 'C')]
+    pub no_commit_logs: bool,
+    /// Maximum number of commits to fetch per commit log
+    #[arg(
+        long = "max-commits",
+        value_name = "NUMBER",
+        default_value_t = 1000,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_commits: u32,
+    /// Don't read commit logs from, or write them to, the on-disk cache
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+    /// Ignore cached commit logs and fetch them again (the cache is still updated)
+    #[arg(long = "refresh", conflicts_with = "no_cache")]
+    pub refresh_cache: bool,
+    /// How long cached commit logs stay valid for (eg. "12h", "7d")
+    #[arg(
+        long = "cache-ttl",
+        value_name = "DURATION",
+        default_value = "7d",
+        value_parser = humantime::parse_duration
+    )]
+    pub cache_ttl: Duration,
+    /// Maximum number of attempts for each API request (1 turns off retries)
+    #[arg(
+        long = "max-attempts",
+        value_name = "NUMBER",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_attempts: u32,
+    /// Longest time to wait before retrying an API request; requests fail if the API asks to wait longer
+    #[arg(
+        long = "max-retry-wait",
+        value_name = "DURATION",
+        default_value = "60s",
+        value_parser = humantime::parse_duration
+    )]
+    pub max_retry_wait: Duration,
+}
+
+impl CommitLogArgs {
+    fn debug_info(&self) -> String {
+        format!(
+            r#"don't show commit logs:               {}
+max commits per log:                  {}
+don't use cache:                      {}
+refresh cache:                        {}
+cache TTL:                            {}
+max attempts per request:             {}
+max wait before retrying:             {}"#,
+            self.no_commit_logs,
+            self.max_commits,
+            self.no_cache,
+            self.refresh_cache,
+            humantime::format_duration(self.cache_ttl),
+            self.max_attempts,
+            humantime::format_duration(self.max_retry_wait),
+        )
+    }
+}
+
 impl std::fmt::Display for Args {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = match &self.command {
             EnveeCommand::Run {
-                versions_file_path,
+                versions_args,
                 only_validate_versions,
-                no_commit_logs,
+                interactive,
+                commit_log_args,
                 output_format,
                 app_filter,
+                status_filter,
+                fail_on,
+                sort_by,
+                history_path,
+                github_actions,
                 stdout_table_style,
                 stdout_plain_output,
                 html_output_path,
                 html_title,
                 html_template_path,
+                markdown_title,
             } => {
                 let flags_based_on_output = match output_format {
                     OutputFormat::Stdout => format!(
@@ -100,24 +462,191 @@ template path:                        {}
                                 .unwrap_or(NOT_PROVIDED.to_string())
                         )
                     }
+                    OutputFormat::Json => "".to_string(),
+                    OutputFormat::Markdown => format!(
+                        r#"
+title:                                {}
+"#,
+                        markdown_title
+                    ),
                 };
 
                 format!(
                     r#"
 command:                              Run
-versions file:                        {}
+{}
+{}
 only validate versions file:          {}
-don't show commit logs:               {}
+interactive:                          {}
+{}
 output format:                        {}
-app filter:                           {}{}
+app filter:                           {}
+status filter:                        {}
+fail on:                              {}
+sort by:                              {}
+history file:                         {}
+github actions:                       {}{}
 "#,
-                    versions_file_path.to_string_lossy(),
+                    project_config_debug_info(self.project_config.as_ref()),
+                    versions_args.debug_info(),
                     only_validate_versions,
-                    no_commit_logs,
-                    match output_format {
-                        OutputFormat::Stdout => "stdout",
-                        OutputFormat::Html => "html",
-                    },
+                    interactive,
+                    commit_log_args.debug_info(),
+                    output_format,
+                    app_filter.as_deref().unwrap_or(NOT_PROVIDED),
+                    join_or_not_provided(status_filter),
+                    join_or_not_provided(fail_on),
+                    sort_by,
+                    path_or_not_provided(history_path.as_ref()),
+                    github_actions,
+                    flags_based_on_output
+                )
+            }
+            EnveeCommand::Serve {
+                versions_args,
+                watch,
+                refresh_interval,
+                host,
+                port,
+                commit_log_args,
+                app_filter,
+                status_filter,
+                sort_by,
+                history_path,
+                html_title,
+                html_template_path,
+            } => format!(
+                r#"
+command:                              Serve
+{}
+watch versions file:                  {}
+refresh interval:                     {}
+address:                              {}
+{}
+app filter:                           {}
+status filter:                        {}
+sort by:                              {}
+history file:                         {}
+title:                                {}
+template path:                        {}
+"#,
+                versions_args.debug_info(),
+                watch,
+                humantime::format_duration(*refresh_interval),
+                SocketAddr::new(*host, *port),
+                commit_log_args.debug_info(),
+                app_filter.as_deref().unwrap_or(NOT_PROVIDED),
+                join_or_not_provided(status_filter),
+                sort_by,
+                path_or_not_provided(history_path.as_ref()),
+                html_title,
+                html_template_path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or(NOT_PROVIDED.to_string())
+            ),
+            EnveeCommand::Diff {
+                old_path,
+                new_path,
+                versions_format,
+                old_git_ref,
+                new_git_ref,
+                repo,
+                commit_log_args,
+                output_format,
+                app_filter,
+                stdout_table_style,
+                stdout_plain_output,
+                html_output_path,
+                html_title,
+                markdown_title,
+            } => {
+                let flags_based_on_output = match output_format {
+                    OutputFormat::Stdout => format!(
+                        r#"
+table style:                          {}
+plain output:                         {}
+"#,
+                        stdout_table_style, stdout_plain_output
+                    ),
+                    OutputFormat::Html => format!(
+                        r#"
+output path:                          {}
+title:                                {}
+"#,
+                        html_output_path.to_string_lossy(),
+                        html_title,
+                    ),
+                    OutputFormat::Json => "".to_string(),
+                    OutputFormat::Markdown => format!(
+                        r#"
+title:                                {}
+"#,
+                        markdown_title
+                    ),
+                };
+
+                format!(
+                    r#"
+command:                              Diff
+old versions file:                    {}
+new versions file:                    {}
+versions format:                      {}
+old git ref:                          {}
+new git ref:                          {}
+versions repo:                        {}
+{}
+output format:                        {}
+app filter:                           {}{}
+"#,
+                    old_path.to_string_lossy(),
+                    new_path.to_string_lossy(),
+                    versions_format
+                        .map(|f| f.to_string())
+                        .unwrap_or(NOT_PROVIDED.to_string()),
+                    old_git_ref.as_deref().unwrap_or(NOT_PROVIDED),
+                    new_git_ref.as_deref().unwrap_or(NOT_PROVIDED),
+                    repo.as_ref()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or(NOT_PROVIDED.to_string()),
+                    commit_log_args.debug_info(),
+                    output_format,
+                    app_filter.as_deref().unwrap_or(NOT_PROVIDED),
+                    flags_based_on_output
+                )
+            }
+            EnveeCommand::History {
+                history_path,
+                since,
+                output_format,
+                app_filter,
+                stdout_table_style,
+                stdout_plain_output,
+            } => {
+                let flags_based_on_output = match output_format {
+                    HistoryOutputFormat::Stdout => format!(
+                        r#"
+table style:                          {}
+plain output:                         {}
+"#,
+                        stdout_table_style, stdout_plain_output
+                    ),
+                    HistoryOutputFormat::Json => "".to_string(),
+                };
+
+                format!(
+                    r#"
+command:                              History
+history file:                         {}
+since:                                {}
+output format:                        {}
+app filter:                           {}{}
+"#,
+                    history_path.to_string_lossy(),
+                    since
+                        .map(|d| humantime::format_duration(d).to_string())
+                        .unwrap_or(NOT_PROVIDED.to_string()),
+                    output_format,
                     app_filter.as_deref().unwrap_or(NOT_PROVIDED),
                     flags_based_on_output
                 )
@@ -127,3 +656,29 @@ app filter:                           {}{}
         f.write_str(&output)
     }
 }
+
+fn join_or_not_provided<T: std::fmt::Display>(values: &[T]) -> String {
+    if values.is_empty() {
+        return NOT_PROVIDED.to_string();
+    }
+
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn project_config_debug_info(usage: Option<&ProjectConfigUsage>) -> String {
+    format!(
+        r#"config file:                          {}
+options from config file:             {}"#,
+        path_or_not_provided(usage.map(|u| &u.path)),
+        join_or_not_provided(usage.map(|u| &u.options[..]).unwrap_or_default()),
+    )
+}
+
+fn path_or_not_provided(path: Option<&PathBuf>) -> String {
+    path.map(|p| p.to_string_lossy().to_string())
+        .unwrap_or(NOT_PROVIDED.to_string())
+}
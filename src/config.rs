@@ -1,3 +1,4 @@
+use crate::domain::SyncStatus;
 use clap::ValueEnum;
 use std::path::PathBuf;
 
@@ -5,6 +6,8 @@ use std::path::PathBuf;
 pub enum OutputFormat {
     Stdout,
     Html,
+    Json,
+    Markdown,
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -12,6 +15,53 @@ impl std::fmt::Display for OutputFormat {
         match self {
             OutputFormat::Stdout => write!(f, "stdout"),
             OutputFormat::Html => write!(f, "html"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Markdown => write!(f, "markdown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HistoryOutputFormat {
+    Stdout,
+    Json,
+}
+
+impl std::fmt::Display for HistoryOutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryOutputFormat::Stdout => write!(f, "stdout"),
+            HistoryOutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VersionsFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl VersionsFormat {
+    pub const EXTENSIONS: [&str; 4] = ["toml", "yaml", "yml", "json"];
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for VersionsFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionsFormat::Toml => write!(f, "toml"),
+            VersionsFormat::Yaml => write!(f, "yaml"),
+            VersionsFormat::Json => write!(f, "json"),
         }
     }
 }
@@ -35,6 +85,103 @@ impl std::fmt::Display for TableStyle {
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SortBy {
+    App,
+    Status,
+}
+
+impl std::fmt::Display for SortBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortBy::App => write!(f, "app"),
+            SortBy::Status => write!(f, "status"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusFilter {
+    InSync,
+    Behind,
+    Ahead,
+    OutOfSync,
+    NotApplicable,
+}
+
+impl std::fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusFilter::InSync => write!(f, "in-sync"),
+            StatusFilter::Behind => write!(f, "behind"),
+            StatusFilter::Ahead => write!(f, "ahead"),
+            StatusFilter::OutOfSync => write!(f, "out-of-sync"),
+            StatusFilter::NotApplicable => write!(f, "not-applicable"),
+        }
+    }
+}
+
+impl From<StatusFilter> for SyncStatus {
+    fn from(filter: StatusFilter) -> Self {
+        match filter {
+            StatusFilter::InSync => SyncStatus::InSync,
+            StatusFilter::Behind => SyncStatus::Behind,
+            StatusFilter::Ahead => SyncStatus::Ahead,
+            StatusFilter::OutOfSync => SyncStatus::OutOfSync,
+            StatusFilter::NotApplicable => SyncStatus::NotApplicable,
+        }
+    }
+}
+
+// the order of variants decides which exit code is used when several conditions are met
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum FailOn {
+    Behind,
+    Ahead,
+    OutOfSync,
+    Missing,
+    FetchError,
+    Policy,
+}
+
+impl FailOn {
+    // 1 is used for all other errors, and 2 by clap for invalid usage
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FailOn::Behind => 3,
+            FailOn::Ahead => 4,
+            FailOn::OutOfSync => 5,
+            FailOn::Missing => 6,
+            FailOn::FetchError => 7,
+            FailOn::Policy => 8,
+        }
+    }
+
+    // out-of-sync is met by any app whose versions differ, whether they can be ordered or not;
+    // missing, fetch-error, and policy don't depend on sync statuses
+    pub fn is_met_by(&self, sync_status: &SyncStatus) -> bool {
+        match self {
+            FailOn::Behind => *sync_status == SyncStatus::Behind,
+            FailOn::Ahead => *sync_status == SyncStatus::Ahead,
+            FailOn::OutOfSync => sync_status.is_out_of_sync(),
+            FailOn::Missing | FailOn::FetchError | FailOn::Policy => false,
+        }
+    }
+}
+
+impl std::fmt::Display for FailOn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailOn::Behind => write!(f, "behind"),
+            FailOn::Ahead => write!(f, "ahead"),
+            FailOn::OutOfSync => write!(f, "out-of-sync"),
+            FailOn::Missing => write!(f, "missing"),
+            FailOn::FetchError => write!(f, "fetch-error"),
+            FailOn::Policy => write!(f, "policy"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct StdoutConfig {
     pub table_style: TableStyle,
@@ -48,10 +195,17 @@ pub struct HtmlConfig {
     pub template: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct MarkdownConfig {
+    pub title: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum OutputType {
     Stdout(StdoutConfig),
     Html(HtmlConfig),
+    Json,
+    Markdown(MarkdownConfig),
 }
 
 #[derive(Debug, Clone)]
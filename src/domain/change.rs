@@ -0,0 +1,55 @@
+use super::{App, DisplayName, Env, Version};
+
+// how an app's version in an env changed between two versions files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Upgraded,
+    Downgraded,
+    // at least one of the versions is not semver, so it's not known which way the version moved
+    Changed,
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Upgraded => write!(f, "upgraded"),
+            ChangeKind::Downgraded => write!(f, "downgraded"),
+            ChangeKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct VersionChange {
+    pub app: App,
+    pub display_name: Option<DisplayName>,
+    pub env: Env,
+    pub kind: ChangeKind,
+    // None when the app wasn't deployed to the env in the old versions file
+    pub old_version: Option<Version>,
+    // None when the app isn't deployed to the env in the new versions file
+    pub new_version: Option<Version>,
+}
+
+impl VersionChange {
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.app)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct VersionChanges {
+    // the envs of the new versions file, followed by the ones only the old one has
+    pub envs: Vec<Env>,
+    // sorted by app, and then by the order of envs
+    pub changes: Vec<VersionChange>,
+    // the number of (app, env) pairs whose version stayed the same
+    pub unchanged: usize,
+}
@@ -0,0 +1,116 @@
+use super::Env;
+
+pub const COMPARISON_STRATEGY_ADJACENT: &str = "adjacent";
+pub const COMPARISON_STRATEGY_FIRST_LAST: &str = "first-last";
+pub const COMPARISON_STRATEGY_EXPLICIT: &str = "explicit";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct EnvPair {
+    pub from: Env,
+    pub to: Env,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(rename_all = "snake_case"))]
+pub enum ComparisonStrategy {
+    #[default]
+    Adjacent,
+    FirstLast,
+    Explicit(Vec<EnvPair>),
+}
+
+impl ComparisonStrategy {
+    pub fn env_pairs(&self, envs: &[Env]) -> Vec<EnvPair> {
+        match self {
+            ComparisonStrategy::Adjacent => envs
+                .windows(2)
+                .rev()
+                .map(|w| EnvPair {
+                    from: w[1].clone(),
+                    to: w[0].clone(),
+                })
+                .collect(),
+            ComparisonStrategy::FirstLast => match (envs.first(), envs.last()) {
+                (Some(first), Some(last)) if envs.len() > 1 => vec![EnvPair {
+                    from: last.clone(),
+                    to: first.clone(),
+                }],
+                _ => vec![],
+            },
+            ComparisonStrategy::Explicit(pairs) => pairs.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envs() -> Vec<Env> {
+        vec!["dev".into(), "qa".into(), "staging".into(), "prod".into()]
+    }
+
+    fn to_strings(pairs: Vec<EnvPair>) -> Vec<String> {
+        pairs
+            .into_iter()
+            .map(|p| format!("{}..{}", p.from, p.to))
+            .collect()
+    }
+
+    #[test]
+    fn adjacent_strategy_returns_every_adjacent_pair() {
+        // GIVEN
+        // WHEN
+        let pairs = ComparisonStrategy::Adjacent.env_pairs(&envs());
+
+        // THEN
+        assert_eq!(
+            to_strings(pairs),
+            vec!["prod..staging", "staging..qa", "qa..dev"]
+        );
+    }
+
+    #[test]
+    fn first_last_strategy_returns_a_single_pair() {
+        // GIVEN
+        // WHEN
+        let pairs = ComparisonStrategy::FirstLast.env_pairs(&envs());
+
+        // THEN
+        assert_eq!(to_strings(pairs), vec!["prod..dev"]);
+    }
+
+    #[test]
+    fn explicit_strategy_returns_configured_pairs() {
+        // GIVEN
+        let strategy = ComparisonStrategy::Explicit(vec![
+            EnvPair {
+                from: "prod".into(),
+                to: "qa".into(),
+            },
+            EnvPair {
+                from: "staging".into(),
+                to: "dev".into(),
+            },
+        ]);
+
+        // WHEN
+        let pairs = strategy.env_pairs(&envs());
+
+        // THEN
+        assert_eq!(to_strings(pairs), vec!["prod..qa", "staging..dev"]);
+    }
+
+    #[test]
+    fn strategies_return_no_pairs_for_a_single_env() {
+        // GIVEN
+        let envs: Vec<Env> = vec!["dev".into()];
+
+        // WHEN
+        // THEN
+        assert!(ComparisonStrategy::Adjacent.env_pairs(&envs).is_empty());
+        assert!(ComparisonStrategy::FirstLast.env_pairs(&envs).is_empty());
+    }
+}
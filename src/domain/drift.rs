@@ -0,0 +1,226 @@
+use super::{Env, Version};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(rename_all = "snake_case"))]
+pub enum DriftAmount {
+    Major(u64),
+    Minor(u64),
+    Patch(u64),
+    PreRelease,
+}
+
+impl std::fmt::Display for DriftAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (count, unit) = match self {
+            DriftAmount::Major(n) => (*n, "major"),
+            DriftAmount::Minor(n) => (*n, "minor"),
+            DriftAmount::Patch(n) => (*n, "patch"),
+            DriftAmount::PreRelease => return write!(f, "a pre-release"),
+        };
+
+        match (count, unit) {
+            (1, _) => write!(f, "1 {unit}"),
+            (_, "patch") => write!(f, "{count} patches"),
+            _ => write!(f, "{count} {unit}s"),
+        }
+    }
+}
+
+impl DriftAmount {
+    // a drift in a more significant component always exceeds one in a less significant
+    // component, regardless of the counts (eg. 1 major exceeds 5 minors)
+    pub fn exceeds(&self, max: &DriftAmount) -> bool {
+        match self.rank().cmp(&max.rank()) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => self.count() > max.count(),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            DriftAmount::Major(_) => 3,
+            DriftAmount::Minor(_) => 2,
+            DriftAmount::Patch(_) => 1,
+            DriftAmount::PreRelease => 0,
+        }
+    }
+
+    fn count(&self) -> u64 {
+        match self {
+            DriftAmount::Major(n) | DriftAmount::Minor(n) | DriftAmount::Patch(n) => *n,
+            DriftAmount::PreRelease => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(rename_all = "snake_case"))]
+pub enum VersionDrift {
+    Behind(DriftAmount),
+    Ahead(DriftAmount),
+    // at least one of the versions is not semver, so they can't be ordered
+    Unordered,
+}
+
+impl VersionDrift {
+    pub fn between(version: &Version, reference: &Version) -> Option<Self> {
+        if version == reference {
+            return None;
+        }
+
+        let (Some(version), Some(reference)) = (version.as_semver(), reference.as_semver()) else {
+            return Some(VersionDrift::Unordered);
+        };
+
+        let amount = if version.major != reference.major {
+            DriftAmount::Major(version.major.abs_diff(reference.major))
+        } else if version.minor != reference.minor {
+            DriftAmount::Minor(version.minor.abs_diff(reference.minor))
+        } else if version.patch != reference.patch {
+            DriftAmount::Patch(version.patch.abs_diff(reference.patch))
+        } else {
+            DriftAmount::PreRelease
+        };
+
+        match version.cmp_precedence(&reference) {
+            Ordering::Less => Some(VersionDrift::Behind(amount)),
+            Ordering::Greater => Some(VersionDrift::Ahead(amount)),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct EnvDrift {
+    pub env: Env,
+    pub reference_env: Env,
+    pub drift: VersionDrift,
+}
+
+impl std::fmt::Display for EnvDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.drift {
+            VersionDrift::Behind(amount) => {
+                write!(f, "{} {} behind {}", self.env, amount, self.reference_env)
+            }
+            VersionDrift::Ahead(amount) => {
+                write!(f, "{} {} ahead of {}", self.env, amount, self.reference_env)
+            }
+            VersionDrift::Unordered => {
+                write!(f, "{} differs from {}", self.env, self.reference_env)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drift(version: &str, reference: &str) -> Option<VersionDrift> {
+        VersionDrift::between(&version.into(), &reference.into())
+    }
+
+    #[test]
+    fn equal_versions_have_no_drift() {
+        assert_eq!(drift("1.2.3", "1.2.3"), None);
+        assert_eq!(drift("v1.2.3", "1.2.3"), None);
+        assert_eq!(drift("1.2", "1.2.0"), None);
+        assert_eq!(drift("1.2.3+build.1", "1.2.3+build.2"), None);
+    }
+
+    #[test]
+    fn versions_behind_reference_are_detected() {
+        assert_eq!(
+            drift("1.9.0", "2.0.0"),
+            Some(VersionDrift::Behind(DriftAmount::Major(1)))
+        );
+        assert_eq!(
+            drift("1.0.0", "1.2.0"),
+            Some(VersionDrift::Behind(DriftAmount::Minor(2)))
+        );
+        assert_eq!(
+            drift("v1.2.0", "v1.2.5"),
+            Some(VersionDrift::Behind(DriftAmount::Patch(5)))
+        );
+        assert_eq!(
+            drift("1.2.0-rc.1", "1.2.0"),
+            Some(VersionDrift::Behind(DriftAmount::PreRelease))
+        );
+    }
+
+    #[test]
+    fn versions_ahead_of_reference_are_detected() {
+        assert_eq!(
+            drift("3.0.0", "1.0.0"),
+            Some(VersionDrift::Ahead(DriftAmount::Major(2)))
+        );
+        assert_eq!(
+            drift("1.2.1", "1.2.0"),
+            Some(VersionDrift::Ahead(DriftAmount::Patch(1)))
+        );
+        assert_eq!(
+            drift("1.2.0-rc.2", "1.2.0-rc.1"),
+            Some(VersionDrift::Ahead(DriftAmount::PreRelease))
+        );
+    }
+
+    #[test]
+    fn non_semver_versions_are_unordered() {
+        assert_eq!(drift("abc1234", "def5678"), Some(VersionDrift::Unordered));
+        assert_eq!(drift("1.0.0", "latest"), Some(VersionDrift::Unordered));
+        assert_eq!(drift("latest", "latest"), None);
+    }
+
+    #[test]
+    fn env_drifts_are_displayed_correctly() {
+        // GIVEN
+        let drifts = [
+            VersionDrift::Behind(DriftAmount::Minor(2)),
+            VersionDrift::Behind(DriftAmount::Patch(3)),
+            VersionDrift::Ahead(DriftAmount::Major(1)),
+            VersionDrift::Ahead(DriftAmount::PreRelease),
+            VersionDrift::Unordered,
+        ];
+
+        // WHEN
+        let result = drifts
+            .into_iter()
+            .map(|drift| {
+                EnvDrift {
+                    env: "prod".into(),
+                    reference_env: "dev".into(),
+                    drift,
+                }
+                .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        insta::assert_snapshot!(result, @r"
+        prod 2 minors behind dev
+        prod 3 patches behind dev
+        prod 1 major ahead of dev
+        prod a pre-release ahead of dev
+        prod differs from dev
+        ");
+    }
+
+    #[test]
+    fn drift_amounts_are_compared_against_maximums_correctly() {
+        use DriftAmount::*;
+
+        assert!(Minor(2).exceeds(&Minor(1)));
+        assert!(Major(1).exceeds(&Minor(5)));
+        assert!(Patch(1).exceeds(&Patch(0)));
+        assert!(!Minor(1).exceeds(&Minor(1)));
+        assert!(!Patch(9).exceeds(&Minor(1)));
+        assert!(!PreRelease.exceeds(&Patch(0)));
+    }
+}
@@ -0,0 +1,158 @@
+use super::{App, Env};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::time::Duration;
+
+pub const DEFAULT_ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const UNKNOWN_VERSION: &str = "unknown";
+const VERSION_CAPTURE_GROUP: &str = "version";
+
+// a URL a running service reports its version on, eg. "/actuator/info" or "/version"
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawEndpoint {
+    pub url: String,
+    pub json_pointer: Option<String>,
+    pub pattern: Option<String>,
+    pub timeout: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct Endpoint {
+    pub app: App,
+    pub env: Env,
+    pub url: String,
+    pub location: VersionLocation,
+    pub timeout: Duration,
+}
+
+// where the version is in an endpoint's response
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(rename_all = "snake_case"))]
+pub enum VersionLocation {
+    // an RFC 6901 JSON pointer into a JSON response, eg. "/build/version"
+    JsonPointer(String),
+    // the "version" capture group of a regex matched against the response
+    Pattern(VersionPattern),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct VersionPattern {
+    raw: String,
+    #[cfg_attr(test, serde(skip))]
+    regex: Regex,
+}
+
+impl VersionPattern {
+    pub fn find<'a>(&self, haystack: &'a str) -> Option<&'a str> {
+        self.regex
+            .captures(haystack)?
+            .name(VERSION_CAPTURE_GROUP)
+            .map(|m| m.as_str())
+    }
+}
+
+impl std::fmt::Display for VersionLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionLocation::JsonPointer(pointer) => write!(f, "json pointer \"{pointer}\""),
+            VersionLocation::Pattern(pattern) => write!(f, "pattern \"{}\"", pattern.raw),
+        }
+    }
+}
+
+impl RawEndpoint {
+    // returns all problems with the endpoint, so they can be reported together with the other
+    // errors in the versions file
+    pub(super) fn parse(
+        self,
+        app: App,
+        env: Env,
+        envs: &HashSet<Env>,
+    ) -> Result<Endpoint, Vec<String>> {
+        let mut errors = Vec::new();
+
+        if !envs.contains(&env) {
+            errors.push(format!(r#"env "{env}" is not in envs"#));
+        }
+
+        let url = self.url.trim().to_string();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            errors.push(format!(
+                r#"url "{url}" is invalid: needs to start with http:// or https://"#
+            ));
+        }
+
+        let location = match (self.json_pointer, self.pattern) {
+            (Some(_), Some(_)) => {
+                errors.push("only one of json_pointer and pattern can be set".to_string());
+                None
+            }
+            (None, None) => {
+                errors.push("one of json_pointer and pattern needs to be set".to_string());
+                None
+            }
+            (Some(pointer), None) => {
+                if pointer.is_empty() || pointer.starts_with('/') {
+                    Some(VersionLocation::JsonPointer(pointer))
+                } else {
+                    errors.push(format!(
+                        r#"json_pointer "{pointer}" is invalid: needs to start with "/""#
+                    ));
+                    None
+                }
+            }
+            (None, Some(pattern)) => match Regex::new(&pattern) {
+                Ok(regex)
+                    if regex
+                        .capture_names()
+                        .flatten()
+                        .any(|name| name == VERSION_CAPTURE_GROUP) =>
+                {
+                    Some(VersionLocation::Pattern(VersionPattern {
+                        raw: pattern,
+                        regex,
+                    }))
+                }
+                Ok(_) => {
+                    errors.push(format!(
+                        r#"pattern needs a capture group named "{VERSION_CAPTURE_GROUP}", eg. "(?P<{VERSION_CAPTURE_GROUP}>\d+\.\d+\.\d+)""#
+                    ));
+                    None
+                }
+                Err(e) => {
+                    errors.push(format!("pattern is not a valid regex: {e}"));
+                    None
+                }
+            },
+        };
+
+        let timeout = match self.timeout.as_deref().map(humantime::parse_duration) {
+            Some(Ok(timeout)) if timeout.is_zero() => {
+                errors.push("timeout needs to be greater than zero".to_string());
+                None
+            }
+            Some(Ok(timeout)) => Some(timeout),
+            Some(Err(e)) => {
+                errors.push(format!("timeout is invalid: {e}"));
+                None
+            }
+            None => Some(DEFAULT_ENDPOINT_TIMEOUT),
+        };
+
+        match (location, timeout) {
+            (Some(location), Some(timeout)) if errors.is_empty() => Ok(Endpoint {
+                app,
+                env,
+                url,
+                location,
+                timeout,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::SyncStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// bump this whenever a field is removed, renamed, or changes its meaning, so that older records can
+// be told apart
+pub const HISTORY_SCHEMA_VERSION: u32 = 1;
+
+// the results of a run, as stored in the history file (one record per line)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub schema_version: u32,
+    pub recorded_at: DateTime<Utc>,
+    pub envs: Vec<String>,
+    pub apps: Vec<HistoryApp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryApp {
+    pub app: String,
+    pub versions: BTreeMap<String, String>,
+    pub sync_status: SyncStatus,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct HistoryReport {
+    // the number of recorded changes the report is based on; runs that didn't change anything
+    // aren't recorded
+    pub changes: usize,
+    pub first_change_at: Option<DateTime<Utc>>,
+    pub last_change_at: Option<DateTime<Utc>>,
+    pub apps: Vec<AppHistory>,
+    pub env_deployments: Vec<EnvDeployments>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct AppHistory {
+    pub app: String,
+    pub timelines: Vec<EnvTimeline>,
+    // the app's status in the latest run it was a part of
+    pub sync_status: SyncStatus,
+    // the first of the latest unbroken run of records the app was out of sync in
+    pub out_of_sync_since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct EnvTimeline {
+    pub env: String,
+    // oldest first; the first one is the version the app had when it was first seen in the env
+    pub deployments: Vec<Deployment>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct Deployment {
+    pub version: String,
+    // the first run the version was seen in; the deployment happened at some point between the
+    // previous run and this one
+    pub seen_at: DateTime<Utc>,
+    // whether this is the first version seen for the env, which was deployed at an unknown time
+    pub first_seen: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct EnvDeployments {
+    pub env: String,
+    // version changes seen across all apps, not counting the versions apps were first seen with
+    pub deployments: usize,
+    // None when the history doesn't span any time
+    pub per_week: Option<f64>,
+}
@@ -1,32 +1,45 @@
-use super::{App, Env, Version};
+use super::{App, DisplayName, Env, EnvPair, Version};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct CommitLog {
     pub app: App,
+    pub display_name: Option<DisplayName>,
     pub from_env: Env,
     pub to_env: Env,
     pub from_version: Version,
     pub to_version: Version,
     pub commits: Vec<Commit>,
     pub html_url: String,
+    pub total_commits: usize,
+    pub from_cache: bool,
 }
 
-#[derive(Debug, Deserialize)]
+impl CommitLog {
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.app)
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.commits.len() < self.total_commits
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Commit {
     pub sha: String,
     pub commit: CommitDetail,
     pub html_url: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CommitDetail {
     pub message: String,
     pub author: Author,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Author {
     pub name: String,
     pub date: DateTime<Utc>,
@@ -40,8 +53,14 @@ pub struct CommitLogResults {
 
 #[derive(Debug)]
 pub enum CommitLogFetchError {
-    App { app: App, error: anyhow::Error },
-    System { error: anyhow::Error },
+    App {
+        app: App,
+        pair: EnvPair,
+        error: anyhow::Error,
+    },
+    System {
+        error: anyhow::Error,
+    },
 }
 
 #[derive(Debug)]
@@ -54,8 +73,9 @@ impl CommitLogFetchErrors {
         Self { errors: Vec::new() }
     }
 
-    pub fn add_app_error(&mut self, app: App, error: anyhow::Error) {
-        self.errors.push(CommitLogFetchError::App { app, error });
+    pub fn add_app_error(&mut self, app: App, pair: EnvPair, error: anyhow::Error) {
+        self.errors
+            .push(CommitLogFetchError::App { app, pair, error });
     }
 
     pub fn add_system_error(&mut self, error: anyhow::Error) {
@@ -65,6 +85,10 @@ impl CommitLogFetchErrors {
     pub fn is_empty(&self) -> bool {
         self.errors.is_empty()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CommitLogFetchError> {
+        self.errors.iter()
+    }
 }
 
 impl std::fmt::Display for CommitLogFetchErrors {
@@ -72,8 +96,8 @@ impl std::fmt::Display for CommitLogFetchErrors {
         writeln!(f, "couldn't fetch commit logs for some apps:")?;
         for error in &self.errors {
             match error {
-                CommitLogFetchError::App { app, error } => {
-                    writeln!(f, " - {}: {}", app, error)?;
+                CommitLogFetchError::App { app, pair, error } => {
+                    writeln!(f, " - {} ({}..{}): {}", app, pair.from, pair.to, error)?;
                 }
                 CommitLogFetchError::System { error } => {
                     writeln!(f, " - system error: {}", error)?;
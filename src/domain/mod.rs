@@ -1,5 +1,21 @@
+mod change;
+mod comparison;
+mod drift;
+mod endpoint;
+mod history;
 mod log;
+mod policy;
+mod provider;
+mod source;
 mod version;
 
+pub use change::*;
+pub use comparison::*;
+pub use drift::*;
+pub use endpoint::*;
+pub use history::*;
 pub use log::*;
+pub use policy::*;
+pub use provider::*;
+pub use source::*;
 pub use version::*;
@@ -0,0 +1,288 @@
+use super::{App, DriftAmount, Env};
+use semver::VersionReq;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+pub const POLICY_RULE_MAX_BEHIND: &str = "max-behind";
+pub const POLICY_RULE_NO_AHEAD: &str = "no-ahead";
+pub const POLICY_RULE_PINNED: &str = "pinned";
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawPolicy {
+    pub rule: String,
+    pub apps: Option<Vec<String>>,
+    pub env: Option<String>,
+    pub reference_env: Option<String>,
+    pub max: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct Policy {
+    pub name: String,
+    // None means the policy applies to all apps
+    pub apps: Option<Vec<App>>,
+    pub rule: PolicyRule,
+}
+
+impl Policy {
+    pub fn applies_to(&self, app: &App) -> bool {
+        self.apps.as_ref().is_none_or(|apps| apps.contains(app))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(rename_all = "snake_case"))]
+pub enum PolicyRule {
+    // env can't be behind reference_env by more than max
+    MaxBehind {
+        env: Env,
+        reference_env: Env,
+        max: DriftAmount,
+    },
+    // no env can be ahead of the one before it (in the order of envs)
+    NoAhead,
+    // the version in env needs to satisfy a semver requirement
+    Pinned {
+        env: Env,
+        requirement: VersionRequirement,
+    },
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct VersionRequirement {
+    raw: String,
+    #[cfg_attr(test, serde(skip))]
+    req: VersionReq,
+}
+
+impl VersionRequirement {
+    pub fn matches(&self, version: &semver::Version) -> bool {
+        self.req.matches(version)
+    }
+}
+
+impl std::fmt::Display for VersionRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct PolicyViolation {
+    pub policy: String,
+    pub env: Env,
+    pub message: String,
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.policy, self.message)
+    }
+}
+
+impl RawPolicy {
+    // returns all problems with the policy, so they can be reported together with the other errors
+    // in the versions file
+    pub(super) fn parse(
+        self,
+        name: String,
+        envs: &HashSet<Env>,
+        version_apps: &HashSet<App>,
+    ) -> Result<Policy, Vec<String>> {
+        let mut errors = Vec::new();
+
+        let apps = self.apps.map(|raw_apps| {
+            raw_apps
+                .into_iter()
+                .filter_map(|raw_app| match App::try_from(raw_app) {
+                    Ok(app) if !version_apps.contains(&app) => {
+                        errors.push(format!(
+                            r#"app "{app}" is not present in any of the versions configured"#
+                        ));
+                        None
+                    }
+                    Ok(app) => Some(app),
+                    Err(e) => {
+                        errors.push(e.to_string());
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let rule = match self.rule.trim() {
+            POLICY_RULE_MAX_BEHIND => {
+                let env = parse_env("env", self.env, &self.rule, envs, &mut errors);
+                let reference_env = parse_env(
+                    "reference_env",
+                    self.reference_env,
+                    &self.rule,
+                    envs,
+                    &mut errors,
+                );
+                let max = match self.max {
+                    Some(max) => parse_drift_amount(&max).map_err(|e| errors.push(e)).ok(),
+                    None => {
+                        errors.push(format!(r#"max is required for rule "{}""#, self.rule));
+                        None
+                    }
+                };
+                if self.version.is_some() {
+                    errors.push(format!(r#"version is not used by rule "{}""#, self.rule));
+                }
+
+                match (env, reference_env, max) {
+                    (Some(env), Some(reference_env), Some(max)) => Some(PolicyRule::MaxBehind {
+                        env,
+                        reference_env,
+                        max,
+                    }),
+                    _ => None,
+                }
+            }
+            POLICY_RULE_NO_AHEAD => {
+                for (key, is_set) in [
+                    ("env", self.env.is_some()),
+                    ("reference_env", self.reference_env.is_some()),
+                    ("max", self.max.is_some()),
+                    ("version", self.version.is_some()),
+                ] {
+                    if is_set {
+                        errors.push(format!(r#"{key} is not used by rule "{}""#, self.rule));
+                    }
+                }
+
+                Some(PolicyRule::NoAhead)
+            }
+            POLICY_RULE_PINNED => {
+                let env = parse_env("env", self.env, &self.rule, envs, &mut errors);
+                let requirement = match self.version {
+                    Some(version) => match VersionReq::parse(version.trim()) {
+                        Ok(req) => Some(VersionRequirement {
+                            raw: version.trim().to_string(),
+                            req,
+                        }),
+                        Err(e) => {
+                            errors.push(format!(
+                                r#"version "{version}" is not a valid semver requirement: {e}"#
+                            ));
+                            None
+                        }
+                    },
+                    None => {
+                        errors.push(format!(r#"version is required for rule "{}""#, self.rule));
+                        None
+                    }
+                };
+                for (key, is_set) in [
+                    ("reference_env", self.reference_env.is_some()),
+                    ("max", self.max.is_some()),
+                ] {
+                    if is_set {
+                        errors.push(format!(r#"{key} is not used by rule "{}""#, self.rule));
+                    }
+                }
+
+                match (env, requirement) {
+                    (Some(env), Some(requirement)) => Some(PolicyRule::Pinned { env, requirement }),
+                    _ => None,
+                }
+            }
+            other => {
+                errors.push(format!(
+                    r#"rule "{other}" is invalid, expected one of: {POLICY_RULE_MAX_BEHIND}, {POLICY_RULE_NO_AHEAD}, {POLICY_RULE_PINNED}"#
+                ));
+                None
+            }
+        };
+
+        match rule {
+            Some(rule) if errors.is_empty() => Ok(Policy { name, apps, rule }),
+            _ => Err(errors),
+        }
+    }
+}
+
+fn parse_env(
+    key: &str,
+    value: Option<String>,
+    rule: &str,
+    envs: &HashSet<Env>,
+    errors: &mut Vec<String>,
+) -> Option<Env> {
+    let Some(value) = value else {
+        errors.push(format!(r#"{key} is required for rule "{rule}""#));
+        return None;
+    };
+
+    match Env::try_from(value) {
+        Ok(env) if !envs.contains(&env) => {
+            errors.push(format!(r#"{key} "{env}" is not present in envs"#));
+            None
+        }
+        Ok(env) => Some(env),
+        Err(e) => {
+            errors.push(format!("{key}: {e}"));
+            None
+        }
+    }
+}
+
+// eg. "1 minor", "2 patches", "0 majors"
+fn parse_drift_amount(s: &str) -> Result<DriftAmount, String> {
+    let invalid = || {
+        format!(
+            r#"max "{s}" is invalid, expected a count followed by one of major, minor, patch (eg. "1 minor")"#
+        )
+    };
+
+    let mut parts = s.split_whitespace();
+    let (Some(count), Some(component), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let count = count.parse::<u64>().map_err(|_| invalid())?;
+
+    match component {
+        "major" | "majors" => Ok(DriftAmount::Major(count)),
+        "minor" | "minors" => Ok(DriftAmount::Minor(count)),
+        "patch" | "patches" => Ok(DriftAmount::Patch(count)),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_drift_amounts_works() {
+        assert_eq!(parse_drift_amount("1 minor"), Ok(DriftAmount::Minor(1)));
+        assert_eq!(parse_drift_amount("2 minors"), Ok(DriftAmount::Minor(2)));
+        assert_eq!(parse_drift_amount("0 major"), Ok(DriftAmount::Major(0)));
+        assert_eq!(
+            parse_drift_amount(" 3  patches "),
+            Ok(DriftAmount::Patch(3))
+        );
+    }
+
+    #[test]
+    fn parsing_invalid_drift_amounts_fails() {
+        for s in [
+            "",
+            "minor",
+            "1",
+            "one minor",
+            "-1 minor",
+            "1 build",
+            "1 minor 2 patches",
+        ] {
+            assert!(parse_drift_amount(s).is_err(), "input: {s:?}");
+        }
+    }
+}
@@ -0,0 +1,42 @@
+pub const PROVIDER_GITHUB: &str = "github";
+pub const PROVIDER_GITLAB: &str = "gitlab";
+pub const PROVIDER_LOCAL: &str = "local";
+
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+pub const DEFAULT_GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(rename_all = "snake_case"))]
+pub enum Provider {
+    #[default]
+    Github,
+    Gitlab,
+    // a git repository cloned locally
+    Local,
+}
+
+impl TryFrom<String> for Provider {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.trim() {
+            PROVIDER_GITHUB => Ok(Self::Github),
+            PROVIDER_GITLAB => Ok(Self::Gitlab),
+            PROVIDER_LOCAL => Ok(Self::Local),
+            other => Err(format!(
+                r#"provider "{other}" is invalid, expected one of: {PROVIDER_GITHUB}, {PROVIDER_GITLAB}, {PROVIDER_LOCAL}"#
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Provider::Github => write!(f, "GitHub"),
+            Provider::Gitlab => write!(f, "GitLab"),
+            Provider::Local => write!(f, "local git repositories"),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+// places versions are read from, in addition to the [[versions]] entries in the versions file
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawSources {
+    pub kubernetes: Option<RawKubernetesSource>,
+    pub extractors: Option<Vec<RawExtractor>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawKubernetesSource {
+    // env -> directory holding the manifests deployed to it
+    pub manifests: BTreeMap<String, String>,
+    // matched against container images (without their tag) to decide which app they belong to
+    pub image_pattern: Option<String>,
+}
+
+// reads versions out of arbitrary YAML/JSON files, such as Argo CD Applications or Helm values
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawExtractor {
+    // fills in the settings below for well known file types; explicitly set ones take precedence
+    pub preset: Option<String>,
+    // glob for the files to read, with "{{env}}" standing in for each env
+    pub files: String,
+    // only documents of this kind are read
+    pub kind: Option<String>,
+    // key path to the app's name in each document
+    pub app_path: Option<String>,
+    // regex matched against each file's path, whose "app" capture group is the app's name
+    pub app_pattern: Option<String>,
+    // key path to the version in each document
+    pub version_path: Option<String>,
+}
@@ -1,6 +1,14 @@
+use super::{
+    COMPARISON_STRATEGY_ADJACENT, COMPARISON_STRATEGY_EXPLICIT, COMPARISON_STRATEGY_FIRST_LAST,
+    ComparisonStrategy, DEFAULT_GITHUB_API_URL, DEFAULT_GITLAB_API_URL, Endpoint, EnvDrift,
+    EnvPair, PROVIDER_LOCAL, Policy, PolicyViolation, Provider, RawEndpoint, RawPolicy, RawSources,
+    UNKNOWN_VERSION,
+};
+use chrono::{DateTime, Utc};
 use derive_more::{Deref, Display};
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 
 macro_rules! define_validated_string_newtype {
     ($name:ident, $error_msg:literal) => {
@@ -35,8 +43,63 @@ macro_rules! define_validated_string_newtype {
 pub struct RawVersions {
     pub envs: Vec<String>,
     pub github_org: String,
+    // can be left out if versions come from sources instead
+    #[serde(default)]
     pub versions: Vec<RawAppVersion>,
     pub git_tag_transform: Option<String>,
+    pub comparison_strategy: Option<String>,
+    pub comparison_pairs: Option<Vec<RawEnvPair>>,
+    pub provider: Option<String>,
+    pub github_api_url: Option<String>,
+    pub gitlab_api_url: Option<String>,
+    pub local_repos_dir: Option<String>,
+    pub apps: Option<BTreeMap<String, RawAppConfig>>,
+    pub policies: Option<BTreeMap<String, RawPolicy>>,
+    pub sources: Option<RawSources>,
+    // app -> env -> endpoint the version is fetched from
+    pub endpoints: Option<BTreeMap<String, BTreeMap<String, RawEndpoint>>>,
+}
+
+// one of several files that are merged into a single RawVersions; every setting is optional here,
+// since it only needs to be present in one of the files
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawVersionsFile {
+    pub envs: Option<Vec<String>>,
+    pub github_org: Option<String>,
+    #[serde(default)]
+    pub versions: Vec<RawAppVersion>,
+    pub git_tag_transform: Option<String>,
+    pub comparison_strategy: Option<String>,
+    pub comparison_pairs: Option<Vec<RawEnvPair>>,
+    pub provider: Option<String>,
+    pub github_api_url: Option<String>,
+    pub gitlab_api_url: Option<String>,
+    pub local_repos_dir: Option<String>,
+    pub apps: Option<BTreeMap<String, RawAppConfig>>,
+    pub policies: Option<BTreeMap<String, RawPolicy>>,
+    pub sources: Option<RawSources>,
+    // app -> env -> endpoint the version is fetched from
+    pub endpoints: Option<BTreeMap<String, BTreeMap<String, RawEndpoint>>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawAppConfig {
+    pub repo: Option<String>,
+    pub owner: Option<String>,
+    pub tag_transform: Option<String>,
+    pub display_name: Option<String>,
+    pub provider: Option<String>,
+    pub github_api_url: Option<String>,
+    pub gitlab_api_url: Option<String>,
+    pub local_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RawEnvPair {
+    pub from: String,
+    pub to: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -45,12 +108,44 @@ pub struct RawAppVersion {
     pub app: String,
     pub env: String,
     pub version: String,
+    // the file the version was read from, when versions are read from more than one file
+    #[serde(skip)]
+    pub source: Option<String>,
 }
 
 define_validated_string_newtype!(App, "app is empty");
 define_validated_string_newtype!(Env, "env is empty");
 define_validated_string_newtype!(Version, "version is empty");
 define_validated_string_newtype!(GithubOrg, "github_org is empty");
+define_validated_string_newtype!(Repo, "repo is empty");
+define_validated_string_newtype!(DisplayName, "display_name is empty");
+
+impl From<&App> for Repo {
+    fn from(app: &App) -> Self {
+        Self(app.to_string())
+    }
+}
+
+impl Version {
+    // versions are parsed leniently: a leading "v" is ignored, and missing minor/patch
+    // components are treated as 0, eg. "v1.2" is parsed as "1.2.0"
+    pub fn as_semver(&self) -> Option<semver::Version> {
+        let trimmed = self.0.strip_prefix(['v', 'V']).unwrap_or(&self.0);
+        if let Ok(v) = semver::Version::parse(trimmed) {
+            return Some(v);
+        }
+
+        let core_end = trimmed.find(['-', '+']).unwrap_or(trimmed.len());
+        let (core, rest) = trimmed.split_at(core_end);
+        let padded = match core.split('.').count() {
+            1 => format!("{core}.0.0{rest}"),
+            2 => format!("{core}.0{rest}"),
+            _ => return None,
+        };
+
+        semver::Version::parse(&padded).ok()
+    }
+}
 
 #[derive(Debug, Clone, Deref, Display)]
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -69,6 +164,39 @@ impl TryFrom<String> for GitTagTransform {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deref, Display)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct ApiUrl(String);
+
+impl ApiUrl {
+    // key is the name of the setting the URL comes from, it's only used in error messages
+    pub fn parse(key: &str, s: String) -> Result<Self, String> {
+        let trimmed = s.trim().trim_end_matches('/');
+        if trimmed.is_empty() {
+            Err(format!("{key} is empty"))
+        } else if !(trimmed.starts_with("https://") || trimmed.starts_with("http://")) {
+            Err(format!(
+                r#"{key} "{trimmed}" needs to start with "https://" or "http://""#
+            ))
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct AppConfig {
+    pub repo: Option<Repo>,
+    pub owner: Option<GithubOrg>,
+    pub tag_transform: Option<GitTagTransform>,
+    pub display_name: Option<DisplayName>,
+    pub provider: Option<Provider>,
+    pub github_api_url: Option<ApiUrl>,
+    pub gitlab_api_url: Option<ApiUrl>,
+    pub local_path: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct Versions {
@@ -76,6 +204,97 @@ pub struct Versions {
     pub github_org: GithubOrg,
     pub versions: Vec<AppVersion>,
     pub git_tag_transform: Option<GitTagTransform>,
+    pub comparison_strategy: ComparisonStrategy,
+    pub provider: Provider,
+    pub github_api_url: ApiUrl,
+    pub gitlab_api_url: ApiUrl,
+    pub local_repos_dir: Option<PathBuf>,
+    pub app_configs: HashMap<App, AppConfig>,
+    pub policies: Vec<Policy>,
+    // versions that are only known once these are fetched
+    pub endpoints: Vec<Endpoint>,
+}
+
+// app level settings take precedence over the top level ones
+impl Versions {
+    pub fn provider_for(&self, app: &App) -> Provider {
+        self.app_configs
+            .get(app)
+            .and_then(|config| config.provider)
+            .unwrap_or(self.provider)
+    }
+
+    // returns the providers used by at least one app, in a stable order
+    pub fn providers_in_use(&self) -> Vec<Provider> {
+        let mut providers: Vec<_> = self
+            .versions
+            .iter()
+            .map(|v| self.provider_for(&v.app))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        providers.sort();
+        providers
+    }
+
+    // returns None for apps that use local repositories
+    pub fn api_url_for(&self, app: &App) -> Option<&ApiUrl> {
+        let config = self.app_configs.get(app);
+        match self.provider_for(app) {
+            Provider::Github => Some(
+                config
+                    .and_then(|c| c.github_api_url.as_ref())
+                    .unwrap_or(&self.github_api_url),
+            ),
+            Provider::Gitlab => Some(
+                config
+                    .and_then(|c| c.gitlab_api_url.as_ref())
+                    .unwrap_or(&self.gitlab_api_url),
+            ),
+            Provider::Local => None,
+        }
+    }
+
+    // an app level path points to the repository itself, whereas local_repos_dir contains one
+    // directory per repository
+    pub fn local_repo_path_for(&self, app: &App) -> Option<PathBuf> {
+        self.app_configs
+            .get(app)
+            .and_then(|config| config.local_path.clone())
+            .or_else(|| {
+                self.local_repos_dir
+                    .as_ref()
+                    .map(|dir| dir.join(self.repo_for(app).as_str()))
+            })
+    }
+
+    pub fn github_org_for(&self, app: &App) -> &GithubOrg {
+        self.app_configs
+            .get(app)
+            .and_then(|config| config.owner.as_ref())
+            .unwrap_or(&self.github_org)
+    }
+
+    // the app name doubles as the repo name unless configured otherwise
+    pub fn repo_for(&self, app: &App) -> Repo {
+        self.app_configs
+            .get(app)
+            .and_then(|config| config.repo.clone())
+            .unwrap_or_else(|| Repo::from(app))
+    }
+
+    pub fn git_tag_transform_for(&self, app: &App) -> Option<&GitTagTransform> {
+        self.app_configs
+            .get(app)
+            .and_then(|config| config.tag_transform.as_ref())
+            .or(self.git_tag_transform.as_ref())
+    }
+
+    pub fn display_name_for(&self, app: &App) -> Option<&DisplayName> {
+        self.app_configs
+            .get(app)
+            .and_then(|config| config.display_name.as_ref())
+    }
 }
 
 #[derive(Debug)]
@@ -153,10 +372,29 @@ impl TryFrom<RawVersions> for Versions {
         }
 
         let mut version_envs = HashSet::new();
+        let mut version_apps = HashSet::new();
         let mut versions = Vec::new();
+        // (app, env) -> where the version was first seen, to catch the same app being listed more
+        // than once for an env
+        let mut seen_versions: HashMap<(App, Env), String> = HashMap::new();
         for (i, raw_version) in raw.versions.into_iter().enumerate() {
+            let location = match &raw_version.source {
+                Some(source) => format!(r#""{source}""#),
+                None => format!("version #{i}"),
+            };
             match AppVersion::try_from(raw_version) {
                 Ok(app_version) => {
+                    let key = (app_version.app.clone(), app_version.env.clone());
+                    if let Some(first_location) = seen_versions.get(&key) {
+                        errors.add_top_level_error(format!(
+                            r#"app "{}" has more than one version for env "{}" (in {} and {})"#,
+                            app_version.app, app_version.env, first_location, location
+                        ));
+                        continue;
+                    }
+                    seen_versions.insert(key, location);
+
+                    version_apps.insert(app_version.app.clone());
                     if envs_set.contains(&app_version.env) {
                         version_envs.insert(app_version.env.clone());
                         versions.push(app_version);
@@ -166,6 +404,17 @@ impl TryFrom<RawVersions> for Versions {
             }
         }
 
+        let endpoints = parse_endpoints(
+            raw.endpoints.unwrap_or_default(),
+            &envs_set,
+            &seen_versions,
+            &mut errors,
+        );
+        for endpoint in &endpoints {
+            version_apps.insert(endpoint.app.clone());
+            version_envs.insert(endpoint.env.clone());
+        }
+
         for env in &envs {
             if !version_envs.contains(env) {
                 errors.add_top_level_error(format!(
@@ -193,18 +442,372 @@ impl TryFrom<RawVersions> for Versions {
             None => None,
         };
 
+        let comparison_strategy = parse_comparison_strategy(
+            raw.comparison_strategy,
+            raw.comparison_pairs,
+            &envs_set,
+            &mut errors,
+        );
+
+        let provider = match raw.provider.map(Provider::try_from) {
+            Some(Ok(provider)) => provider,
+            Some(Err(e)) => {
+                errors.add_top_level_error(e);
+                Provider::default()
+            }
+            None => Provider::default(),
+        };
+
+        let github_api_url = parse_top_level_api_url(
+            "github_api_url",
+            raw.github_api_url,
+            DEFAULT_GITHUB_API_URL,
+            &mut errors,
+        );
+        let gitlab_api_url = parse_top_level_api_url(
+            "gitlab_api_url",
+            raw.gitlab_api_url,
+            DEFAULT_GITLAB_API_URL,
+            &mut errors,
+        );
+
+        let local_repos_dir = match raw.local_repos_dir {
+            Some(dir) if dir.trim().is_empty() => {
+                errors.add_top_level_error("local_repos_dir is empty");
+                None
+            }
+            Some(dir) => Some(PathBuf::from(dir.trim())),
+            None => None,
+        };
+
+        let app_configs =
+            parse_app_configs(raw.apps.unwrap_or_default(), &version_apps, &mut errors);
+
+        let policies = parse_policies(
+            raw.policies.unwrap_or_default(),
+            &envs_set,
+            &version_apps,
+            &mut errors,
+        );
+
+        if local_repos_dir.is_none() {
+            let mut apps_without_path: Vec<_> = version_apps
+                .iter()
+                .filter(|app| {
+                    let config = app_configs.get(*app);
+                    config.and_then(|c| c.provider).unwrap_or(provider) == Provider::Local
+                        && config.and_then(|c| c.local_path.as_ref()).is_none()
+                })
+                .collect();
+            apps_without_path.sort_by_key(|app| app.to_string());
+
+            for app in apps_without_path {
+                errors.add_top_level_error(format!(
+                    r#"app "{app}" uses the "{PROVIDER_LOCAL}" provider, but neither local_repos_dir nor apps.{app}.local_path is set"#
+                ));
+            }
+        }
+
         match maybe_github_org {
             Some(github_org) if errors.is_empty() => Ok(Self {
                 envs,
                 github_org,
                 versions,
                 git_tag_transform,
+                comparison_strategy,
+                provider,
+                github_api_url,
+                gitlab_api_url,
+                local_repos_dir,
+                app_configs,
+                policies,
+                endpoints,
             }),
             _ => Err(errors),
         }
     }
 }
 
+fn parse_endpoints(
+    raw_endpoints: BTreeMap<String, BTreeMap<String, RawEndpoint>>,
+    envs_set: &HashSet<Env>,
+    seen_versions: &HashMap<(App, Env), String>,
+    errors: &mut VersionsValidationErrors,
+) -> Vec<Endpoint> {
+    let mut endpoints = Vec::new();
+
+    for (raw_app, raw_env_endpoints) in raw_endpoints {
+        let Ok(app) = App::try_from(raw_app) else {
+            errors.add_top_level_error("endpoints: app name is empty");
+            continue;
+        };
+
+        for (raw_env, raw_endpoint) in raw_env_endpoints {
+            let Ok(env) = Env::try_from(raw_env) else {
+                errors.add_top_level_error(format!("endpoints.{app}: env name is empty"));
+                continue;
+            };
+
+            if let Some(location) = seen_versions.get(&(app.clone(), env.clone())) {
+                errors.add_top_level_error(format!(
+                    r#"app "{app}" has both a version (in {location}) and an endpoint for env "{env}""#
+                ));
+                continue;
+            }
+
+            match raw_endpoint.parse(app.clone(), env.clone(), envs_set) {
+                Ok(endpoint) => endpoints.push(endpoint),
+                Err(endpoint_errors) => {
+                    for e in endpoint_errors {
+                        errors.add_top_level_error(format!("endpoints.{app}.{env}: {e}"));
+                    }
+                }
+            }
+        }
+    }
+
+    endpoints
+}
+
+fn parse_policies(
+    raw_policies: BTreeMap<String, RawPolicy>,
+    envs_set: &HashSet<Env>,
+    version_apps: &HashSet<App>,
+    errors: &mut VersionsValidationErrors,
+) -> Vec<Policy> {
+    let mut policies = Vec::new();
+
+    for (name, raw_policy) in raw_policies {
+        if name.trim().is_empty() {
+            errors.add_top_level_error("policies: policy name is empty");
+            continue;
+        }
+
+        match raw_policy.parse(name.clone(), envs_set, version_apps) {
+            Ok(policy) => policies.push(policy),
+            Err(policy_errors) => {
+                for e in policy_errors {
+                    errors.add_top_level_error(format!("policies.{name}: {e}"));
+                }
+            }
+        }
+    }
+
+    policies
+}
+
+fn parse_comparison_strategy(
+    raw_strategy: Option<String>,
+    raw_pairs: Option<Vec<RawEnvPair>>,
+    envs_set: &HashSet<Env>,
+    errors: &mut VersionsValidationErrors,
+) -> ComparisonStrategy {
+    let strategy = raw_strategy.as_deref().map(str::trim);
+
+    match (strategy, raw_pairs) {
+        (None | Some(COMPARISON_STRATEGY_EXPLICIT), Some(raw_pairs)) => {
+            if raw_pairs.is_empty() {
+                errors.add_top_level_error("comparison_pairs is empty");
+            }
+
+            let mut pairs = Vec::new();
+            for (i, raw_pair) in raw_pairs.into_iter().enumerate() {
+                let from = Env::try_from(raw_pair.from);
+                let to = Env::try_from(raw_pair.to);
+
+                let mut pair_is_valid = true;
+                for env in [&from, &to] {
+                    match env {
+                        Ok(env) if !envs_set.contains(env) => {
+                            errors.add_top_level_error(format!(
+                                r#"comparison_pairs[{i}]: env "{env}" is not present in envs"#
+                            ));
+                            pair_is_valid = false;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            errors.add_top_level_error(format!("comparison_pairs[{i}]: {e}"));
+                            pair_is_valid = false;
+                        }
+                    }
+                }
+
+                if let (Ok(from), Ok(to)) = (from, to)
+                    && pair_is_valid
+                {
+                    if from == to {
+                        errors.add_top_level_error(format!(
+                            r#"comparison_pairs[{i}]: env "{from}" is compared against itself"#
+                        ));
+                    } else {
+                        pairs.push(EnvPair { from, to });
+                    }
+                }
+            }
+
+            ComparisonStrategy::Explicit(pairs)
+        }
+        (Some(COMPARISON_STRATEGY_EXPLICIT), None) => {
+            errors.add_top_level_error(format!(
+                r#"comparison_pairs needs to be provided when comparison_strategy is "{COMPARISON_STRATEGY_EXPLICIT}""#
+            ));
+            ComparisonStrategy::default()
+        }
+        (Some(s), Some(_)) => {
+            errors.add_top_level_error(format!(
+                r#"comparison_pairs cannot be used with comparison_strategy "{s}""#
+            ));
+            ComparisonStrategy::default()
+        }
+        (None | Some(COMPARISON_STRATEGY_ADJACENT), None) => ComparisonStrategy::Adjacent,
+        (Some(COMPARISON_STRATEGY_FIRST_LAST), None) => ComparisonStrategy::FirstLast,
+        (Some(s), None) => {
+            errors.add_top_level_error(format!(
+                r#"comparison_strategy "{s}" is invalid, expected one of: {COMPARISON_STRATEGY_ADJACENT}, {COMPARISON_STRATEGY_FIRST_LAST}, {COMPARISON_STRATEGY_EXPLICIT}"#
+            ));
+            ComparisonStrategy::default()
+        }
+    }
+}
+
+fn parse_top_level_api_url(
+    key: &str,
+    raw_url: Option<String>,
+    default_url: &str,
+    errors: &mut VersionsValidationErrors,
+) -> ApiUrl {
+    match raw_url.map(|url| ApiUrl::parse(key, url)) {
+        Some(Ok(url)) => url,
+        Some(Err(e)) => {
+            errors.add_top_level_error(e);
+            ApiUrl(default_url.to_string())
+        }
+        None => ApiUrl(default_url.to_string()),
+    }
+}
+
+fn parse_app_configs(
+    raw_configs: BTreeMap<String, RawAppConfig>,
+    version_apps: &HashSet<App>,
+    errors: &mut VersionsValidationErrors,
+) -> HashMap<App, AppConfig> {
+    let mut app_configs = HashMap::new();
+    let mut display_names: HashMap<DisplayName, String> = HashMap::new();
+
+    for (raw_app, raw_config) in raw_configs {
+        let app = match App::try_from(raw_app.clone()) {
+            Ok(app) if !version_apps.contains(&app) => {
+                errors.add_top_level_error(format!(
+                    r#"apps.{raw_app}: app "{app}" is not present in any of the versions configured"#
+                ));
+                None
+            }
+            Ok(app) => Some(app),
+            Err(e) => {
+                errors.add_top_level_error(format!("apps.{raw_app}: {e}"));
+                None
+            }
+        };
+
+        let mut add_error = |e: &str| errors.add_top_level_error(format!("apps.{raw_app}: {e}"));
+
+        let repo = match raw_config.repo.map(Repo::try_from) {
+            Some(Ok(repo)) => Some(repo),
+            Some(Err(e)) => {
+                add_error(e);
+                None
+            }
+            None => None,
+        };
+
+        let owner = match raw_config.owner.map(GithubOrg::try_from) {
+            Some(Ok(owner)) => Some(owner),
+            Some(Err(_)) => {
+                add_error("owner is empty");
+                None
+            }
+            None => None,
+        };
+
+        let tag_transform = match raw_config.tag_transform.map(GitTagTransform::try_from) {
+            Some(Ok(transform)) => Some(transform),
+            Some(Err(_)) => {
+                add_error(r#"tag_transform doesn't include the placeholder "{{version}}""#);
+                None
+            }
+            None => None,
+        };
+
+        let display_name = match raw_config.display_name.map(DisplayName::try_from) {
+            Some(Ok(name)) => match display_names.get(&name) {
+                Some(other_app) => {
+                    add_error(&format!(
+                        r#"display_name "{name}" is already used by app "{other_app}""#
+                    ));
+                    None
+                }
+                None => {
+                    display_names.insert(name.clone(), raw_app.clone());
+                    Some(name)
+                }
+            },
+            Some(Err(e)) => {
+                add_error(e);
+                None
+            }
+            None => None,
+        };
+
+        let provider = match raw_config.provider.map(Provider::try_from) {
+            Some(Ok(provider)) => Some(provider),
+            Some(Err(e)) => {
+                add_error(&e);
+                None
+            }
+            None => None,
+        };
+
+        let mut parse_api_url =
+            |key: &str, raw_url: Option<String>| match raw_url.map(|url| ApiUrl::parse(key, url)) {
+                Some(Ok(url)) => Some(url),
+                Some(Err(e)) => {
+                    add_error(&e);
+                    None
+                }
+                None => None,
+            };
+        let github_api_url = parse_api_url("github_api_url", raw_config.github_api_url);
+        let gitlab_api_url = parse_api_url("gitlab_api_url", raw_config.gitlab_api_url);
+
+        let local_path = match raw_config.local_path {
+            Some(path) if path.trim().is_empty() => {
+                add_error("local_path is empty");
+                None
+            }
+            Some(path) => Some(PathBuf::from(path.trim())),
+            None => None,
+        };
+
+        if let Some(app) = app {
+            app_configs.insert(
+                app,
+                AppConfig {
+                    repo,
+                    owner,
+                    tag_transform,
+                    display_name,
+                    provider,
+                    github_api_url,
+                    gitlab_api_url,
+                    local_path,
+                },
+            );
+        }
+    }
+
+    app_configs
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct AppVersion {
@@ -276,27 +879,64 @@ impl TryFrom<RawAppVersion> for AppVersion {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SyncStatus {
     InSync,
+    Behind,
+    Ahead,
     OutOfSync,
     NotApplicable,
 }
 
+impl SyncStatus {
+    pub fn is_out_of_sync(&self) -> bool {
+        matches!(
+            self,
+            SyncStatus::Behind | SyncStatus::Ahead | SyncStatus::OutOfSync
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct DiffResult {
     pub envs: Vec<Env>,
     pub app_results: Vec<AppResult>,
+    // whether any policies were checked, so views can tell "no violations" from "no policies"
+    pub policies_checked: bool,
+    // whether the history of runs was looked at, so views can tell "in sync" from "no history"
+    pub history_used: bool,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct AppResult {
     pub app: App,
+    pub display_name: Option<DisplayName>,
     pub values: HashMap<Env, Version>,
     pub sync_status: SyncStatus,
+    pub drifts: Vec<EnvDrift>,
+    pub policy_violations: Vec<PolicyViolation>,
+    // envs whose version couldn't be fetched, which are left out of comparisons
+    pub unknown_envs: Vec<Env>,
+    // when the app was first seen to be out of sync, going by the history of runs
+    pub out_of_sync_since: Option<DateTime<Utc>>,
+}
+
+impl AppResult {
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.app)
+    }
+
+    // what to show for the app's version in an env
+    pub fn version_text(&self, env: &Env) -> &str {
+        match self.values.get(env) {
+            Some(version) => version,
+            None if self.unknown_envs.contains(env) => UNKNOWN_VERSION,
+            None => "",
+        }
+    }
 }
 
 #[cfg(test)]
@@ -315,16 +955,28 @@ mod tests {
             envs: vec!["dev".to_string(), "prod".to_string()],
             github_org: "my-org".to_string(),
             git_tag_transform: Some("v{{version}}".to_string()),
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
             versions: vec![
                 RawAppVersion {
                     app: "app-a".to_string(),
                     env: "dev".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "app-a".to_string(),
                     env: "prod".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
             ],
         };
@@ -346,6 +998,14 @@ mod tests {
             env: prod
             version: 1.0.0
         git_tag_transform: "v{{version}}"
+        comparison_strategy: adjacent
+        provider: github
+        github_api_url: "https://api.github.com"
+        gitlab_api_url: "https://gitlab.com/api/v4"
+        local_repos_dir: ~
+        app_configs: {}
+        policies: []
+        endpoints: []
         "#);
     }
 
@@ -356,21 +1016,34 @@ mod tests {
             envs: vec!["dev".to_string(), "prod".to_string()],
             github_org: "my-org".to_string(),
             git_tag_transform: Some("v{{version}}".to_string()),
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
             versions: vec![
                 RawAppVersion {
                     app: "app-a".to_string(),
                     env: "dev".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "app-a".to_string(),
                     env: "prod".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "app-a".to_string(),
                     env: "not-in-list".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
             ],
         };
@@ -392,9 +1065,401 @@ mod tests {
             env: prod
             version: 1.0.0
         git_tag_transform: "v{{version}}"
+        comparison_strategy: adjacent
+        provider: github
+        github_api_url: "https://api.github.com"
+        gitlab_api_url: "https://gitlab.com/api/v4"
+        local_repos_dir: ~
+        app_configs: {}
+        policies: []
+        endpoints: []
         "#);
     }
 
+    #[test]
+    fn parsing_versions_with_explicit_comparison_pairs_works() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "qa".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: Some("explicit".to_string()),
+            comparison_pairs: Some(vec![
+                RawEnvPair {
+                    from: "prod".to_string(),
+                    to: "qa".to_string(),
+                },
+                RawEnvPair {
+                    from: "prod".to_string(),
+                    to: "dev".to_string(),
+                },
+            ]),
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.1.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "qa".to_string(),
+                    version: "1.1.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let versions = Versions::try_from(raw).expect("result should've been Ok");
+
+        // THEN
+        assert_yaml_snapshot!(versions.comparison_strategy, @r"
+        explicit:
+          - from: prod
+            to: qa
+          - from: prod
+            to: dev
+        ");
+    }
+
+    #[test]
+    fn github_api_url_can_be_overridden_per_app() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: Some(" https://github.example.com/api/v3/ ".to_string()),
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: Some(BTreeMap::from([(
+                "app-b".to_string(),
+                RawAppConfig {
+                    repo: None,
+                    owner: None,
+                    tag_transform: None,
+                    display_name: None,
+                    provider: None,
+                    github_api_url: Some("https://api.github.com".to_string()),
+                    gitlab_api_url: None,
+                    local_path: None,
+                },
+            )])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-b".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let versions = Versions::try_from(raw).expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(
+            versions
+                .api_url_for(&"app-a".into())
+                .map(|url| url.as_str()),
+            Some("https://github.example.com/api/v3")
+        );
+        assert_eq!(
+            versions
+                .api_url_for(&"app-b".into())
+                .map(|url| url.as_str()),
+            Some("https://api.github.com")
+        );
+    }
+
+    #[test]
+    fn provider_can_be_overridden_per_app() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: Some("gitlab".to_string()),
+            github_api_url: None,
+            gitlab_api_url: Some("https://gitlab.example.com/api/v4".to_string()),
+            local_repos_dir: None,
+            apps: Some(BTreeMap::from([(
+                "app-b".to_string(),
+                RawAppConfig {
+                    repo: None,
+                    owner: None,
+                    tag_transform: None,
+                    display_name: None,
+                    provider: Some("github".to_string()),
+                    github_api_url: None,
+                    gitlab_api_url: None,
+                    local_path: None,
+                },
+            )])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-b".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let versions = Versions::try_from(raw).expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(versions.provider_for(&"app-a".into()), Provider::Gitlab);
+        assert_eq!(
+            versions
+                .api_url_for(&"app-a".into())
+                .map(|url| url.as_str()),
+            Some("https://gitlab.example.com/api/v4")
+        );
+        assert_eq!(versions.provider_for(&"app-b".into()), Provider::Github);
+        assert_eq!(
+            versions
+                .api_url_for(&"app-b".into())
+                .map(|url| url.as_str()),
+            Some(DEFAULT_GITHUB_API_URL)
+        );
+        assert_eq!(
+            versions.providers_in_use(),
+            vec![Provider::Github, Provider::Gitlab]
+        );
+    }
+
+    #[test]
+    fn local_repo_paths_are_resolved_correctly() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: Some("local".to_string()),
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: Some("/home/user/repos".to_string()),
+            apps: Some(BTreeMap::from([
+                (
+                    "app-b".to_string(),
+                    RawAppConfig {
+                        repo: Some("repo-b".to_string()),
+                        owner: None,
+                        tag_transform: None,
+                        display_name: None,
+                        provider: None,
+                        github_api_url: None,
+                        gitlab_api_url: None,
+                        local_path: None,
+                    },
+                ),
+                (
+                    "app-c".to_string(),
+                    RawAppConfig {
+                        repo: None,
+                        owner: None,
+                        tag_transform: None,
+                        display_name: None,
+                        provider: None,
+                        github_api_url: None,
+                        gitlab_api_url: None,
+                        local_path: Some("/work/app-c".to_string()),
+                    },
+                ),
+            ])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: ["app-a", "app-b", "app-c"]
+                .into_iter()
+                .flat_map(|app| {
+                    ["dev", "prod"].map(|env| RawAppVersion {
+                        app: app.to_string(),
+                        env: env.to_string(),
+                        version: "1.0.0".to_string(),
+                        source: None,
+                    })
+                })
+                .collect(),
+        };
+
+        // WHEN
+        let versions = Versions::try_from(raw).expect("result should've been Ok");
+
+        // THEN
+        let path = |app: &str| versions.local_repo_path_for(&app.into());
+        assert_eq!(path("app-a"), Some(PathBuf::from("/home/user/repos/app-a")));
+        assert_eq!(
+            path("app-b"),
+            Some(PathBuf::from("/home/user/repos/repo-b"))
+        );
+        assert_eq!(path("app-c"), Some(PathBuf::from("/work/app-c")));
+        assert_eq!(versions.api_url_for(&"app-a".into()), None);
+    }
+
+    #[test]
+    fn github_api_url_defaults_to_github_dot_com() {
+        // GIVEN
+        // WHEN
+        let versions = Versions::try_from(RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        })
+        .expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(
+            versions
+                .api_url_for(&"app-a".into())
+                .map(|url| url.as_str()),
+            Some(DEFAULT_GITHUB_API_URL)
+        );
+    }
+
+    #[test]
+    fn app_level_settings_take_precedence_over_top_level_ones() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: Some("v{{version}}".to_string()),
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: Some(BTreeMap::from([(
+                "service-x".to_string(),
+                RawAppConfig {
+                    repo: Some("monorepo".to_string()),
+                    owner: Some("other-org".to_string()),
+                    tag_transform: Some("service-x/v{{version}}".to_string()),
+                    display_name: Some("Service X".to_string()),
+                    provider: None,
+                    github_api_url: None,
+                    gitlab_api_url: None,
+                    local_path: None,
+                },
+            )])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "service-x".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let versions = Versions::try_from(raw).expect("result should've been Ok");
+
+        // THEN
+        let result = ["service-x", "app-a"]
+            .into_iter()
+            .map(|app| {
+                let app = App::from(app);
+                format!(
+                    "{app}: owner={}, repo={}, tag_transform={}, display_name={}",
+                    versions.github_org_for(&app),
+                    versions.repo_for(&app),
+                    versions
+                        .git_tag_transform_for(&app)
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    versions
+                        .display_name_for(&app)
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_snapshot!(result, @r"
+        service-x: owner=other-org, repo=monorepo, tag_transform=service-x/v{{version}}, display_name=Service X
+        app-a: owner=my-org, repo=app-a, tag_transform=v{{version}}, display_name=
+        ");
+    }
+
     //------------//
     //  FAILURES  //
     //------------//
@@ -406,21 +1471,34 @@ mod tests {
             envs: vec!["unknown".to_string()],
             github_org: "".to_string(),
             git_tag_transform: Some("no-placeholder".to_string()),
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
             versions: vec![
                 RawAppVersion {
                     app: "".to_string(),
                     env: "".to_string(),
                     version: "".to_string(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "valid-app".to_string(),
                     env: "dev".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "".to_string(),
                     env: "prod".to_string(),
                     version: "".to_string(),
+                    source: None,
                 },
             ],
         };
@@ -453,16 +1531,28 @@ mod tests {
             envs: vec![empty.clone(), empty.clone()],
             github_org: empty.clone(),
             git_tag_transform: Some(empty.clone()),
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
             versions: vec![
                 RawAppVersion {
                     app: empty.clone(),
                     env: empty.clone(),
                     version: empty.clone(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "valid-app".to_string(),
                     env: "dev".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
             ],
         };
@@ -491,16 +1581,28 @@ mod tests {
             envs: vec!["dev".to_string(), "prod".to_string()],
             github_org: "my-org".to_string(),
             git_tag_transform: Some("v{{version}}".to_string()),
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
             versions: vec![
                 RawAppVersion {
                     app: "app-a".to_string(),
                     env: "not-in-global-list".to_string(),
                     version: "1.0.0".to_string(),
+                    source: None,
                 },
                 RawAppVersion {
                     app: "app-b".to_string(),
                     env: "not-in-global-list".to_string(),
                     version: "1.1.0".to_string(),
+                    source: None,
                 },
             ],
         };
@@ -515,4 +1617,384 @@ mod tests {
          - env "prod" is not present in any of the versions configured
         "#);
     }
+
+    #[test]
+    fn parsing_versions_with_duplicate_app_envs_fails() {
+        // GIVEN
+        let version = |env: &str, version: &str, source: Option<&str>| RawAppVersion {
+            app: "app-a".to_string(),
+            env: env.to_string(),
+            version: version.to_string(),
+            source: source.map(|s| s.to_string()),
+        };
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                version("dev", "1.1.0", None),
+                version("prod", "1.0.0", None),
+                version("dev", "1.2.0", None),
+                version("prod", "1.0.0", Some("prod.toml")),
+            ],
+        };
+
+        // WHEN
+        let error = Versions::try_from(raw).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(error.to_string(), @r#"
+        versions config has errors:
+         - app "app-a" has more than one version for env "dev" (in version #0 and version #2)
+         - app "app-a" has more than one version for env "prod" (in version #1 and "prod.toml")
+        "#);
+    }
+
+    #[test]
+    fn parsing_invalid_comparison_pairs_fails() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: None,
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: Some(vec![
+                RawEnvPair {
+                    from: "prod".to_string(),
+                    to: "unknown".to_string(),
+                },
+                RawEnvPair {
+                    from: " ".to_string(),
+                    to: "dev".to_string(),
+                },
+                RawEnvPair {
+                    from: "dev".to_string(),
+                    to: "dev".to_string(),
+                },
+            ]),
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let error = Versions::try_from(raw).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(error.to_string(), @r#"
+        versions config has errors:
+         - comparison_pairs[0]: env "unknown" is not present in envs
+         - comparison_pairs[1]: env is empty
+         - comparison_pairs[2]: env "dev" is compared against itself
+        "#);
+    }
+
+    #[test]
+    fn parsing_versions_with_invalid_provider_settings_fails() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: Some("bitbucket".to_string()),
+            github_api_url: Some("github.example.com".to_string()),
+            gitlab_api_url: Some("".to_string()),
+            local_repos_dir: None,
+            apps: Some(BTreeMap::from([
+                (
+                    "app-a".to_string(),
+                    RawAppConfig {
+                        repo: None,
+                        owner: None,
+                        tag_transform: None,
+                        display_name: None,
+                        provider: Some("svn".to_string()),
+                        github_api_url: Some(" ".to_string()),
+                        gitlab_api_url: None,
+                        local_path: None,
+                    },
+                ),
+                (
+                    "app-z".to_string(),
+                    RawAppConfig {
+                        repo: None,
+                        owner: None,
+                        tag_transform: None,
+                        display_name: None,
+                        provider: None,
+                        github_api_url: None,
+                        gitlab_api_url: None,
+                        local_path: None,
+                    },
+                ),
+            ])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let error = Versions::try_from(raw).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(error.to_string(), @r#"
+        versions config has errors:
+         - provider "bitbucket" is invalid, expected one of: github, gitlab, local
+         - github_api_url "github.example.com" needs to start with "https://" or "http://"
+         - gitlab_api_url is empty
+         - apps.app-a: provider "svn" is invalid, expected one of: github, gitlab, local
+         - apps.app-a: github_api_url is empty
+         - apps.app-z: app "app-z" is not present in any of the versions configured
+        "#);
+    }
+
+    #[test]
+    fn parsing_versions_with_invalid_app_settings_fails() {
+        // GIVEN
+        let raw_config =
+            |repo: &str, owner: &str, tag_transform: &str, display_name: &str| RawAppConfig {
+                repo: Some(repo.to_string()),
+                owner: Some(owner.to_string()),
+                tag_transform: Some(tag_transform.to_string()),
+                display_name: Some(display_name.to_string()),
+                provider: None,
+                github_api_url: None,
+                gitlab_api_url: None,
+                local_path: None,
+            };
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: Some(BTreeMap::from([
+                (
+                    "app-a".to_string(),
+                    raw_config("", " ", "v{version}", "App"),
+                ),
+                (
+                    "app-b".to_string(),
+                    raw_config("repo-b", "org-b", "v{{version}}", "App"),
+                ),
+            ])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-b".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let error = Versions::try_from(raw).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(error.to_string(), @r#"
+        versions config has errors:
+         - apps.app-a: repo is empty
+         - apps.app-a: owner is empty
+         - apps.app-a: tag_transform doesn't include the placeholder "{{version}}"
+         - apps.app-b: display_name "App" is already used by app "app-a"
+        "#);
+    }
+
+    #[test]
+    fn parsing_versions_with_local_provider_and_no_paths_fails() {
+        // GIVEN
+        let raw = RawVersions {
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            github_org: "my-org".to_string(),
+            git_tag_transform: None,
+            provider: None,
+            github_api_url: None,
+            gitlab_api_url: None,
+            local_repos_dir: None,
+            apps: Some(BTreeMap::from([
+                (
+                    "app-a".to_string(),
+                    RawAppConfig {
+                        repo: None,
+                        owner: None,
+                        tag_transform: None,
+                        display_name: None,
+                        provider: Some("local".to_string()),
+                        github_api_url: None,
+                        gitlab_api_url: None,
+                        local_path: None,
+                    },
+                ),
+                (
+                    "app-b".to_string(),
+                    RawAppConfig {
+                        repo: None,
+                        owner: None,
+                        tag_transform: None,
+                        display_name: None,
+                        provider: Some("local".to_string()),
+                        github_api_url: None,
+                        gitlab_api_url: None,
+                        local_path: Some(" ".to_string()),
+                    },
+                ),
+            ])),
+            policies: None,
+            sources: None,
+            endpoints: None,
+            comparison_strategy: None,
+            comparison_pairs: None,
+            versions: vec![
+                RawAppVersion {
+                    app: "app-a".to_string(),
+                    env: "dev".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+                RawAppVersion {
+                    app: "app-b".to_string(),
+                    env: "prod".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: None,
+                },
+            ],
+        };
+
+        // WHEN
+        let error = Versions::try_from(raw).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(error.to_string(), @r#"
+        versions config has errors:
+         - apps.app-b: local_path is empty
+         - app "app-a" uses the "local" provider, but neither local_repos_dir nor apps.app-a.local_path is set
+         - app "app-b" uses the "local" provider, but neither local_repos_dir nor apps.app-b.local_path is set
+        "#);
+    }
+
+    #[test]
+    fn parsing_invalid_comparison_strategy_fails() {
+        // GIVEN
+        let cases = [
+            (Some("unknown"), None),
+            (Some("explicit"), None),
+            (Some("adjacent"), Some(vec![])),
+            (Some("explicit"), Some(vec![])),
+        ];
+
+        // WHEN
+        let errors = cases
+            .into_iter()
+            .map(|(strategy, pairs)| {
+                let raw = RawVersions {
+                    envs: vec!["dev".to_string(), "prod".to_string()],
+                    github_org: "my-org".to_string(),
+                    git_tag_transform: None,
+                    policies: None,
+                    sources: None,
+                    endpoints: None,
+                    comparison_strategy: strategy.map(String::from),
+                    comparison_pairs: pairs,
+                    provider: None,
+                    github_api_url: None,
+                    gitlab_api_url: None,
+                    local_repos_dir: None,
+                    apps: None,
+                    versions: vec![
+                        RawAppVersion {
+                            app: "app-a".to_string(),
+                            env: "dev".to_string(),
+                            version: "1.0.0".to_string(),
+                            source: None,
+                        },
+                        RawAppVersion {
+                            app: "app-a".to_string(),
+                            env: "prod".to_string(),
+                            version: "1.0.0".to_string(),
+                            source: None,
+                        },
+                    ],
+                };
+
+                Versions::try_from(raw)
+                    .expect_err("result should've been an error")
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(errors, @r#"
+        versions config has errors:
+         - comparison_strategy "unknown" is invalid, expected one of: adjacent, first-last, explicit
+
+        versions config has errors:
+         - comparison_pairs needs to be provided when comparison_strategy is "explicit"
+
+        versions config has errors:
+         - comparison_pairs cannot be used with comparison_strategy "adjacent"
+
+        versions config has errors:
+         - comparison_pairs is empty
+        "#);
+    }
 }
@@ -0,0 +1,50 @@
+use anyhow::Context;
+use std::path::Path;
+use std::process::{Command, Output};
+
+// runs git in a repository, and returns its stdout; when an index file is passed, git uses it
+// in place of the repository's own index
+pub fn run(repo: &Path, args: &[&str], index: Option<&Path>) -> anyhow::Result<String> {
+    let output = command(repo, args, index)
+        .output()
+        .context("failed to run git")?;
+
+    get_stdout(args, output)
+}
+
+// same as run, for callers that shouldn't block the async runtime
+pub async fn run_async(repo: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = tokio::process::Command::from(command(repo, args, None))
+        .output()
+        .await
+        .context("failed to run git")?;
+
+    get_stdout(args, output)
+}
+
+fn command(repo: &Path, args: &[&str], index: Option<&Path>) -> Command {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repo).args(args);
+    if let Some(index) = index {
+        command.env("GIT_INDEX_FILE", index);
+    }
+
+    command
+}
+
+fn get_stdout(args: &[&str], output: Output) -> anyhow::Result<String> {
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let command = args.first().unwrap_or(&"");
+        if stderr.trim().is_empty() {
+            anyhow::bail!("git {command} failed with {}", output.status);
+        }
+        anyhow::bail!(
+            "git {command} failed with {}: {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    String::from_utf8(output.stdout).context("git output is not valid UTF-8")
+}
@@ -1,56 +1,75 @@
 mod args;
 mod config;
 mod domain;
+mod git;
+mod project_config;
+mod serve;
 mod service;
+mod snapshot;
+mod sources;
+mod tui;
 mod versions;
 mod view;
 
-use crate::config::{HtmlConfig, OutputFormat, StdoutConfig};
+use crate::config::{
+    FailOn, HistoryOutputFormat, HtmlConfig, MarkdownConfig, OutputFormat, SortBy, StdoutConfig,
+};
 use anyhow::Context;
 use args::Args;
-use chrono::Utc;
-use clap::Parser;
+use chrono::{DateTime, Utc};
 use config::{Config, OutputType};
+use domain::{ApiUrl, CommitLogResults, DiffResult, Provider, SyncStatus, Versions};
 use regex::Regex;
+use service::{CommitLogCache, EndpointFetchErrors, HistoryStore, ProviderTokens, RetryPolicy};
+use snapshot::GitSnapshot;
 use std::env::VarError;
+use std::io::IsTerminal;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 const ENV_VAR_GH_TOKEN: &str = "ENVEE_GH_TOKEN";
+const ENV_VAR_GH_API_URL: &str = "ENVEE_GH_API_URL";
+const ENV_VAR_GITLAB_TOKEN: &str = "ENVEE_GITLAB_TOKEN";
+const ENV_VAR_GITLAB_API_URL: &str = "ENVEE_GITLAB_API_URL";
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+    let args = Args::parse_with_project_config()?;
 
     if args.debug {
         print!("DEBUG INFO\n{args}");
+        // run is the only command whose quota usage is worth knowing about before running it
+        if let args::EnveeCommand::Run { versions_args, .. } = &args.command {
+            println!(
+                "GitHub API quota:                     {}",
+                get_github_quota(versions_args).await
+            );
+        }
         return Ok(());
     }
 
     match args.command {
         args::EnveeCommand::Run {
-            versions_file_path,
+            versions_args,
             only_validate_versions,
-            no_commit_logs,
+            interactive,
+            commit_log_args,
             output_format,
             app_filter,
+            status_filter,
+            fail_on,
+            sort_by,
+            history_path,
+            github_actions,
             stdout_table_style,
             stdout_plain_output,
             html_output_path,
             html_title,
             html_template_path,
+            markdown_title,
         } => {
             // READ AND VALIDATE INPUT
-            let maybe_token = if no_commit_logs || only_validate_versions {
-                None
-            } else {
-                get_env_var(ENV_VAR_GH_TOKEN)?
-            };
-
-            if !(no_commit_logs || only_validate_versions) && maybe_token.is_none() {
-                anyhow::bail!(
-                    "{ENV_VAR_GH_TOKEN} needs to be set to fetch commit logs from GitHub"
-                );
-            }
-
             let app_filter = app_filter
                 .map(|pattern| Regex::new(&pattern))
                 .transpose()
@@ -77,65 +96,654 @@ async fn main() -> anyhow::Result<()> {
                             template,
                         })
                     }
+                    OutputFormat::Json => OutputType::Json,
+                    OutputFormat::Markdown => OutputType::Markdown(MarkdownConfig {
+                        title: markdown_title.clone(),
+                    }),
                 },
             };
 
-            let versions = versions::get_from_file(&versions_file_path, app_filter.as_ref())?;
+            let mut versions = load_versions(&versions_args, app_filter.as_ref())?;
 
             if only_validate_versions {
                 println!("versions file is valid ✅");
                 return Ok(());
             }
 
-            // GET RESULTS
-            let diff_result = service::get_diff_result(versions.envs.clone(), &versions.versions);
+            let endpoint_errors = add_endpoint_versions(&mut versions).await;
+            let maybe_tokens = get_tokens_if_needed(&versions, &commit_log_args)?;
+            let cache = get_cache_if_needed(&commit_log_args)?;
 
-            let commit_log_results = if no_commit_logs {
+            // GET RESULTS
+            let now = Utc::now();
+            let mut diff_result =
+                service::get_diff_result(versions.envs.clone(), &versions.versions);
+            service::add_unknown_versions(&mut diff_result, endpoint_errors.unknown_versions());
+            service::add_display_names(&mut diff_result, &versions);
+            service::check_policies(&mut diff_result, &versions.policies);
+            // --status only affects what's shown, so all apps are recorded, and violations are
+            // looked for, before filtering
+            if let Some(path) = history_path {
+                HistoryStore::new(path).record(&mut diff_result, now)?;
+            }
+            let mut violations = service::get_sync_violations(&diff_result, &fail_on);
+            // like --fail-on, annotations and step outputs cover apps that --status hides
+            let github_actions_report = if github_actions
+                || get_env_var(service::ENV_VAR_GITHUB_ACTIONS)?.as_deref() == Some("true")
+            {
+                Some((
+                    service::get_app_annotations(&diff_result, &fail_on),
+                    service::StepOutputs::new(&diff_result),
+                ))
+            } else {
                 None
+            };
+            let statuses: Vec<SyncStatus> = status_filter.into_iter().map(Into::into).collect();
+            service::filter_by_sync_status(&mut diff_result, &statuses);
+            service::sort_app_results(&mut diff_result, sort_by);
+
+            if interactive {
+                if !std::io::stdout().is_terminal() {
+                    anyhow::bail!("--interactive needs stdout to be a terminal");
+                }
+
+                return tui::run(tui::TuiContext {
+                    diff_result,
+                    versions,
+                    tokens: maybe_tokens,
+                    max_commits: commit_log_args.max_commits as usize,
+                    cache,
+                    retry_policy: get_retry_policy(&commit_log_args),
+                });
+            }
+
+            let commit_log_results = match maybe_tokens {
+                Some(tokens) => Some(
+                    service::fetch_commit_logs(
+                        &diff_result,
+                        &versions,
+                        &tokens,
+                        commit_log_args.max_commits as usize,
+                        cache.as_ref(),
+                        get_retry_policy(&commit_log_args),
+                    )
+                    .await,
+                ),
+                None => None,
+            };
+
+            // DISPLAY OUTPUT
+            let output =
+                view::render_output(&diff_result, commit_log_results.as_ref(), &config, now)?;
+
+            write_output(output, &config)?;
+
+            if let Some((annotations, outputs)) = github_actions_report {
+                report_to_github_actions(
+                    annotations,
+                    outputs,
+                    &diff_result,
+                    commit_log_results.as_ref(),
+                    &fail_on,
+                    &markdown_title,
+                    now,
+                )?;
+            }
+
+            let fetch_errors = commit_log_results
+                .map(|results| results.errors)
+                .filter(|errors| !errors.is_empty());
+
+            if fail_on.is_empty() {
+                if let Some(errors) = fetch_errors {
+                    return Err(errors.into());
+                }
+                return Ok(());
+            }
+
+            // when --fail-on is used, only the conditions passed to it decide whether envee fails
+            if let Some(errors) = fetch_errors {
+                violations.extend(service::get_fetch_error_violation(&errors, &fail_on));
+                if fail_on.contains(&FailOn::FetchError) {
+                    eprint!("Error: {errors}");
+                } else {
+                    eprint!("Warning: {errors}");
+                }
+            }
+
+            if let Some(violation) = violations.first() {
+                eprintln!("Error: conditions passed to --fail-on were met:");
+                for violation in &violations {
+                    eprintln!(" - {violation}");
+                }
+                std::process::exit(violation.condition.exit_code());
+            }
+        }
+        args::EnveeCommand::Serve {
+            versions_args,
+            watch,
+            refresh_interval,
+            host,
+            port,
+            commit_log_args,
+            app_filter,
+            status_filter,
+            sort_by,
+            history_path,
+            html_title,
+            html_template_path,
+        } => {
+            if refresh_interval.is_zero() {
+                anyhow::bail!("--refresh-interval needs to be greater than zero");
+            }
+            // versions are read again on every refresh, which stdin can't support
+            if versions_args
+                .paths
+                .iter()
+                .any(|p| p.as_os_str() == versions::STDIN_PATH)
+            {
+                anyhow::bail!("serve can't read versions from stdin");
+            }
+            // the files at a git revision don't change, so there's nothing to watch
+            if watch && versions_args.git_ref.is_some() {
+                anyhow::bail!("--watch can't be used with --versions-git-ref");
+            }
+
+            let app_filter = app_filter
+                .map(|pattern| Regex::new(&pattern))
+                .transpose()
+                .context("invalid regex pattern provided")?;
+
+            let html_template = if let Some(ref template_path) = html_template_path {
+                Some(std::fs::read_to_string(template_path).with_context(|| {
+                    format!("failed to read HTML template from {:?}", template_path)
+                })?)
             } else {
-                let token =
-                    maybe_token.with_context(|| format!("{ENV_VAR_GH_TOKEN} is not set"))?;
-                Some(service::fetch_commit_logs(&diff_result, &versions, &token).await)
+                None
+            };
+
+            let watch_paths = versions_args.paths.clone();
+            let settings = Arc::new(ReportSettings {
+                versions_args,
+                app_filter,
+                cache: get_cache_if_needed(&commit_log_args)?,
+                commit_log_args,
+                status_filter: status_filter.into_iter().map(Into::into).collect(),
+                sort_by,
+                history: history_path.map(HistoryStore::new),
+            });
+            let build_report: serve::BuildReport = Arc::new(move || {
+                let settings = Arc::clone(&settings);
+                Box::pin(async move { build_report(&settings).await })
+            });
+
+            serve::serve(
+                serve::ServeConfig {
+                    address: SocketAddr::new(host, port),
+                    refresh_interval,
+                    watch_paths: if watch { watch_paths } else { vec![] },
+                    html_title,
+                    html_template,
+                },
+                build_report,
+            )
+            .await?;
+        }
+        args::EnveeCommand::Diff {
+            old_path,
+            new_path,
+            versions_format,
+            old_git_ref,
+            new_git_ref,
+            repo,
+            commit_log_args,
+            output_format,
+            app_filter,
+            stdout_table_style,
+            stdout_plain_output,
+            html_output_path,
+            html_title,
+            markdown_title,
+        } => {
+            // READ AND VALIDATE INPUT
+            if old_path.as_os_str() == versions::STDIN_PATH
+                && new_path.as_os_str() == versions::STDIN_PATH
+            {
+                anyhow::bail!("only one of OLD and NEW can be read from stdin");
+            }
+            if old_git_ref.is_some() && !is_repo_relative(&old_path) {
+                anyhow::bail!(
+                    "OLD needs to be a path relative to the repository when --old-git-ref is used"
+                );
+            }
+            if new_git_ref.is_some() && !is_repo_relative(&new_path) {
+                anyhow::bail!(
+                    "NEW needs to be a path relative to the repository when --new-git-ref is used"
+                );
+            }
+            if repo.is_some() && old_git_ref.is_none() && new_git_ref.is_none() {
+                anyhow::bail!("--versions-repo needs --old-git-ref or --new-git-ref to be used");
+            }
+
+            let app_filter = app_filter
+                .map(|pattern| Regex::new(&pattern))
+                .transpose()
+                .context("invalid regex pattern provided")?;
+
+            let config = Config {
+                output_type: match output_format {
+                    OutputFormat::Stdout => OutputType::Stdout(StdoutConfig {
+                        table_style: stdout_table_style,
+                        plain_output: stdout_plain_output,
+                    }),
+                    OutputFormat::Html => OutputType::Html(HtmlConfig {
+                        output_path: html_output_path,
+                        title: html_title,
+                        template: None,
+                    }),
+                    OutputFormat::Json => OutputType::Json,
+                    OutputFormat::Markdown => OutputType::Markdown(MarkdownConfig {
+                        title: markdown_title,
+                    }),
+                },
+            };
+
+            let load = |path: &Path, git_ref: Option<&str>| -> anyhow::Result<Versions> {
+                let mut versions = read_versions_at(git_ref, repo.as_deref(), |root| {
+                    versions::get_from_file(root, path, versions_format, app_filter.as_ref())
+                })?;
+                override_api_urls(&mut versions)?;
+                Ok(versions)
+            };
+            let old_versions = load(&old_path, old_git_ref.as_deref())?;
+            // apps' settings, such as their repos, are taken from the new versions file
+            let new_versions = load(&new_path, new_git_ref.as_deref())?;
+
+            let maybe_tokens = get_tokens_if_needed(&new_versions, &commit_log_args)?;
+            let cache = get_cache_if_needed(&commit_log_args)?;
+
+            // GET RESULTS
+            let changes = service::get_version_changes(&old_versions, &new_versions);
+
+            let commit_log_results = match maybe_tokens {
+                Some(tokens) => Some(
+                    service::fetch_commit_logs_for_changes(
+                        &changes,
+                        &new_versions,
+                        &tokens,
+                        commit_log_args.max_commits as usize,
+                        cache.as_ref(),
+                        get_retry_policy(&commit_log_args),
+                    )
+                    .await,
+                ),
+                None => None,
             };
 
             // DISPLAY OUTPUT
-            let output = view::render_output(
-                &diff_result,
+            let output = view::render_changes_output(
+                &changes,
                 commit_log_results.as_ref(),
                 &config,
                 Utc::now(),
             )?;
+            write_output(output, &config)?;
 
-            match &config.output_type {
-                OutputType::Stdout(_) => {
-                    println!("{}", output);
-                }
-                OutputType::Html(html_config) => {
-                    if let Some(parent) = html_config.output_path.parent() {
-                        std::fs::create_dir_all(parent)
-                            .with_context(|| format!("failed to create directory {:?}", parent))?;
-                    }
-                    std::fs::write(&html_config.output_path, output).with_context(|| {
-                        format!("failed to write HTML to {:?}", html_config.output_path)
-                    })?;
-                    println!(
-                        "HTML report written to: {}",
-                        html_config.output_path.display()
-                    );
-                }
+            if let Some(errors) = commit_log_results
+                .map(|results| results.errors)
+                .filter(|errors| !errors.is_empty())
+            {
+                return Err(errors.into());
+            }
+        }
+        args::EnveeCommand::History {
+            history_path,
+            since,
+            output_format,
+            app_filter,
+            stdout_table_style,
+            stdout_plain_output,
+        } => {
+            // an empty history is expected before the first run, but not when reading it
+            if !history_path.exists() {
+                anyhow::bail!("history file {:?} doesn't exist", history_path);
             }
 
-            if let Some(results) = commit_log_results
-                && !results.errors.is_empty()
-            {
-                return Err(results.errors.into());
+            let app_filter = app_filter
+                .map(|pattern| Regex::new(&pattern))
+                .transpose()
+                .context("invalid regex pattern provided")?;
+
+            let now = Utc::now();
+            let since = since
+                .map(chrono::Duration::from_std)
+                .transpose()
+                .context("--since is too long")?
+                .map(|since| {
+                    now.checked_sub_signed(since)
+                        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+                });
+
+            let records = HistoryStore::new(history_path).read()?;
+            let report = service::get_history_report(&records, app_filter.as_ref(), since);
+
+            let output = match output_format {
+                HistoryOutputFormat::Stdout => view::render_history_table(
+                    &report,
+                    &StdoutConfig {
+                        table_style: stdout_table_style,
+                        plain_output: stdout_plain_output,
+                    },
+                ),
+                HistoryOutputFormat::Json => view::render_history_json(&report, now)?,
+            };
+            println!("{output}");
+        }
+    }
+
+    Ok(())
+}
+
+// everything needed to build a report from scratch, which `serve` does on every refresh
+struct ReportSettings {
+    versions_args: args::VersionsArgs,
+    app_filter: Option<Regex>,
+    commit_log_args: args::CommitLogArgs,
+    cache: Option<CommitLogCache>,
+    status_filter: Vec<SyncStatus>,
+    sort_by: SortBy,
+    history: Option<HistoryStore>,
+}
+
+async fn build_report(settings: &ReportSettings) -> anyhow::Result<serve::Report> {
+    let mut versions = load_versions(&settings.versions_args, settings.app_filter.as_ref())?;
+    let endpoint_errors = add_endpoint_versions(&mut versions).await;
+    let maybe_tokens = get_tokens_if_needed(&versions, &settings.commit_log_args)?;
+
+    let mut diff_result = service::get_diff_result(versions.envs.clone(), &versions.versions);
+    service::add_unknown_versions(&mut diff_result, endpoint_errors.unknown_versions());
+    service::add_display_names(&mut diff_result, &versions);
+    service::check_policies(&mut diff_result, &versions.policies);
+    if let Some(history) = &settings.history {
+        history.record(&mut diff_result, Utc::now())?;
+    }
+    service::filter_by_sync_status(&mut diff_result, &settings.status_filter);
+    service::sort_app_results(&mut diff_result, settings.sort_by);
+
+    let commit_log_results = match maybe_tokens {
+        Some(tokens) => Some(
+            service::fetch_commit_logs(
+                &diff_result,
+                &versions,
+                &tokens,
+                settings.commit_log_args.max_commits as usize,
+                settings.cache.as_ref(),
+                get_retry_policy(&settings.commit_log_args),
+            )
+            .await,
+        ),
+        None => None,
+    };
+
+    Ok(serve::Report {
+        diff_result,
+        commit_log_results,
+    })
+}
+
+fn load_versions(
+    versions_args: &args::VersionsArgs,
+    app_filter: Option<&Regex>,
+) -> anyhow::Result<Versions> {
+    if versions_args.git_ref.is_some() && versions_args.paths.iter().any(|p| !is_repo_relative(p)) {
+        anyhow::bail!(
+            "--versions needs to be a path relative to the repository when --versions-git-ref is used"
+        );
+    }
+
+    let mut versions = read_versions_at(
+        versions_args.git_ref.as_deref(),
+        versions_args.repo.as_deref(),
+        |root| {
+            versions::get_from_paths(
+                root,
+                &versions_args.paths,
+                &versions_args.envs,
+                versions_args.format,
+                app_filter,
+            )
+        },
+    )?;
+    override_api_urls(&mut versions)?;
+
+    Ok(versions)
+}
+
+// reads versions using `read`, which is passed the directory relative paths are to be resolved
+// against; that's the working directory, unless the files are to be read as they were at `git_ref`
+fn read_versions_at<F>(
+    git_ref: Option<&str>,
+    repo: Option<&Path>,
+    read: F,
+) -> anyhow::Result<Versions>
+where
+    F: FnOnce(&Path) -> anyhow::Result<Versions>,
+{
+    let Some(git_ref) = git_ref else {
+        return read(Path::new(""));
+    };
+
+    // the snapshot is kept around until versions are read, since sources read files from it too
+    let snapshot = GitSnapshot::create(repo.unwrap_or(Path::new(".")), git_ref)?;
+
+    read(&snapshot.root()).with_context(|| {
+        format!(
+            r#"couldn't read versions at "{git_ref}" (commit {})"#,
+            snapshot.commit
+        )
+    })
+}
+
+// files at a git revision can only be looked up by their path in the repository
+fn is_repo_relative(path: &Path) -> bool {
+    path.as_os_str() != versions::STDIN_PATH && !path.is_absolute()
+}
+
+// the env vars override the top level settings in the versions file, but not the app level ones
+fn override_api_urls(versions: &mut Versions) -> anyhow::Result<()> {
+    if let Some(api_url) = get_env_var(ENV_VAR_GH_API_URL)? {
+        versions.github_api_url = ApiUrl::parse("github_api_url", api_url)
+            .map_err(|e| anyhow::anyhow!("{ENV_VAR_GH_API_URL} is invalid: {e}"))?;
+    }
+    if let Some(api_url) = get_env_var(ENV_VAR_GITLAB_API_URL)? {
+        versions.gitlab_api_url = ApiUrl::parse("gitlab_api_url", api_url)
+            .map_err(|e| anyhow::anyhow!("{ENV_VAR_GITLAB_API_URL} is invalid: {e}"))?;
+    }
+
+    Ok(())
+}
+
+fn write_output(output: String, config: &Config) -> anyhow::Result<()> {
+    match &config.output_type {
+        OutputType::Stdout(_) | OutputType::Json | OutputType::Markdown(_) => {
+            println!("{}", output);
+        }
+        OutputType::Html(html_config) => {
+            if let Some(parent) = html_config.output_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create directory {:?}", parent))?;
             }
+            std::fs::write(&html_config.output_path, output).with_context(|| {
+                format!("failed to write HTML to {:?}", html_config.output_path)
+            })?;
+            println!(
+                "HTML report written to: {}",
+                html_config.output_path.display()
+            );
         }
     }
 
     Ok(())
 }
 
+// annotations are written to stderr, so that they don't end up in output meant for other tools,
+// eg. JSON; GitHub Actions picks them up from both stdout and stderr
+fn report_to_github_actions(
+    mut annotations: Vec<service::Annotation>,
+    mut outputs: service::StepOutputs,
+    diff_result: &DiffResult,
+    commit_log_results: Option<&CommitLogResults>,
+    fail_on: &[FailOn],
+    title: &str,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let get_path = |key: &str| -> anyhow::Result<Option<PathBuf>> {
+        Ok(get_env_var(key)?
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from))
+    };
+    let files = service::GithubActionsFiles {
+        step_summary_path: get_path(service::ENV_VAR_GITHUB_STEP_SUMMARY)?,
+        output_path: get_path(service::ENV_VAR_GITHUB_OUTPUT)?,
+    };
+
+    let commit_logs = commit_log_results.map(|r| &r.logs[..]).unwrap_or(&[]);
+    if let Some(results) = commit_log_results {
+        annotations.extend(service::get_fetch_error_annotations(
+            &results.errors,
+            fail_on,
+        ));
+        outputs.commit_log_error_count = results.errors.iter().count();
+    }
+
+    for annotation in &annotations {
+        eprintln!("{annotation}");
+    }
+
+    let summary = view::render_markdown(
+        diff_result,
+        commit_logs,
+        title,
+        now,
+        view::GITHUB_COMMENT_MAX_LENGTH,
+    );
+    files.append_step_summary(&summary)?;
+    files.set_outputs(&outputs)
+}
+
+// versions fetched from endpoints are added to the ones read from the versions file; endpoints
+// that couldn't be fetched are warned about rather than failing the run, and show up as unknown
+async fn add_endpoint_versions(versions: &mut Versions) -> EndpointFetchErrors {
+    let results = service::fetch_endpoint_versions(&versions.endpoints).await;
+    versions.versions.extend(results.versions);
+
+    if !results.errors.is_empty() {
+        eprint!("Warning: {}", results.errors);
+    }
+
+    results.errors
+}
+
+fn get_tokens_if_needed(
+    versions: &Versions,
+    commit_log_args: &args::CommitLogArgs,
+) -> anyhow::Result<Option<ProviderTokens>> {
+    if commit_log_args.no_commit_logs {
+        return Ok(None);
+    }
+
+    get_provider_tokens(&versions.providers_in_use()).map(Some)
+}
+
+fn get_cache_if_needed(
+    commit_log_args: &args::CommitLogArgs,
+) -> anyhow::Result<Option<CommitLogCache>> {
+    if commit_log_args.no_cache || commit_log_args.no_commit_logs {
+        return Ok(None);
+    }
+
+    Ok(Some(CommitLogCache::new(
+        CommitLogCache::default_dir()?,
+        commit_log_args.cache_ttl,
+        commit_log_args.refresh_cache,
+    )))
+}
+
+fn get_retry_policy(commit_log_args: &args::CommitLogArgs) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: commit_log_args.max_attempts,
+        max_wait: commit_log_args.max_retry_wait,
+    }
+}
+
+// the quota is looked up at the GitHub API URL run would use; whatever keeps it from being looked
+// up is shown in its place, since this is only debug output
+async fn get_github_quota(versions_args: &args::VersionsArgs) -> String {
+    let token = match get_env_var(ENV_VAR_GH_TOKEN) {
+        Ok(Some(token)) => token,
+        Ok(None) => return format!("unknown ({ENV_VAR_GH_TOKEN} is not set)"),
+        Err(e) => return format!("unknown ({e:#})"),
+    };
+
+    let api_url = match get_debug_github_api_url(versions_args) {
+        Ok(api_url) => api_url,
+        Err(e) => return format!("unknown (couldn't get the GitHub API URL: {e:#})"),
+    };
+
+    match service::get_rate_limit(&api_url, &token).await {
+        Ok(rate_limit) => format!(
+            "{}/{} requests remaining, resets at {}",
+            rate_limit.remaining,
+            rate_limit.limit,
+            rate_limit.reset.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+        Err(e) => format!("unknown (couldn't fetch it: {e:#})"),
+    }
+}
+
+// debug mode isn't supposed to do anything, so versions files are only read if they're plain
+// files, ie. not stdin (which would be consumed), and not at a git revision (which would need to be
+// checked out)
+fn get_debug_github_api_url(versions_args: &args::VersionsArgs) -> anyhow::Result<ApiUrl> {
+    if let Some(api_url) = get_env_var(ENV_VAR_GH_API_URL)? {
+        return ApiUrl::parse("github_api_url", api_url)
+            .map_err(|e| anyhow::anyhow!("{ENV_VAR_GH_API_URL} is invalid: {e}"));
+    }
+
+    if versions_args.git_ref.is_some() {
+        anyhow::bail!("versions files at a git revision aren't read in debug mode");
+    }
+    if versions_args
+        .paths
+        .iter()
+        .any(|p| p.as_os_str() == versions::STDIN_PATH)
+    {
+        anyhow::bail!("versions aren't read from stdin in debug mode");
+    }
+
+    Ok(load_versions(versions_args, None)?.github_api_url)
+}
+
+// only the tokens for providers that are actually in use are required
+fn get_provider_tokens(providers: &[Provider]) -> anyhow::Result<ProviderTokens> {
+    let mut tokens = ProviderTokens::default();
+
+    for provider in providers {
+        let (env_var, token) = match provider {
+            Provider::Github => (ENV_VAR_GH_TOKEN, &mut tokens.github),
+            Provider::Gitlab => (ENV_VAR_GITLAB_TOKEN, &mut tokens.gitlab),
+            Provider::Local => continue,
+        };
+
+        *token = get_env_var(env_var)?;
+        if token.is_none() {
+            anyhow::bail!("{env_var} needs to be set to fetch commit logs from {provider}");
+        }
+    }
+
+    Ok(tokens)
+}
+
 fn get_env_var(key: &str) -> anyhow::Result<Option<String>> {
     match std::env::var(key) {
         Ok(v) => Ok(Some(v)),
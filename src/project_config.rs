@@ -0,0 +1,340 @@
+use anyhow::Context;
+use clap::{Arg, ArgAction, Command, Id};
+use etcetera::BaseStrategy;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+pub const PROJECT_CONFIG_FILE_NAME: &str = "envee.toml";
+
+// default options for envee's subcommands, read from envee.toml; only the [run] table is looked at,
+// so the file can double as the top level versions file
+#[derive(Debug, Clone)]
+pub struct ProjectConfig {
+    pub path: PathBuf,
+    run: toml::Table,
+}
+
+#[derive(Deserialize)]
+struct RawProjectConfig {
+    run: Option<toml::Table>,
+}
+
+// what the project config file contributed to the parsed args, for debug output
+#[derive(Debug, Clone, Default)]
+pub struct ProjectConfigUsage {
+    pub path: PathBuf,
+    // options that weren't overridden by flags
+    pub options: Vec<String>,
+}
+
+impl ProjectConfig {
+    // the current directory is looked in first, then envee's config directory
+    pub fn discover() -> anyhow::Result<Option<Self>> {
+        let local_path = PathBuf::from(PROJECT_CONFIG_FILE_NAME);
+        if local_path.is_file() {
+            return Self::read(local_path).map(Some);
+        }
+
+        let strategy = etcetera::choose_base_strategy()
+            .context("couldn't determine the config directory for your OS")?;
+        let global_path = strategy
+            .config_dir()
+            .join("envee")
+            .join(PROJECT_CONFIG_FILE_NAME);
+        if global_path.is_file() {
+            return Self::read(global_path).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn read(path: PathBuf) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("couldn't read config file {:?}", path))?;
+
+        Self::parse(path, &contents)
+    }
+
+    fn parse(path: PathBuf, contents: &str) -> anyhow::Result<Self> {
+        let raw: RawProjectConfig = toml::from_str(contents)
+            .with_context(|| format!("config file {:?} is invalid", path))?;
+
+        Ok(Self {
+            path,
+            run: raw.run.unwrap_or_default(),
+        })
+    }
+
+    // sets the options in the [run] table as the defaults of the run subcommand's args, so that
+    // flags passed on the command line take precedence over them; returns the updated command, along
+    // with the options that were set and the args they were set for
+    pub fn apply_to_run(&self, mut run: Command) -> anyhow::Result<(Command, Vec<(String, Id)>)> {
+        let mut applied = Vec::with_capacity(self.run.len());
+
+        for (key, value) in &self.run {
+            let long = key.replace('_', "-");
+            let arg = run
+                .get_arguments()
+                .find(|arg| {
+                    arg.get_long() == Some(long.as_str())
+                        && !matches!(arg.get_action(), ArgAction::Help | ArgAction::Version)
+                })
+                .with_context(|| {
+                    format!(
+                        r#"config file {:?} has an unknown option "{key}" in [run]"#,
+                        self.path
+                    )
+                })?;
+            let id = arg.get_id().clone();
+            let invalid = |reason: String| {
+                anyhow::anyhow!(
+                    r#"config file {:?} has an invalid value for "{key}" in [run]: {reason}"#,
+                    self.path
+                )
+            };
+
+            let values = get_values(arg.get_action(), value).map_err(invalid)?;
+
+            // clap panics on invalid defaults in debug builds, so they're checked beforehand
+            for value in &values {
+                let parts = match arg.get_value_delimiter() {
+                    Some(delimiter) => value.split(delimiter).collect::<Vec<_>>(),
+                    None => vec![value.as_str()],
+                };
+                for part in parts {
+                    check_value(arg, &long, part).map_err(invalid)?;
+                }
+            }
+
+            run = run.mut_arg(&id, |arg| arg.default_values(values));
+            applied.push((key.clone(), id));
+        }
+
+        Ok((run, applied))
+    }
+}
+
+fn get_values(action: &ArgAction, value: &toml::Value) -> Result<Vec<String>, String> {
+    let takes_many = matches!(action, ArgAction::Append);
+
+    match value {
+        toml::Value::Array(items) if takes_many => items.iter().map(get_scalar_value).collect(),
+        toml::Value::Array(_) => Err("expected a single value, not an array".to_string()),
+        toml::Value::Boolean(_) if !matches!(action, ArgAction::SetTrue) => {
+            Err("expected a value, not a boolean".to_string())
+        }
+        value => get_scalar_value(value).map(|value| vec![value]),
+    }
+}
+
+fn get_scalar_value(value: &toml::Value) -> Result<String, String> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(n) => Ok(n.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        other => Err(format!(
+            "values of type {} aren't supported",
+            other.type_str()
+        )),
+    }
+}
+
+// parses the value the way clap would if it were passed on the command line
+fn check_value(arg: &Arg, long: &str, value: &str) -> Result<(), String> {
+    let mut checked_arg = Arg::new(arg.get_id().clone())
+        .long(long.to_string())
+        .action(ArgAction::Set)
+        .allow_hyphen_values(true)
+        .value_parser(arg.get_value_parser().clone());
+    if let Some(value_names) = arg.get_value_names() {
+        checked_arg = checked_arg.value_names(value_names.to_vec());
+    }
+
+    Command::new("envee")
+        .no_binary_name(true)
+        .arg(checked_arg)
+        .try_get_matches_from([format!("--{long}"), value.to_string()])
+        .map(|_| ())
+        .map_err(|e| get_clap_error_message(&e))
+}
+
+// clap's errors are meant for the command line, and come with usage hints that don't apply here
+fn get_clap_error_message(error: &clap::Error) -> String {
+    error
+        .to_string()
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .map(|line| line.trim().trim_start_matches("error: "))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use crate::config::{StatusFilter, TableStyle};
+    use clap::CommandFactory;
+    use std::time::Duration;
+
+    fn get_run_command() -> Command {
+        Args::command()
+            .find_subcommand("run")
+            .cloned()
+            .expect("run subcommand should've existed")
+    }
+
+    fn get_config(contents: &str) -> ProjectConfig {
+        ProjectConfig::parse(PathBuf::from(PROJECT_CONFIG_FILE_NAME), contents)
+            .expect("config should've been parsed")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn options_are_applied_as_defaults_of_run() {
+        // GIVEN
+        let config = get_config(
+            r#"
+[run]
+versions = ["versions/", "extra.toml"]
+status = "behind,ahead"
+max_commits = 50
+cache_ttl = "1d"
+stdout_plain = true
+stdout_table_style = "ascii"
+"#,
+        );
+
+        // WHEN
+        let (run, applied) = config
+            .apply_to_run(get_run_command())
+            .expect("result should've been Ok");
+        let matches = run.get_matches_from(["run", "--stdout-table-style", "markdown"]);
+
+        // THEN
+        assert_eq!(
+            applied
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "cache_ttl",
+                "max_commits",
+                "status",
+                "stdout_plain",
+                "stdout_table_style",
+                "versions"
+            ]
+        );
+        assert_eq!(
+            matches
+                .get_many::<PathBuf>("paths")
+                .map(|paths| paths.cloned().collect::<Vec<_>>()),
+            Some(vec![
+                PathBuf::from("versions/"),
+                PathBuf::from("extra.toml")
+            ])
+        );
+        assert_eq!(
+            matches
+                .get_many::<StatusFilter>("status_filter")
+                .map(|statuses| statuses.copied().collect::<Vec<_>>()),
+            Some(vec![StatusFilter::Behind, StatusFilter::Ahead])
+        );
+        assert_eq!(matches.get_one::<u32>("max_commits"), Some(&50));
+        assert_eq!(
+            matches.get_one::<Duration>("cache_ttl"),
+            Some(&Duration::from_secs(24 * 60 * 60))
+        );
+        assert_eq!(matches.get_one::<bool>("stdout_plain_output"), Some(&true));
+        // flags passed on the command line take precedence
+        assert!(matches!(
+            matches.get_one::<TableStyle>("stdout_table_style"),
+            Some(TableStyle::Markdown)
+        ));
+    }
+
+    #[test]
+    fn a_config_file_without_a_run_table_sets_no_options() {
+        // GIVEN
+        // envee.toml can also be the top level versions file
+        let config = get_config(
+            r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+"#,
+        );
+
+        // WHEN
+        let (_, applied) = config
+            .apply_to_run(get_run_command())
+            .expect("result should've been Ok");
+
+        // THEN
+        assert!(applied.is_empty());
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn applying_unknown_options_fails() {
+        // GIVEN
+        let config = get_config(
+            r#"
+[run]
+stdout_table_styles = "ascii"
+"#,
+        );
+
+        // WHEN
+        let error = config
+            .apply_to_run(get_run_command())
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error, @r#"config file "envee.toml" has an unknown option "stdout_table_styles" in [run]"#);
+    }
+
+    #[test]
+    fn applying_invalid_values_fails() {
+        // GIVEN
+        let config = get_config(
+            r#"
+[run]
+stdout_table_style = "fancy"
+"#,
+        );
+
+        // WHEN
+        let error = config
+            .apply_to_run(get_run_command())
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error, @r#"config file "envee.toml" has an invalid value for "stdout_table_style" in [run]: invalid value 'fancy' for '--stdout-table-style <STRING>' [possible values: ascii, markdown, none, utf8]"#);
+    }
+
+    #[test]
+    fn applying_values_of_the_wrong_type_fails() {
+        // GIVEN
+        let config = get_config(
+            r#"
+[run]
+html_title = ["a", "b"]
+"#,
+        );
+
+        // WHEN
+        let error = config
+            .apply_to_run(get_run_command())
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error, @r#"config file "envee.toml" has an invalid value for "html_title" in [run]: expected a single value, not an array"#);
+    }
+}
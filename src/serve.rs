@@ -0,0 +1,417 @@
+use crate::domain::{CommitLogResults, DiffResult};
+use crate::view;
+use anyhow::Context;
+use axum::Router;
+use axum::extract::State;
+use axum::http::{StatusCode, header};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use tokio::time::MissedTickBehavior;
+
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Report {
+    pub diff_result: DiffResult,
+    pub commit_log_results: Option<CommitLogResults>,
+}
+
+// builds a fresh report every time it's called; it's a closure so that this module doesn't need to
+// know where versions and commit logs come from
+pub type BuildReport = Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<Report>> + Send + Sync>;
+
+pub struct ServeConfig {
+    pub address: SocketAddr,
+    pub refresh_interval: Duration,
+    // the report is also refreshed as soon as any of these files (or files in these directories)
+    // change
+    pub watch_paths: Vec<PathBuf>,
+    pub html_title: String,
+    pub html_template: Option<String>,
+}
+
+struct Rendered {
+    html: String,
+    json: String,
+    refreshed_at: DateTime<Utc>,
+}
+
+struct Snapshot {
+    // the last report that was built successfully; it keeps being served if later refreshes fail
+    rendered: Rendered,
+    last_error: Option<RefreshError>,
+}
+
+#[derive(Clone, Serialize)]
+struct RefreshError {
+    failed_at: DateTime<Utc>,
+    message: String,
+}
+
+type SharedSnapshot = Arc<RwLock<Snapshot>>;
+
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+    last_refreshed_at: DateTime<Utc>,
+    last_error: Option<RefreshError>,
+}
+
+struct Refresher {
+    build_report: BuildReport,
+    html_title: String,
+    html_template: Option<String>,
+}
+
+// serves the report until the process is interrupted; the report is built once before the server
+// starts listening, so that problems with the setup surface right away
+pub async fn serve(config: ServeConfig, build_report: BuildReport) -> anyhow::Result<()> {
+    let refresher = Refresher {
+        build_report,
+        html_title: config.html_title,
+        html_template: config.html_template,
+    };
+
+    let rendered = refresher.build().await?;
+    let snapshot = Arc::new(RwLock::new(Snapshot {
+        rendered,
+        last_error: None,
+    }));
+
+    let listener = tokio::net::TcpListener::bind(config.address)
+        .await
+        .with_context(|| format!("couldn't listen on {}", config.address))?;
+    let address = listener
+        .local_addr()
+        .context("couldn't determine the address being listened on")?;
+    println!("serving report on http://{address} (press ctrl+c to stop)");
+
+    let refresh_task = tokio::spawn(refresh_periodically(
+        refresher,
+        Arc::clone(&snapshot),
+        config.refresh_interval,
+        config.watch_paths,
+    ));
+
+    let result = axum::serve(listener, router(snapshot))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .context("couldn't serve the report");
+
+    refresh_task.abort();
+
+    result
+}
+
+fn router(snapshot: SharedSnapshot) -> Router {
+    Router::new()
+        .route("/", get(get_html_report))
+        .route("/api/report", get(get_json_report))
+        .route("/healthz", get(get_health))
+        .with_state(snapshot)
+}
+
+async fn get_html_report(State(snapshot): State<SharedSnapshot>) -> Html<String> {
+    Html(snapshot.read().await.rendered.html.clone())
+}
+
+async fn get_json_report(State(snapshot): State<SharedSnapshot>) -> Response {
+    let json = snapshot.read().await.rendered.json.clone();
+
+    ([(header::CONTENT_TYPE, "application/json")], json).into_response()
+}
+
+async fn get_health(State(snapshot): State<SharedSnapshot>) -> Response {
+    let snapshot = snapshot.read().await;
+    let (status_code, status) = match snapshot.last_error {
+        Some(_) => (StatusCode::SERVICE_UNAVAILABLE, "failing"),
+        None => (StatusCode::OK, "ok"),
+    };
+
+    let health = Health {
+        status,
+        last_refreshed_at: snapshot.rendered.refreshed_at,
+        last_error: snapshot.last_error.clone(),
+    };
+
+    (status_code, axum::Json(health)).into_response()
+}
+
+async fn refresh_periodically(
+    refresher: Refresher,
+    snapshot: SharedSnapshot,
+    refresh_interval: Duration,
+    watch_paths: Vec<PathBuf>,
+) {
+    let mut refresh_ticker = tokio::time::interval(refresh_interval);
+    refresh_ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // the first tick completes right away, and the report has just been built
+    refresh_ticker.tick().await;
+
+    let mut watch_ticker = tokio::time::interval(WATCH_POLL_INTERVAL);
+    let mut last_modified = get_modified_times(&watch_paths);
+
+    loop {
+        tokio::select! {
+            _ = refresh_ticker.tick() => {}
+            _ = watch_ticker.tick(), if !watch_paths.is_empty() => {
+                let modified = get_modified_times(&watch_paths);
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+                refresh_ticker.reset();
+            }
+        }
+
+        refresher.refresh(&snapshot).await;
+    }
+}
+
+// directories are looked into, so that changes to the files in them are picked up too
+fn get_modified_times(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut modified_times = Vec::new();
+
+    for path in paths {
+        modified_times.push((path.clone(), get_modified_time(path)));
+
+        if let Ok(entries) = std::fs::read_dir(path) {
+            let mut dir_times = entries
+                .filter_map(Result::ok)
+                .map(|entry| {
+                    let entry_path = entry.path();
+                    let modified = get_modified_time(&entry_path);
+                    (entry_path, modified)
+                })
+                .collect::<Vec<_>>();
+            dir_times.sort();
+            modified_times.extend(dir_times);
+        }
+    }
+
+    modified_times
+}
+
+fn get_modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Refresher {
+    async fn build(&self) -> anyhow::Result<Rendered> {
+        let report = (self.build_report)().await?;
+        let now = Utc::now();
+
+        let commit_logs = report
+            .commit_log_results
+            .as_ref()
+            .map(|r| &r.logs[..])
+            .unwrap_or(&[]);
+        let html = view::render_html(
+            &report.diff_result,
+            commit_logs,
+            self.html_template.as_deref(),
+            &self.html_title,
+            now,
+        )?;
+        let json = view::render_json(&report.diff_result, report.commit_log_results.as_ref(), now)?;
+
+        Ok(Rendered {
+            html,
+            json,
+            refreshed_at: now,
+        })
+    }
+
+    async fn refresh(&self, snapshot: &SharedSnapshot) {
+        let result = self.build().await;
+
+        let mut snapshot = snapshot.write().await;
+        match result {
+            Ok(rendered) => {
+                snapshot.rendered = rendered;
+                snapshot.last_error = None;
+            }
+            Err(e) => {
+                let message = format!("{e:#}");
+                eprintln!("Error: couldn't refresh report: {message}");
+                snapshot.last_error = Some(RefreshError {
+                    failed_at: Utc::now(),
+                    message,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AppResult, SyncStatus};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TEST_TEMPLATE: &str =
+        "<h1>{{ title }}</h1>{% for row in rows %}<p>{{ row.data.0 }}</p>{% endfor %}";
+
+    // the first build succeeds, and every build after that fails
+    fn get_flaky_build_report() -> BuildReport {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        Arc::new(move || {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                if call > 0 {
+                    anyhow::bail!("versions file is gone");
+                }
+
+                Ok(Report {
+                    diff_result: DiffResult {
+                        envs: vec!["dev".into(), "prod".into()],
+                        app_results: vec![AppResult {
+                            app: "app-one".into(),
+                            display_name: None,
+                            values: HashMap::from([
+                                ("dev".into(), "1.1.0".into()),
+                                ("prod".into(), "1.1.0".into()),
+                            ]),
+                            sync_status: SyncStatus::InSync,
+                            drifts: vec![],
+                            policy_violations: vec![],
+                            unknown_envs: vec![],
+                            out_of_sync_since: None,
+                        }],
+                        policies_checked: false,
+                        history_used: false,
+                    },
+                    commit_log_results: None,
+                })
+            })
+        })
+    }
+
+    fn get_refresher() -> Refresher {
+        Refresher {
+            build_report: get_flaky_build_report(),
+            html_title: "envee".to_string(),
+            html_template: Some(TEST_TEMPLATE.to_string()),
+        }
+    }
+
+    async fn start_server(snapshot: SharedSnapshot) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(snapshot)).await });
+
+        address
+    }
+
+    async fn get_snapshot(refresher: &Refresher) -> SharedSnapshot {
+        Arc::new(RwLock::new(Snapshot {
+            rendered: refresher.build().await.unwrap(),
+            last_error: None,
+        }))
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[tokio::test]
+    async fn html_report_is_rendered_using_the_custom_template() {
+        // GIVEN
+        let refresher = get_refresher();
+        let address = start_server(get_snapshot(&refresher).await).await;
+
+        // WHEN
+        let response = reqwest::get(format!("http://{address}/")).await.unwrap();
+
+        // THEN
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.text().await.unwrap(),
+            "<h1>envee</h1><p>app-one</p>"
+        );
+    }
+
+    #[tokio::test]
+    async fn json_report_mirrors_the_json_output() {
+        // GIVEN
+        let refresher = get_refresher();
+        let address = start_server(get_snapshot(&refresher).await).await;
+
+        // WHEN
+        let response = reqwest::get(format!("http://{address}/api/report"))
+            .await
+            .unwrap();
+
+        // THEN
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE.as_str()],
+            "application/json"
+        );
+        let report: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(report["schema_version"], view::JSON_SCHEMA_VERSION);
+        assert_eq!(report["apps"][0]["app"], "app-one");
+        assert_eq!(report["apps"][0]["sync_status"], "in_sync");
+    }
+
+    #[tokio::test]
+    async fn health_check_passes_after_a_successful_refresh() {
+        // GIVEN
+        let refresher = get_refresher();
+        let address = start_server(get_snapshot(&refresher).await).await;
+
+        // WHEN
+        let response = reqwest::get(format!("http://{address}/healthz"))
+            .await
+            .unwrap();
+
+        // THEN
+        assert_eq!(response.status(), 200);
+        let health: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(health["status"], "ok");
+        assert!(health["last_error"].is_null());
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[tokio::test]
+    async fn failed_refresh_keeps_serving_the_last_report_and_fails_health_check() {
+        // GIVEN
+        let refresher = get_refresher();
+        let snapshot = get_snapshot(&refresher).await;
+        let address = start_server(Arc::clone(&snapshot)).await;
+
+        // WHEN
+        refresher.refresh(&snapshot).await;
+
+        // THEN
+        let health_response = reqwest::get(format!("http://{address}/healthz"))
+            .await
+            .unwrap();
+        assert_eq!(health_response.status(), 503);
+        let health: serde_json::Value = health_response.json().await.unwrap();
+        assert_eq!(health["status"], "failing");
+        assert_eq!(health["last_error"]["message"], "versions file is gone");
+
+        let html = reqwest::get(format!("http://{address}/"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(html, "<h1>envee</h1><p>app-one</p>");
+    }
+}
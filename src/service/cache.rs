@@ -0,0 +1,378 @@
+use crate::domain::{ApiUrl, Commit, CommitLog, GithubOrg, Provider, Repo};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use etcetera::BaseStrategy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+// bump this whenever the layout of a cache entry changes, so that older entries are ignored
+const CACHE_ENTRY_VERSION: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub struct CommitLogCache {
+    dir: PathBuf,
+    ttl: Duration,
+    // when set, existing entries are ignored, but fresh results are still written to the cache
+    refresh: bool,
+}
+
+// identifies a compare range; commits between two refs don't change as long as the refs don't
+pub(super) struct CacheKey {
+    pub provider: Provider,
+    pub api_url: ApiUrl,
+    pub owner: GithubOrg,
+    pub repo: Repo,
+    pub base: String,
+    pub head: String,
+}
+
+pub(super) struct CachedCommitLog {
+    pub commits: Vec<Commit>,
+    pub html_url: String,
+    pub total_commits: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    version: u32,
+    cached_at: DateTime<Utc>,
+    html_url: String,
+    total_commits: usize,
+    // in the same order as CommitLog.commits, ie, newest first
+    commits: Vec<Commit>,
+}
+
+impl CommitLogCache {
+    pub fn new(dir: PathBuf, ttl: Duration, refresh: bool) -> Self {
+        Self { dir, ttl, refresh }
+    }
+
+    pub fn default_dir() -> anyhow::Result<PathBuf> {
+        let strategy = etcetera::choose_base_strategy()
+            .context("couldn't determine the cache directory for your OS")?;
+
+        Ok(strategy.cache_dir().join("envee").join("commit-logs"))
+    }
+
+    pub(super) fn get(
+        &self,
+        key: &CacheKey,
+        max_commits: usize,
+        now: DateTime<Utc>,
+    ) -> Option<CachedCommitLog> {
+        if self.refresh {
+            return None;
+        }
+
+        // a missing or unreadable entry is treated the same way: as a miss
+        let contents = std::fs::read(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&contents).ok()?;
+
+        if entry.version != CACHE_ENTRY_VERSION {
+            return None;
+        }
+
+        let age = now.signed_duration_since(entry.cached_at).to_std().ok()?;
+        if age > self.ttl {
+            return None;
+        }
+
+        // an entry written with a lower commit limit can't serve a request for more commits
+        let mut commits = entry.commits;
+        if commits.len() < entry.total_commits && commits.len() < max_commits {
+            return None;
+        }
+
+        // the commits closest to the base are at the end
+        if commits.len() > max_commits {
+            commits = commits.split_off(commits.len() - max_commits);
+        }
+
+        Some(CachedCommitLog {
+            commits,
+            html_url: entry.html_url,
+            total_commits: entry.total_commits,
+        })
+    }
+
+    pub(super) fn put(
+        &self,
+        key: &CacheKey,
+        log: &CommitLog,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let entry = CacheEntry {
+            version: CACHE_ENTRY_VERSION,
+            cached_at: now,
+            html_url: log.html_url.clone(),
+            total_commits: log.total_commits,
+            commits: log.commits.clone(),
+        };
+
+        let path = self.entry_path(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {:?}", parent))?;
+        }
+
+        let contents = serde_json::to_vec(&entry).context("failed to serialize cache entry")?;
+
+        // writing to a temporary file first means concurrent runs never see partial entries
+        let tmp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp_path, contents)
+            .with_context(|| format!("failed to write cache entry to {:?}", tmp_path))?;
+        std::fs::rename(&tmp_path, &path)
+            .with_context(|| format!("failed to write cache entry to {:?}", path))?;
+
+        Ok(())
+    }
+
+    // eg. <dir>/github/https%3A%2F%2Fapi.github.com/org/repo/v1.0.0...v1.1.0.json
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        let provider = match key.provider {
+            Provider::Github => "github",
+            Provider::Gitlab => "gitlab",
+            Provider::Local => "local",
+        };
+
+        let mut path = self.dir.join(provider);
+        for component in [key.api_url.as_str(), &key.owner, &key.repo] {
+            path.push(encode_path_component(component));
+        }
+        path.push(format!(
+            "{}...{}.json",
+            encode_path_component(&key.base),
+            encode_path_component(&key.head)
+        ));
+
+        path
+    }
+}
+
+// percent encodes everything that could be problematic in a file name, eg. the slashes in
+// "https://gitlab.example.com/api/v4", "group/subgroup", or "service-x/v1.0.0"
+fn encode_path_component(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => encoded.push(byte as char),
+            b'.' if !encoded.is_empty() => encoded.push('.'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Author, CommitDetail};
+    use chrono::TimeZone;
+
+    fn commit(sha: &str) -> Commit {
+        Commit {
+            sha: sha.to_string(),
+            commit: CommitDetail {
+                message: format!("commit {sha}"),
+                author: Author {
+                    name: "User A".to_string(),
+                    date: Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap(),
+                },
+            },
+            html_url: format!("https://github.com/org/app-a/commit/{sha}"),
+        }
+    }
+
+    fn commit_log(commits: Vec<Commit>, total_commits: usize) -> CommitLog {
+        CommitLog {
+            app: "app-a".into(),
+            display_name: None,
+            from_env: "prod".into(),
+            to_env: "dev".into(),
+            from_version: "1.0.0".into(),
+            to_version: "1.1.0".into(),
+            commits,
+            html_url: "https://github.com/org/app-a/compare/v1.0.0...v1.1.0".to_string(),
+            total_commits,
+            from_cache: false,
+        }
+    }
+
+    fn key() -> CacheKey {
+        CacheKey {
+            provider: Provider::Github,
+            api_url: ApiUrl::parse("github_api_url", "https://api.github.com".into())
+                .expect("API URL should've been valid"),
+            owner: "org".into(),
+            repo: "app-a".into(),
+            base: "v1.0.0".to_string(),
+            head: "v1.1.0".to_string(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 2, 1, 10, 0, 0).unwrap()
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn cached_commit_logs_can_be_read_back() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let cache = CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), false);
+        let key = key();
+        cache
+            .put(
+                &key,
+                &commit_log(vec![commit("bbb"), commit("aaa")], 2),
+                now(),
+            )
+            .expect("result should've been Ok");
+
+        // WHEN
+        let cached = cache
+            .get(&key, 100, now() + chrono::Duration::minutes(30))
+            .expect("entry should've been present");
+
+        // THEN
+        let shas: Vec<_> = cached.commits.iter().map(|c| c.sha.as_str()).collect();
+        assert_eq!(shas, vec!["bbb", "aaa"]);
+        assert_eq!(cached.total_commits, 2);
+        assert_eq!(
+            cached.html_url,
+            "https://github.com/org/app-a/compare/v1.0.0...v1.1.0"
+        );
+    }
+
+    #[test]
+    fn cached_commit_logs_are_limited_to_max_commits() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let cache = CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), false);
+        let key = key();
+        cache
+            .put(
+                &key,
+                &commit_log(vec![commit("ccc"), commit("bbb"), commit("aaa")], 3),
+                now(),
+            )
+            .expect("result should've been Ok");
+
+        // WHEN
+        let cached = cache
+            .get(&key, 2, now())
+            .expect("entry should've been present");
+
+        // THEN
+        let shas: Vec<_> = cached.commits.iter().map(|c| c.sha.as_str()).collect();
+        assert_eq!(shas, vec!["bbb", "aaa"]);
+        assert_eq!(cached.total_commits, 3);
+    }
+
+    #[test]
+    fn cache_entry_paths_are_encoded_correctly() {
+        // GIVEN
+        let cache = CommitLogCache::new("/cache".into(), Duration::from_secs(3600), false);
+        let api_url = ApiUrl::parse("gitlab_api_url", "https://gitlab.example.com/api/v4".into())
+            .expect("API URL should've been valid");
+        let key = CacheKey {
+            provider: Provider::Gitlab,
+            api_url,
+            owner: "group/subgroup".into(),
+            repo: "service-x".into(),
+            base: "service-x/v1.0.0".to_string(),
+            head: "..".to_string(),
+        };
+
+        // WHEN
+        let path = cache.entry_path(&key);
+
+        // THEN
+        assert_eq!(
+            path.to_string_lossy(),
+            "/cache/gitlab/https%3A%2F%2Fgitlab.example.com%2Fapi%2Fv4/group%2Fsubgroup/service-x/service-x%2Fv1.0.0...%2E..json"
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn expired_cache_entries_are_ignored() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let cache = CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), false);
+        let key = key();
+        cache
+            .put(&key, &commit_log(vec![commit("aaa")], 1), now())
+            .expect("result should've been Ok");
+
+        // WHEN
+        let cached = cache.get(&key, 100, now() + chrono::Duration::minutes(61));
+
+        // THEN
+        assert!(cached.is_none());
+    }
+
+    #[test]
+    fn cache_entries_with_fewer_commits_than_requested_are_ignored() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let cache = CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), false);
+        let key = key();
+        cache
+            .put(
+                &key,
+                &commit_log(vec![commit("bbb"), commit("aaa")], 5),
+                now(),
+            )
+            .expect("result should've been Ok");
+
+        // WHEN
+        let cached = cache.get(&key, 3, now());
+
+        // THEN
+        assert!(cached.is_none());
+    }
+
+    #[test]
+    fn cache_entries_are_ignored_when_refreshing() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let key = key();
+        CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), false)
+            .put(&key, &commit_log(vec![commit("aaa")], 1), now())
+            .expect("result should've been Ok");
+        let cache = CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), true);
+
+        // WHEN
+        let cached = cache.get(&key, 100, now());
+
+        // THEN
+        assert!(cached.is_none());
+    }
+
+    #[test]
+    fn corrupt_cache_entries_are_ignored() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let cache = CommitLogCache::new(dir.path().into(), Duration::from_secs(3600), false);
+        let key = key();
+        let path = cache.entry_path(&key);
+        std::fs::create_dir_all(path.parent().expect("path should've had a parent"))
+            .expect("directory should've been created");
+        std::fs::write(&path, "{ not json").expect("file should've been written");
+
+        // WHEN
+        let cached = cache.get(&key, 100, now());
+
+        // THEN
+        assert!(cached.is_none());
+    }
+}
@@ -0,0 +1,286 @@
+use crate::domain::{
+    App, ChangeKind, Env, Version, VersionChange, VersionChanges, VersionDrift, Versions,
+};
+use std::collections::HashMap;
+
+// compares two sets of versions, eg. a versions file as it was yesterday and as it is today, and
+// returns the (app, env) pairs whose version is different in the new one
+pub fn get_version_changes(old: &Versions, new: &Versions) -> VersionChanges {
+    let mut envs = new.envs.clone();
+    envs.extend(old.envs.iter().filter(|e| !new.envs.contains(e)).cloned());
+
+    let old_values = get_values(old);
+    let new_values = get_values(new);
+
+    let mut apps: Vec<&App> = old_values
+        .keys()
+        .chain(new_values.keys())
+        .map(|(app, _)| *app)
+        .collect();
+    apps.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    apps.dedup();
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+
+    for app in apps {
+        let display_name = new
+            .display_name_for(app)
+            .or_else(|| old.display_name_for(app));
+
+        for env in &envs {
+            let old_version = old_values.get(&(app, env)).copied();
+            let new_version = new_values.get(&(app, env)).copied();
+
+            let kind = match (old_version, new_version) {
+                (None, None) => continue,
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(old_version), Some(new_version)) => {
+                    match VersionDrift::between(new_version, old_version) {
+                        None if old_version == new_version => {
+                            unchanged += 1;
+                            continue;
+                        }
+                        Some(VersionDrift::Ahead(_)) => ChangeKind::Upgraded,
+                        Some(VersionDrift::Behind(_)) => ChangeKind::Downgraded,
+                        // versions that only differ in their build metadata have the same
+                        // precedence, but aren't the same version
+                        Some(VersionDrift::Unordered) | None => ChangeKind::Changed,
+                    }
+                }
+            };
+
+            changes.push(VersionChange {
+                app: app.clone(),
+                display_name: display_name.cloned(),
+                env: env.clone(),
+                kind,
+                old_version: old_version.cloned(),
+                new_version: new_version.cloned(),
+            });
+        }
+    }
+
+    VersionChanges {
+        envs,
+        changes,
+        unchanged,
+    }
+}
+
+fn get_values(versions: &Versions) -> HashMap<(&App, &Env), &Version> {
+    versions
+        .versions
+        .iter()
+        .map(|v| ((&v.app, &v.env), &v.version))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RawVersions;
+
+    fn get_versions(contents: &str) -> Versions {
+        let raw: RawVersions = toml::from_str(contents).expect("versions should've been parsed");
+        raw.try_into().expect("versions should've been valid")
+    }
+
+    fn get_summary(changes: &VersionChanges) -> String {
+        let mut lines = changes
+            .changes
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} {} {}: {} -> {}",
+                    c.name(),
+                    c.env,
+                    c.kind,
+                    c.old_version.as_ref().map_or("-", |v| v),
+                    c.new_version.as_ref().map_or("-", |v| v),
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!("unchanged: {}", changes.unchanged));
+
+        lines.join("\n")
+    }
+
+    #[test]
+    fn version_changes_are_computed_correctly() {
+        // GIVEN
+        let old = get_versions(
+            r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+[[versions]]
+app = "app-a"
+env = "dev"
+version = "1.1.0"
+
+[[versions]]
+app = "app-a"
+env = "prod"
+version = "1.0.0"
+
+[[versions]]
+app = "app-b"
+env = "dev"
+version = "2.1.0"
+
+[[versions]]
+app = "app-b"
+env = "prod"
+version = "2.0.0"
+
+[[versions]]
+app = "app-c"
+env = "prod"
+version = "abc1234"
+
+[[versions]]
+app = "app-d"
+env = "prod"
+version = "0.9.0"
+"#,
+        );
+        let new = get_versions(
+            r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+[apps.app-b]
+display_name = "App B"
+
+[[versions]]
+app = "app-a"
+env = "dev"
+version = "1.2.0"
+
+[[versions]]
+app = "app-a"
+env = "prod"
+version = "1.1.0"
+
+[[versions]]
+app = "app-b"
+env = "dev"
+version = "2.0.1"
+
+[[versions]]
+app = "app-b"
+env = "prod"
+version = "2.0.0"
+
+[[versions]]
+app = "app-c"
+env = "prod"
+version = "def5678"
+
+[[versions]]
+app = "app-e"
+env = "dev"
+version = "0.1.0"
+"#,
+        );
+
+        // WHEN
+        let changes = get_version_changes(&old, &new);
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&changes), @r"
+        app-a dev upgraded: 1.1.0 -> 1.2.0
+        app-a prod upgraded: 1.0.0 -> 1.1.0
+        App B dev downgraded: 2.1.0 -> 2.0.1
+        app-c prod changed: abc1234 -> def5678
+        app-d prod removed: 0.9.0 -> -
+        app-e dev added: - -> 0.1.0
+        unchanged: 1
+        ");
+    }
+
+    #[test]
+    fn envs_only_in_the_old_versions_are_compared_too() {
+        // GIVEN
+        let old = get_versions(
+            r#"
+envs = ["dev", "qa", "prod"]
+github_org = "dhth"
+
+[[versions]]
+app = "app-a"
+env = "dev"
+version = "1.0.0"
+
+[[versions]]
+app = "app-a"
+env = "qa"
+version = "1.0.0"
+
+[[versions]]
+app = "app-a"
+env = "prod"
+version = "1.0.0"
+"#,
+        );
+        let new = get_versions(
+            r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+[[versions]]
+app = "app-a"
+env = "dev"
+version = "1.0.0"
+
+[[versions]]
+app = "app-a"
+env = "prod"
+version = "1.0.0"
+"#,
+        );
+
+        // WHEN
+        let changes = get_version_changes(&old, &new);
+
+        // THEN
+        assert_eq!(
+            changes.envs,
+            vec![Env::from("dev"), Env::from("prod"), Env::from("qa")]
+        );
+        insta::assert_snapshot!(get_summary(&changes), @r"
+        app-a qa removed: 1.0.0 -> -
+        unchanged: 2
+        ");
+    }
+
+    #[test]
+    fn identical_versions_have_no_changes() {
+        // GIVEN
+        let contents = r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+[[versions]]
+app = "app-a"
+env = "dev"
+version = "1.0.0+build.1"
+
+[[versions]]
+app = "app-a"
+env = "prod"
+version = "1.0.0"
+"#;
+        let old = get_versions(contents);
+        let new = get_versions(contents);
+
+        // WHEN
+        let changes = get_version_changes(&old, &new);
+
+        // THEN
+        assert!(changes.changes.is_empty());
+        assert_eq!(changes.unchanged, 2);
+    }
+}
@@ -0,0 +1,393 @@
+use super::cache::{CacheKey, CommitLogCache};
+use super::http::RetryPolicy;
+use super::{github, gitlab, local};
+use crate::domain::{
+    ApiUrl, App, AppResult, ChangeKind, CommitLog, CommitLogFetchErrors, CommitLogResults,
+    DiffResult, DisplayName, Env, EnvPair, GitTagTransform, GithubOrg, Provider, Repo, Version,
+    VersionChanges, Versions,
+};
+use chrono::Utc;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+const MAX_CONCURRENT_FETCHES: usize = 20;
+
+pub struct FetchCommitLogParams {
+    pub owner: GithubOrg,
+    pub repo: Repo,
+    pub app: App,
+    pub from_env: Env,
+    pub to_env: Env,
+    pub from_version: Version,
+    pub to_version: Version,
+    pub tag_transform: Option<GitTagTransform>,
+    pub max_commits: usize,
+}
+
+impl FetchCommitLogParams {
+    // returns the git refs to compare, ie, (base, head)
+    pub(super) fn refs(&self) -> (String, String) {
+        match self.tag_transform {
+            Some(ref template) => (
+                build_tag(template, &self.from_version),
+                build_tag(template, &self.to_version),
+            ),
+            None => (self.from_version.to_string(), self.to_version.to_string()),
+        }
+    }
+}
+
+// where a commit log is fetched from, along with whatever is needed to access it
+enum CommitLogSource {
+    Github { api_url: ApiUrl, token: String },
+    Gitlab { api_url: ApiUrl, token: String },
+    Local { repo_path: PathBuf },
+}
+
+impl CommitLogSource {
+    fn for_app(versions: &Versions, tokens: &ProviderTokens, app: &App) -> anyhow::Result<Self> {
+        let provider = versions.provider_for(app);
+        let api_url = || {
+            versions
+                .api_url_for(app)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("no API URL configured for {provider}"))
+        };
+        let token = || {
+            tokens
+                .get(provider)
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("no token available for {provider}"))
+        };
+
+        match provider {
+            Provider::Github => Ok(Self::Github {
+                api_url: api_url()?,
+                token: token()?,
+            }),
+            Provider::Gitlab => Ok(Self::Gitlab {
+                api_url: api_url()?,
+                token: token()?,
+            }),
+            Provider::Local => versions
+                .local_repo_path_for(app)
+                .map(|repo_path| Self::Local { repo_path })
+                .ok_or_else(|| anyhow::anyhow!("no local repository path configured")),
+        }
+    }
+
+    // reading from a local repository is about as fast as reading from the cache, and branches in
+    // it can move around, so only remote commit logs are cached
+    fn cache_key(&self, params: &FetchCommitLogParams) -> Option<CacheKey> {
+        let (provider, api_url) = match self {
+            Self::Github { api_url, .. } => (Provider::Github, api_url),
+            Self::Gitlab { api_url, .. } => (Provider::Gitlab, api_url),
+            Self::Local { .. } => return None,
+        };
+        let (base, head) = params.refs();
+
+        Some(CacheKey {
+            provider,
+            api_url: api_url.clone(),
+            owner: params.owner.clone(),
+            repo: params.repo.clone(),
+            base,
+            head,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProviderTokens {
+    pub github: Option<String>,
+    pub gitlab: Option<String>,
+}
+
+impl ProviderTokens {
+    fn get(&self, provider: Provider) -> Option<&str> {
+        match provider {
+            Provider::Github => self.github.as_deref(),
+            Provider::Gitlab => self.gitlab.as_deref(),
+            // local repositories don't need a token
+            Provider::Local => None,
+        }
+    }
+}
+
+// a commit log to fetch; fetched logs are sorted by app, and then by `order`
+struct FetchJob {
+    order: usize,
+    params: FetchCommitLogParams,
+    display_name: Option<DisplayName>,
+}
+
+pub async fn fetch_commit_logs(
+    diff_result: &DiffResult,
+    versions: &Versions,
+    tokens: &ProviderTokens,
+    max_commits: usize,
+    cache: Option<&CommitLogCache>,
+    retry_policy: RetryPolicy,
+) -> CommitLogResults {
+    let env_pairs = versions.comparison_strategy.env_pairs(&diff_result.envs);
+
+    let jobs = diff_result
+        .app_results
+        .iter()
+        .filter(|row| row.sync_status.is_out_of_sync())
+        .flat_map(|row| {
+            env_pairs
+                .iter()
+                .enumerate()
+                .filter_map(move |(pair_index, pair)| {
+                    get_fetch_params(row, pair, versions, max_commits).map(|params| FetchJob {
+                        order: pair_index,
+                        params,
+                        display_name: row.display_name.clone(),
+                    })
+                })
+        })
+        .collect();
+
+    fetch_all(jobs, versions, tokens, cache, retry_policy).await
+}
+
+// fetches the commit log for every upgrade between two versions files; the commits an app was
+// upgraded by in an env are the ones between its old and new version
+pub async fn fetch_commit_logs_for_changes(
+    changes: &VersionChanges,
+    versions: &Versions,
+    tokens: &ProviderTokens,
+    max_commits: usize,
+    cache: Option<&CommitLogCache>,
+    retry_policy: RetryPolicy,
+) -> CommitLogResults {
+    let jobs = changes
+        .changes
+        .iter()
+        .enumerate()
+        .filter(|(_, change)| change.kind == ChangeKind::Upgraded)
+        .filter_map(|(index, change)| {
+            let from_version = change.old_version.clone()?;
+            let to_version = change.new_version.clone()?;
+
+            Some(FetchJob {
+                order: index,
+                params: FetchCommitLogParams {
+                    owner: versions.github_org_for(&change.app).clone(),
+                    repo: versions.repo_for(&change.app),
+                    app: change.app.clone(),
+                    from_env: change.env.clone(),
+                    to_env: change.env.clone(),
+                    from_version,
+                    to_version,
+                    tag_transform: versions.git_tag_transform_for(&change.app).cloned(),
+                    max_commits,
+                },
+                display_name: change.display_name.clone(),
+            })
+        })
+        .collect();
+
+    fetch_all(jobs, versions, tokens, cache, retry_policy).await
+}
+
+async fn fetch_all(
+    jobs: Vec<FetchJob>,
+    versions: &Versions,
+    tokens: &ProviderTokens,
+    cache: Option<&CommitLogCache>,
+    retry_policy: RetryPolicy,
+) -> CommitLogResults {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let mut futures = FuturesUnordered::new();
+    let mut errors = CommitLogFetchErrors::new();
+
+    for job in jobs {
+        let FetchJob {
+            order,
+            params,
+            display_name,
+        } = job;
+        let pair = EnvPair {
+            from: params.from_env.clone(),
+            to: params.to_env.clone(),
+        };
+
+        let source = match CommitLogSource::for_app(versions, tokens, &params.app) {
+            Ok(source) => source,
+            Err(e) => {
+                errors.add_app_error(params.app, pair, e);
+                continue;
+            }
+        };
+
+        let semaphore = Arc::clone(&semaphore);
+        let cache = cache.cloned();
+        let app_clone = params.app.clone();
+
+        futures.push(tokio::task::spawn(async move {
+            let permit = semaphore.acquire().await;
+            if let Err(e) = permit {
+                return (
+                    app_clone,
+                    pair,
+                    order,
+                    Err(anyhow::anyhow!("couldn't acquire semaphore: {e}")),
+                );
+            }
+
+            let result = fetch_commit_log(source, params, cache.as_ref(), retry_policy)
+                .await
+                .map(|log| CommitLog {
+                    display_name,
+                    ..log
+                });
+
+            (app_clone, pair, order, result)
+        }));
+    }
+
+    let mut commit_logs = Vec::new();
+
+    while let Some(task_result) = futures.next().await {
+        match task_result {
+            Ok((_app, _pair, order, Ok(log))) => commit_logs.push((order, log)),
+            Ok((app, pair, _order, Err(e))) => {
+                errors.add_app_error(app, pair, e);
+            }
+            Err(e) => {
+                errors.add_system_error(anyhow::anyhow!("task panicked: {e}"));
+            }
+        }
+    }
+
+    // logs are shown in the same order as apps are, ie. by display name
+    commit_logs.sort_by(|(a_order, a), (b_order, b)| {
+        a.name()
+            .cmp(b.name())
+            .then_with(|| a.app.cmp(&b.app))
+            .then_with(|| a_order.cmp(b_order))
+    });
+
+    CommitLogResults {
+        logs: commit_logs.into_iter().map(|(_, log)| log).collect(),
+        errors,
+    }
+}
+
+// fetches the commit log for a single app and env pair; returns None if the app doesn't have
+// different versions in both envs
+pub async fn fetch_commit_log_for_app(
+    row: &AppResult,
+    pair: &EnvPair,
+    versions: &Versions,
+    tokens: &ProviderTokens,
+    max_commits: usize,
+    cache: Option<&CommitLogCache>,
+    retry_policy: RetryPolicy,
+) -> anyhow::Result<Option<CommitLog>> {
+    let Some(params) = get_fetch_params(row, pair, versions, max_commits) else {
+        return Ok(None);
+    };
+    let source = CommitLogSource::for_app(versions, tokens, &row.app)?;
+
+    let log = fetch_commit_log(source, params, cache, retry_policy).await?;
+
+    Ok(Some(CommitLog {
+        display_name: row.display_name.clone(),
+        ..log
+    }))
+}
+
+fn get_fetch_params(
+    row: &AppResult,
+    pair: &EnvPair,
+    versions: &Versions,
+    max_commits: usize,
+) -> Option<FetchCommitLogParams> {
+    let from_version = row.values.get(&pair.from).cloned()?;
+    let to_version = row.values.get(&pair.to).cloned()?;
+
+    if from_version == to_version {
+        return None;
+    }
+
+    Some(FetchCommitLogParams {
+        owner: versions.github_org_for(&row.app).clone(),
+        repo: versions.repo_for(&row.app),
+        app: row.app.clone(),
+        from_env: pair.from.clone(),
+        to_env: pair.to.clone(),
+        from_version,
+        to_version,
+        tag_transform: versions.git_tag_transform_for(&row.app).cloned(),
+        max_commits,
+    })
+}
+
+async fn fetch_commit_log(
+    source: CommitLogSource,
+    params: FetchCommitLogParams,
+    cache: Option<&CommitLogCache>,
+    retry_policy: RetryPolicy,
+) -> anyhow::Result<CommitLog> {
+    let cache_key = cache.and_then(|_| source.cache_key(&params));
+
+    if let (Some(cache), Some(key)) = (cache, &cache_key)
+        && let Some(cached) = cache.get(key, params.max_commits, Utc::now())
+    {
+        return Ok(CommitLog {
+            app: params.app,
+            display_name: None,
+            from_env: params.from_env,
+            to_env: params.to_env,
+            from_version: params.from_version,
+            to_version: params.to_version,
+            commits: cached.commits,
+            html_url: cached.html_url,
+            total_commits: cached.total_commits,
+            from_cache: true,
+        });
+    }
+
+    let log = match source {
+        CommitLogSource::Github { api_url, token } => {
+            github::fetch_commit_log(params, &api_url, &token, retry_policy).await?
+        }
+        CommitLogSource::Gitlab { api_url, token } => {
+            gitlab::fetch_commit_log(params, &api_url, &token, retry_policy).await?
+        }
+        CommitLogSource::Local { repo_path } => local::fetch_commit_log(params, &repo_path).await?,
+    };
+
+    // not being able to write to the cache only means that the next run will be slower, which
+    // isn't worth failing over
+    if let (Some(cache), Some(key)) = (cache, &cache_key) {
+        let _ = cache.put(key, &log, Utc::now());
+    }
+
+    Ok(log)
+}
+
+fn build_tag(template: &str, version: &str) -> String {
+    template.replacen("{{version}}", version, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn building_tag_works() {
+        assert_eq!(build_tag("v{{version}}", "0.83.0"), "v0.83.0");
+        assert_eq!(build_tag("release-{{version}}", "1.2.3"), "release-1.2.3");
+        assert_eq!(build_tag("{{version}}", "2.0.0"), "2.0.0");
+        assert_eq!(
+            build_tag("v{{version}}-{{version}}", "2.0.0"),
+            "v2.0.0-{{version}}"
+        );
+    }
+}
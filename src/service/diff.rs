@@ -1,5 +1,9 @@
-use crate::domain::{App, AppResult, AppVersion, DiffResult, Env, SyncStatus, Version};
-use std::collections::{HashMap, HashSet};
+use crate::config::SortBy;
+use crate::domain::{
+    App, AppResult, AppVersion, DiffResult, Env, EnvDrift, SyncStatus, Version, VersionDrift,
+    Versions,
+};
+use std::collections::HashMap;
 
 // Note: TryFrom<RawVersions> for Versions should ensure that every AppVersion.env is present in
 // the envs Vec
@@ -14,24 +18,35 @@ pub fn get_diff_result(envs: Vec<Env>, versions: &Vec<AppVersion>) -> DiffResult
     }
 
     for (app, env_to_version) in app_data {
+        let drifts = get_drifts(&envs, &env_to_version);
+
         let sync_status = if env_to_version.len() == 1 {
             SyncStatus::NotApplicable
-        } else if envs
+        } else if drifts.is_empty() {
+            SyncStatus::InSync
+        } else if drifts
             .iter()
-            .filter_map(|env| env_to_version.get(env))
-            .collect::<HashSet<&Version>>()
-            .len()
-            == 1
+            .any(|d| matches!(d.drift, VersionDrift::Ahead(_)))
         {
-            SyncStatus::InSync
+            SyncStatus::Ahead
+        } else if drifts
+            .iter()
+            .any(|d| matches!(d.drift, VersionDrift::Behind(_)))
+        {
+            SyncStatus::Behind
         } else {
             SyncStatus::OutOfSync
         };
 
         rows.push(AppResult {
             app,
+            display_name: None,
             values: env_to_version,
             sync_status,
+            drifts,
+            policy_violations: vec![],
+            unknown_envs: vec![],
+            out_of_sync_since: None,
         });
     }
 
@@ -40,9 +55,102 @@ pub fn get_diff_result(envs: Vec<Env>, versions: &Vec<AppVersion>) -> DiffResult
     DiffResult {
         envs,
         app_results: rows,
+        policies_checked: false,
+        history_used: false,
+    }
+}
+
+// versions that couldn't be fetched are shown as unknown; apps without any known versions still get
+// a row, so that they don't silently go missing from the results
+pub fn add_unknown_versions<'a>(
+    diff_result: &mut DiffResult,
+    unknown_versions: impl IntoIterator<Item = (&'a App, &'a Env)>,
+) {
+    for (app, env) in unknown_versions {
+        match diff_result
+            .app_results
+            .iter_mut()
+            .find(|row| &row.app == app)
+        {
+            Some(row) => row.unknown_envs.push(env.clone()),
+            None => diff_result.app_results.push(AppResult {
+                app: app.clone(),
+                display_name: None,
+                values: HashMap::new(),
+                sync_status: SyncStatus::NotApplicable,
+                drifts: vec![],
+                policy_violations: vec![],
+                unknown_envs: vec![env.clone()],
+                out_of_sync_since: None,
+            }),
+        }
+    }
+
+    diff_result.app_results.sort_by(|a, b| a.app.cmp(&b.app));
+}
+
+pub fn add_display_names(diff_result: &mut DiffResult, versions: &Versions) {
+    for row in &mut diff_result.app_results {
+        row.display_name = versions.display_name_for(&row.app).cloned();
     }
 }
 
+pub fn filter_by_sync_status(diff_result: &mut DiffResult, statuses: &[SyncStatus]) {
+    if statuses.is_empty() {
+        return;
+    }
+
+    diff_result
+        .app_results
+        .retain(|row| statuses.contains(&row.sync_status));
+}
+
+pub fn sort_app_results(diff_result: &mut DiffResult, sort_by: SortBy) {
+    match sort_by {
+        SortBy::App => diff_result
+            .app_results
+            .sort_by(|a, b| a.name().cmp(b.name())),
+        SortBy::Status => diff_result.app_results.sort_by(|a, b| {
+            status_rank(&a.sync_status)
+                .cmp(&status_rank(&b.sync_status))
+                .then_with(|| a.name().cmp(b.name()))
+        }),
+    }
+}
+
+// statuses that most likely need attention come first
+fn status_rank(status: &SyncStatus) -> u8 {
+    match status {
+        SyncStatus::Ahead => 0,
+        SyncStatus::Behind => 1,
+        SyncStatus::OutOfSync => 2,
+        SyncStatus::InSync => 3,
+        SyncStatus::NotApplicable => 4,
+    }
+}
+
+// every env is compared against the first env (in the order of envs) that the app has a version
+// for
+fn get_drifts(envs: &[Env], env_to_version: &HashMap<Env, Version>) -> Vec<EnvDrift> {
+    let mut versions = envs
+        .iter()
+        .filter_map(|env| env_to_version.get(env).map(|version| (env, version)));
+
+    let Some((reference_env, reference_version)) = versions.next() else {
+        return vec![];
+    };
+
+    versions
+        .filter_map(|(env, version)| {
+            VersionDrift::between(version, reference_version).map(|drift| EnvDrift {
+                env: env.clone(),
+                reference_env: reference_env.clone(),
+                drift,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,19 +201,41 @@ mod tests {
               - prod
             app_results:
               - app: app1
+                display_name: ~
                 values:
                   dev: 1.0.0
                   prod: 1.0.0
                 sync_status: in_sync
+                drifts: []
+                policy_violations: []
+                unknown_envs: []
+                out_of_sync_since: ~
               - app: app2
+                display_name: ~
                 values:
                   dev: 2.0.0
                   prod: 1.9.0
-                sync_status: out_of_sync
+                sync_status: behind
+                drifts:
+                  - env: prod
+                    reference_env: dev
+                    drift:
+                      behind:
+                        major: 1
+                policy_violations: []
+                unknown_envs: []
+                out_of_sync_since: ~
               - app: app3
+                display_name: ~
                 values:
                   dev: 0.1.0
                 sync_status: not_applicable
+                drifts: []
+                policy_violations: []
+                unknown_envs: []
+                out_of_sync_since: ~
+            policies_checked: false
+            history_used: false
             ");
         });
     }
@@ -140,10 +270,17 @@ mod tests {
               - prod
             app_results:
               - app: app1
+                display_name: ~
                 values:
                   dev: 1.0.0
                   prod: 1.0.0
                 sync_status: in_sync
+                drifts: []
+                policy_violations: []
+                unknown_envs: []
+                out_of_sync_since: ~
+            policies_checked: false
+            history_used: false
             ");
         });
     }
@@ -181,15 +318,214 @@ mod tests {
               - staging
             app_results:
               - app: multi-env-in-sync
+                display_name: ~
                 values:
                   dev: 1.0.0
                   prod: 1.0.0
                 sync_status: in_sync
+                drifts: []
+                policy_violations: []
+                unknown_envs: []
+                out_of_sync_since: ~
               - app: single-env-app
+                display_name: ~
                 values:
                   staging: 2.0.0
                 sync_status: not_applicable
+                drifts: []
+                policy_violations: []
+                unknown_envs: []
+                out_of_sync_since: ~
+            policies_checked: false
+            history_used: false
+            ");
+        });
+    }
+
+    #[test]
+    fn unknown_versions_are_added_to_results() {
+        // GIVEN
+        let envs = vec!["dev".into(), "prod".into()];
+        let versions = vec![AppVersion {
+            app: "app1".into(),
+            env: "dev".into(),
+            version: "1.0.0".into(),
+        }];
+        let mut result = get_diff_result(envs, &versions);
+        let unknown: Vec<(App, Env)> = vec![
+            ("app1".into(), "prod".into()),
+            ("app0".into(), "dev".into()),
+            ("app0".into(), "prod".into()),
+        ];
+
+        // WHEN
+        add_unknown_versions(&mut result, unknown.iter().map(|(app, env)| (app, env)));
+
+        // THEN
+        let mut settings = insta::Settings::clone_current();
+        settings.set_sort_maps(true);
+        settings.bind(|| {
+            insta::assert_yaml_snapshot!(result, @r"
+            envs:
+              - dev
+              - prod
+            app_results:
+              - app: app0
+                display_name: ~
+                values: {}
+                sync_status: not_applicable
+                drifts: []
+                policy_violations: []
+                unknown_envs:
+                  - dev
+                  - prod
+                out_of_sync_since: ~
+              - app: app1
+                display_name: ~
+                values:
+                  dev: 1.0.0
+                sync_status: not_applicable
+                drifts: []
+                policy_violations: []
+                unknown_envs:
+                  - prod
+                out_of_sync_since: ~
+            policies_checked: false
+            history_used: false
             ");
         });
     }
+
+    #[test]
+    fn ahead_and_behind_statuses_are_computed_correctly() {
+        // GIVEN
+        let envs = vec!["dev".into(), "staging".into(), "prod".into()];
+        let version = |app: &str, env: &str, version: &str| AppVersion {
+            app: app.into(),
+            env: env.into(),
+            version: version.into(),
+        };
+        let versions = vec![
+            version("behind", "dev", "1.2.0"),
+            version("behind", "staging", "1.2.0"),
+            version("behind", "prod", "1.0.0"),
+            version("ahead", "dev", "1.2.0"),
+            version("ahead", "staging", "1.2.1"),
+            version("ahead", "prod", "1.1.0"),
+            version("non-semver", "dev", "abc1234"),
+            version("non-semver", "prod", "def5678"),
+            version("equal-semver", "dev", "v2.0"),
+            version("equal-semver", "prod", "2.0.0"),
+            version("no-dev", "staging", "3.0.0"),
+            version("no-dev", "prod", "2.9.0"),
+        ];
+
+        // WHEN
+        let result = get_diff_result(envs, &versions);
+
+        // THEN
+        let summary = result
+            .app_results
+            .iter()
+            .map(|row| {
+                format!(
+                    "{}: {:?} [{}]",
+                    row.app,
+                    row.sync_status,
+                    row.drifts
+                        .iter()
+                        .map(|d| d.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(summary, @r"
+        ahead: Ahead [staging 1 patch ahead of dev, prod 1 minor behind dev]
+        behind: Behind [prod 2 minors behind dev]
+        equal-semver: InSync []
+        no-dev: Behind [prod 1 major behind staging]
+        non-semver: OutOfSync [prod differs from dev]
+        ");
+    }
+
+    #[test]
+    fn filtering_by_sync_status_works() {
+        // GIVEN
+        let mut result = get_test_diff_result();
+
+        // WHEN
+        filter_by_sync_status(
+            &mut result,
+            &[SyncStatus::Behind, SyncStatus::NotApplicable],
+        );
+
+        // THEN
+        let apps: Vec<_> = result.app_results.iter().map(|r| r.app.as_str()).collect();
+        assert_eq!(apps, vec!["app2", "app3"]);
+    }
+
+    #[test]
+    fn filtering_by_no_sync_statuses_keeps_all_apps() {
+        // GIVEN
+        let mut result = get_test_diff_result();
+
+        // WHEN
+        filter_by_sync_status(&mut result, &[]);
+
+        // THEN
+        assert_eq!(result.app_results.len(), 4);
+    }
+
+    #[test]
+    fn sorting_by_status_works() {
+        // GIVEN
+        let mut result = get_test_diff_result();
+
+        // WHEN
+        sort_app_results(&mut result, SortBy::Status);
+
+        // THEN
+        let apps: Vec<_> = result.app_results.iter().map(|r| r.app.as_str()).collect();
+        assert_eq!(apps, vec!["app4", "app2", "app1", "app3"]);
+    }
+
+    #[test]
+    fn sorting_by_app_uses_display_names() {
+        // GIVEN
+        let mut result = get_test_diff_result();
+        for row in &mut result.app_results {
+            if row.app.as_str() == "app4" {
+                row.display_name = Some("a-service".into());
+            }
+        }
+
+        // WHEN
+        sort_app_results(&mut result, SortBy::App);
+
+        // THEN
+        let apps: Vec<_> = result.app_results.iter().map(|r| r.name()).collect();
+        assert_eq!(apps, vec!["a-service", "app1", "app2", "app3"]);
+    }
+
+    fn get_test_diff_result() -> DiffResult {
+        let envs = vec!["dev".into(), "prod".into()];
+        let version = |app: &str, env: &str, version: &str| AppVersion {
+            app: app.into(),
+            env: env.into(),
+            version: version.into(),
+        };
+        let versions = vec![
+            version("app1", "dev", "1.0.0"),
+            version("app1", "prod", "1.0.0"),
+            version("app2", "dev", "2.0.0"),
+            version("app2", "prod", "1.9.0"),
+            version("app3", "dev", "0.1.0"),
+            version("app4", "dev", "1.0.0"),
+            version("app4", "prod", "1.1.0"),
+        ];
+
+        get_diff_result(envs, &versions)
+    }
 }
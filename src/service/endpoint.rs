@@ -0,0 +1,406 @@
+use crate::domain::{App, AppVersion, Endpoint, Env, Version, VersionLocation};
+use anyhow::Context;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+const MAX_CONCURRENT_FETCHES: usize = 20;
+
+pub struct EndpointResults {
+    pub versions: Vec<AppVersion>,
+    pub errors: EndpointFetchErrors,
+}
+
+#[derive(Debug, Default)]
+pub struct EndpointFetchErrors {
+    errors: Vec<(App, Env, anyhow::Error)>,
+}
+
+impl EndpointFetchErrors {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    // the (app, env) pairs whose version couldn't be fetched
+    pub fn unknown_versions(&self) -> impl Iterator<Item = (&App, &Env)> {
+        self.errors.iter().map(|(app, env, _)| (app, env))
+    }
+}
+
+impl std::fmt::Display for EndpointFetchErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "couldn't fetch versions from endpoints:")?;
+
+        for (app, env, error) in &self.errors {
+            writeln!(f, " - {app} ({env}): {error:#}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// endpoints are fetched concurrently; an endpoint failing doesn't affect the others, and is
+// reported in the errors instead
+pub async fn fetch_endpoint_versions(endpoints: &[Endpoint]) -> EndpointResults {
+    let mut versions = Vec::new();
+    let mut errors = EndpointFetchErrors::default();
+
+    if endpoints.is_empty() {
+        return EndpointResults { versions, errors };
+    }
+
+    let client = match reqwest::Client::builder()
+        .build()
+        .context("failed to build HTTP client")
+    {
+        Ok(client) => client,
+        Err(e) => {
+            let message = format!("{e:#}");
+            errors.errors = endpoints
+                .iter()
+                .map(|endpoint| {
+                    (
+                        endpoint.app.clone(),
+                        endpoint.env.clone(),
+                        anyhow::anyhow!("{message}"),
+                    )
+                })
+                .collect();
+            return EndpointResults { versions, errors };
+        }
+    };
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let mut futures = FuturesUnordered::new();
+
+    for endpoint in endpoints {
+        let semaphore = Arc::clone(&semaphore);
+        let client = client.clone();
+        let endpoint = endpoint.clone();
+
+        let (app, env) = (endpoint.app.clone(), endpoint.env.clone());
+
+        let handle = tokio::task::spawn(async move {
+            match semaphore.acquire().await {
+                Ok(_permit) => fetch_version(&client, &endpoint).await,
+                Err(e) => Err(anyhow::anyhow!("couldn't acquire semaphore: {e}")),
+            }
+        });
+        // the endpoint is kept outside the task, so that its version can be marked as unknown even
+        // if the task panics
+        futures.push(async move { (app, env, handle.await) });
+    }
+
+    while let Some((app, env, task_result)) = futures.next().await {
+        match task_result {
+            Ok(Ok(version)) => versions.push(AppVersion { app, env, version }),
+            Ok(Err(e)) => errors.errors.push((app, env, e)),
+            Err(e) => errors
+                .errors
+                .push((app, env, anyhow::anyhow!("task panicked: {e}"))),
+        }
+    }
+
+    // results arrive in the order the fetches finish in
+    versions.sort_by(|a, b| a.app.cmp(&b.app).then_with(|| a.env.cmp(&b.env)));
+    errors
+        .errors
+        .sort_by(|(a_app, a_env, _), (b_app, b_env, _)| {
+            a_app.cmp(b_app).then_with(|| a_env.cmp(b_env))
+        });
+
+    EndpointResults { versions, errors }
+}
+
+async fn fetch_version(client: &reqwest::Client, endpoint: &Endpoint) -> anyhow::Result<Version> {
+    let response = client
+        .get(&endpoint.url)
+        .header("User-Agent", "envee@v0.1.0")
+        .timeout(endpoint.timeout)
+        .send()
+        .await
+        .with_context(|| format!("request to {} failed", endpoint.url))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("{} responded with status {}", endpoint.url, status);
+    }
+
+    let body = response
+        .text()
+        .await
+        .with_context(|| format!("couldn't read response from {}", endpoint.url))?;
+
+    let version = get_version(&body, &endpoint.location)?;
+
+    Version::try_from(version).map_err(|e| anyhow::anyhow!("{}: {e}", endpoint.location))
+}
+
+fn get_version(body: &str, location: &VersionLocation) -> anyhow::Result<String> {
+    let version = match location {
+        VersionLocation::JsonPointer(pointer) => {
+            let json: serde_json::Value =
+                serde_json::from_str(body).context("response is not valid JSON")?;
+            match json.pointer(pointer) {
+                Some(serde_json::Value::String(s)) => Some(s.clone()),
+                Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+                _ => None,
+            }
+        }
+        VersionLocation::Pattern(pattern) => pattern.find(body).map(String::from),
+    };
+
+    version.with_context(|| format!("{location} didn't match a version in the response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{RawVersions, Versions};
+
+    fn get_endpoints(raw_endpoints: &str) -> Vec<Endpoint> {
+        let raw: RawVersions = toml::from_str(&format!(
+            r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+{raw_endpoints}
+"#
+        ))
+        .expect("versions should've been parsed");
+        let versions: Versions = raw.try_into().expect("versions should've been valid");
+
+        versions.endpoints
+    }
+
+    fn get_summary(results: &EndpointResults, server_url: &str) -> String {
+        let mut lines = results
+            .versions
+            .iter()
+            .map(|v| format!("{} {} {}", v.app, v.env, v.version))
+            .collect::<Vec<_>>();
+        if !results.errors.is_empty() {
+            lines.push(results.errors.to_string().replace(server_url, "<SERVER>"));
+        }
+
+        lines.join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[tokio::test]
+    async fn fetching_versions_from_endpoints_works() {
+        // GIVEN
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/app-a/actuator/info")
+            .with_status(200)
+            .with_body(r#"{"build": {"version": "1.2.0", "time": "2025-01-15T10:00:00Z"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/app-a/prod/version")
+            .with_status(200)
+            .with_body("app-a version: 1.1.0 (abc1234)")
+            .create_async()
+            .await;
+        let url = server.url();
+        let endpoints = get_endpoints(&format!(
+            r#"
+[endpoints.app-a.dev]
+url = "{url}/app-a/actuator/info"
+json_pointer = "/build/version"
+
+[endpoints.app-a.prod]
+url = "{url}/app-a/prod/version"
+pattern = 'version: (?P<version>\S+)'
+"#
+        ));
+
+        // WHEN
+        let results = fetch_endpoint_versions(&endpoints).await;
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&results, &url), @r"
+        app-a dev 1.2.0
+        app-a prod 1.1.0
+        ");
+    }
+
+    #[test]
+    fn getting_version_from_json_numbers_works() {
+        // GIVEN
+        let location = VersionLocation::JsonPointer("/version".to_string());
+
+        // WHEN
+        let version =
+            get_version(r#"{"version": 42}"#, &location).expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(version, "42");
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[tokio::test]
+    async fn failing_endpoints_are_reported_without_affecting_others() {
+        // GIVEN
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/app-a/version")
+            .with_status(200)
+            .with_body(r#"{"version": "1.2.0"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/app-b/version")
+            .with_status(503)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/app-c/version")
+            .with_status(200)
+            .with_body("<html>maintenance</html>")
+            .create_async()
+            .await;
+        let url = server.url();
+        let endpoints = get_endpoints(&format!(
+            r#"
+[endpoints.app-a.dev]
+url = "{url}/app-a/version"
+json_pointer = "/version"
+
+[endpoints.app-b.dev]
+url = "{url}/app-b/version"
+json_pointer = "/version"
+
+[endpoints.app-c.dev]
+url = "{url}/app-c/version"
+json_pointer = "/version"
+
+[endpoints.app-c.prod]
+url = "{url}/app-c/version"
+pattern = 'v(?P<version>\d+\.\d+\.\d+)'
+"#
+        ));
+
+        // WHEN
+        let results = fetch_endpoint_versions(&endpoints).await;
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&results, &url), @r#"
+        app-a dev 1.2.0
+        couldn't fetch versions from endpoints:
+         - app-b (dev): <SERVER>/app-b/version responded with status 503 Service Unavailable
+         - app-c (dev): response is not valid JSON: expected value at line 1 column 1
+         - app-c (prod): pattern "v(?P<version>\d+\.\d+\.\d+)" didn't match a version in the response
+        "#);
+        assert_eq!(
+            results
+                .errors
+                .unknown_versions()
+                .map(|(app, env)| format!("{app}/{env}"))
+                .collect::<Vec<_>>(),
+            vec!["app-b/dev", "app-c/dev", "app-c/prod"]
+        );
+    }
+
+    #[tokio::test]
+    async fn slow_endpoints_time_out() {
+        // GIVEN
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should've been bound");
+        let address = listener.local_addr().expect("address should've been known");
+        // accepts connections but never responds
+        let _server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+        let endpoints = get_endpoints(&format!(
+            r#"
+[endpoints.app-a.dev]
+url = "http://{address}/version"
+json_pointer = "/version"
+timeout = "200ms"
+
+[endpoints.app-a.prod]
+url = "http://{address}/version"
+json_pointer = "/version"
+timeout = "200ms"
+"#
+        ));
+
+        // WHEN
+        let results = fetch_endpoint_versions(&endpoints).await;
+
+        // THEN
+        assert!(results.versions.is_empty());
+        let errors = results.errors.to_string();
+        assert!(errors.contains("app-a (dev)"), "errors: {errors}");
+        assert!(errors.contains("app-a (prod)"), "errors: {errors}");
+        assert!(errors.contains("timed out"), "errors: {errors}");
+    }
+
+    #[test]
+    fn invalid_endpoints_fail_validation() {
+        // GIVEN
+        let raw: RawVersions = toml::from_str(
+            r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+[[versions]]
+app = "app-a"
+env = "dev"
+version = "1.0.0"
+
+[endpoints.app-a.dev]
+url = "https://app-a.example.com/version"
+json_pointer = "/version"
+
+[endpoints.app-b.staging]
+url = "app-b.example.com/version"
+json_pointer = "version"
+
+[endpoints.app-b.prod]
+url = "https://app-b.example.com/version"
+json_pointer = "/version"
+pattern = '(?P<version>.+)'
+timeout = "soon"
+
+[endpoints.app-c.prod]
+url = "https://app-c.example.com/version"
+pattern = '(\d+)'
+
+[endpoints.app-d.prod]
+url = "https://app-d.example.com/version"
+"#,
+        )
+        .expect("versions should've been parsed");
+
+        // WHEN
+        let errors = Versions::try_from(raw).expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(errors.to_string(), @r#"
+        versions config has errors:
+         - app "app-a" has both a version (in version #0) and an endpoint for env "dev"
+         - endpoints.app-b.prod: only one of json_pointer and pattern can be set
+         - endpoints.app-b.prod: timeout is invalid: expected number at 0
+         - endpoints.app-b.staging: env "staging" is not in envs
+         - endpoints.app-b.staging: url "app-b.example.com/version" is invalid: needs to start with http:// or https://
+         - endpoints.app-b.staging: json_pointer "version" is invalid: needs to start with "/"
+         - endpoints.app-c.prod: pattern needs a capture group named "version", eg. "(?P<version>\d+\.\d+\.\d+)"
+         - endpoints.app-d.prod: one of json_pointer and pattern needs to be set
+         - env "prod" is not present in any of the versions configured
+        "#);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::config::FailOn;
+use crate::domain::{CommitLogFetchError, CommitLogFetchErrors, DiffResult};
+
+#[derive(Debug)]
+pub struct FailOnViolation {
+    pub condition: FailOn,
+    pub apps: Vec<String>,
+}
+
+impl std::fmt::Display for FailOnViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.apps.is_empty() {
+            write!(f, "{}", self.condition)
+        } else {
+            write!(f, "{}: {}", self.condition, self.apps.join(", "))
+        }
+    }
+}
+
+// returns violations in the order of FailOn's variants, so the first one decides the exit code
+pub fn get_sync_violations(
+    diff_result: &DiffResult,
+    conditions: &[FailOn],
+) -> Vec<FailOnViolation> {
+    let mut conditions = conditions.to_vec();
+    conditions.sort();
+    conditions.dedup();
+
+    conditions
+        .into_iter()
+        .filter_map(|condition| {
+            let apps: Vec<String> = diff_result
+                .app_results
+                .iter()
+                .filter(|row| match condition {
+                    FailOn::Behind | FailOn::Ahead | FailOn::OutOfSync => {
+                        condition.is_met_by(&row.sync_status)
+                    }
+                    FailOn::Missing => diff_result
+                        .envs
+                        .iter()
+                        .any(|env| !row.values.contains_key(env)),
+                    FailOn::Policy => !row.policy_violations.is_empty(),
+                    // handled by get_fetch_error_violation
+                    FailOn::FetchError => false,
+                })
+                .map(|row| row.name().to_string())
+                .collect();
+
+            (!apps.is_empty()).then_some(FailOnViolation { condition, apps })
+        })
+        .collect()
+}
+
+pub fn get_fetch_error_violation(
+    errors: &CommitLogFetchErrors,
+    conditions: &[FailOn],
+) -> Option<FailOnViolation> {
+    if errors.is_empty() || !conditions.contains(&FailOn::FetchError) {
+        return None;
+    }
+
+    let mut apps: Vec<String> = errors
+        .iter()
+        .filter_map(|error| match error {
+            CommitLogFetchError::App { app, .. } => Some(app.to_string()),
+            CommitLogFetchError::System { .. } => None,
+        })
+        .collect();
+    apps.sort();
+    apps.dedup();
+
+    Some(FailOnViolation {
+        condition: FailOn::FetchError,
+        apps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AppResult, EnvPair, PolicyViolation, SyncStatus};
+    use std::collections::HashMap;
+
+    fn app_result(app: &str, versions: &[(&str, &str)], sync_status: SyncStatus) -> AppResult {
+        AppResult {
+            app: app.into(),
+            display_name: None,
+            values: versions
+                .iter()
+                .map(|(env, version)| ((*env).into(), (*version).into()))
+                .collect::<HashMap<_, _>>(),
+            sync_status,
+            drifts: vec![],
+            policy_violations: vec![],
+            unknown_envs: vec![],
+            out_of_sync_since: None,
+        }
+    }
+
+    fn get_test_diff_result() -> DiffResult {
+        DiffResult {
+            envs: vec!["dev".into(), "staging".into(), "prod".into()],
+            app_results: vec![
+                app_result(
+                    "app-a",
+                    &[("dev", "1.1.0"), ("staging", "1.1.0"), ("prod", "1.0.0")],
+                    SyncStatus::Behind,
+                ),
+                app_result(
+                    "app-b",
+                    &[("dev", "1.0.0"), ("staging", "1.0.0"), ("prod", "1.0.0")],
+                    SyncStatus::InSync,
+                ),
+                app_result(
+                    "app-c",
+                    &[("dev", "2.0.0"), ("prod", "1.0.0")],
+                    SyncStatus::Behind,
+                ),
+                app_result("app-d", &[("dev", "1.0.0")], SyncStatus::NotApplicable),
+            ],
+            policies_checked: false,
+            history_used: false,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn sync_violations_are_computed_correctly() {
+        // GIVEN
+        let diff_result = get_test_diff_result();
+
+        // WHEN
+        let violations = get_sync_violations(
+            &diff_result,
+            &[
+                FailOn::Missing,
+                FailOn::Ahead,
+                FailOn::Behind,
+                FailOn::Behind,
+            ],
+        );
+
+        // THEN
+        let result = violations
+            .iter()
+            .map(|v| format!("{} ({})", v, v.condition.exit_code()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(result, @r"
+        behind: app-a, app-c (3)
+        missing: app-c, app-d (6)
+        ");
+    }
+
+    #[test]
+    fn no_sync_violations_are_reported_when_conditions_are_not_met() {
+        // GIVEN
+        let diff_result = get_test_diff_result();
+
+        // WHEN
+        let violations = get_sync_violations(&diff_result, &[FailOn::Ahead, FailOn::FetchError]);
+
+        // THEN
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn out_of_sync_violations_include_apps_that_are_behind_or_ahead() {
+        // GIVEN
+        let mut diff_result = get_test_diff_result();
+        diff_result.app_results.push(app_result(
+            "app-e",
+            &[("dev", "1.0.0"), ("prod", "1.1.0")],
+            SyncStatus::Ahead,
+        ));
+        diff_result.app_results.push(app_result(
+            "app-f",
+            &[("dev", "main"), ("prod", "1.0.0")],
+            SyncStatus::OutOfSync,
+        ));
+
+        // WHEN
+        let violations = get_sync_violations(&diff_result, &[FailOn::OutOfSync]);
+
+        // THEN
+        let result = violations
+            .iter()
+            .map(|v| format!("{} ({})", v, v.condition.exit_code()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(result, @"out-of-sync: app-a, app-c, app-e, app-f (5)");
+    }
+
+    #[test]
+    fn policy_violations_are_computed_correctly() {
+        // GIVEN
+        let mut diff_result = get_test_diff_result();
+        diff_result.app_results[2].policy_violations = vec![PolicyViolation {
+            policy: "prod-lag".into(),
+            env: "prod".into(),
+            message: "prod is 1 major behind dev, more than the 1 minor allowed".into(),
+        }];
+
+        // WHEN
+        let violations = get_sync_violations(&diff_result, &[FailOn::Policy]);
+
+        // THEN
+        let result = violations
+            .iter()
+            .map(|v| format!("{} ({})", v, v.condition.exit_code()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(result, @"policy: app-c (8)");
+    }
+
+    #[test]
+    fn fetch_error_violation_is_computed_correctly() {
+        // GIVEN
+        let pair = EnvPair {
+            from: "prod".into(),
+            to: "dev".into(),
+        };
+        let mut errors = CommitLogFetchErrors::new();
+        errors.add_app_error("app-b".into(), pair.clone(), anyhow::anyhow!("not found"));
+        errors.add_app_error("app-a".into(), pair, anyhow::anyhow!("not found"));
+        errors.add_system_error(anyhow::anyhow!("task panicked"));
+
+        // WHEN
+        let violation = get_fetch_error_violation(&errors, &[FailOn::FetchError]);
+        let no_violation = get_fetch_error_violation(&errors, &[FailOn::Behind]);
+
+        // THEN
+        let violation = violation.expect("violation should've been present");
+        assert_eq!(violation.to_string(), "fetch-error: app-a, app-b");
+        assert_eq!(violation.condition.exit_code(), 7);
+        assert!(no_violation.is_none());
+    }
+}
@@ -1,149 +1,100 @@
-use crate::domain::{
-    App, Commit, CommitLog, CommitLogFetchErrors, CommitLogResults, DiffResult, Env,
-    GitTagTransform, GithubOrg, SyncStatus, Version, Versions,
-};
+use super::FetchCommitLogParams;
+use super::http::{RetryPolicy, send_with_retries};
+use crate::domain::{ApiUrl, Commit, CommitLog};
 use anyhow::Context;
-use futures::stream::{FuturesUnordered, StreamExt};
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
-use std::sync::Arc;
-use tokio::sync::Semaphore;
-
-const MAX_CONCURRENT_FETCHES: usize = 20;
-
-pub struct FetchCommitLogParams {
-    pub github_org: GithubOrg,
-    pub app: App,
-    pub from_env: Env,
-    pub to_env: Env,
-    pub from_version: Version,
-    pub to_version: Version,
-    pub token: String,
-    pub tag_transform: Option<GitTagTransform>,
-}
+
+// the maximum page size the GitHub compare API supports
+const COMPARE_PAGE_SIZE: usize = 100;
 
 #[derive(Debug, Deserialize)]
 struct CompareResponse {
     commits: Vec<Commit>,
     html_url: String,
+    total_commits: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitResponse {
+    rate: RateLimit,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub reset: DateTime<Utc>,
 }
 
-pub async fn fetch_commit_logs(
-    diff_result: &DiffResult,
-    versions: &Versions,
+pub(super) async fn fetch_commit_log(
+    params: FetchCommitLogParams,
+    api_url: &ApiUrl,
     token: &str,
-) -> CommitLogResults {
-    let out_of_sync: Vec<_> = diff_result
-        .app_results
-        .iter()
-        .filter(|row| matches!(row.sync_status, SyncStatus::OutOfSync))
-        .collect();
-
-    if out_of_sync.is_empty() {
-        return CommitLogResults {
-            logs: vec![],
-            errors: CommitLogFetchErrors::new(),
-        };
-    }
+    retry_policy: RetryPolicy,
+) -> anyhow::Result<CommitLog> {
+    let (base_tag, head_tag) = params.refs();
 
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
-    let mut futures = FuturesUnordered::new();
-
-    for row in out_of_sync {
-        let semaphore = Arc::clone(&semaphore);
-        let github_org = versions.github_org.clone();
-        let app = row.app.clone();
-        let tag_transform = versions.git_tag_transform.clone();
-        let token = token.to_string();
-
-        let from_env = diff_result.envs[diff_result.envs.len() - 1].clone();
-        let to_env = diff_result.envs[0].clone();
-
-        let Some(from_version) = row.values.get(&from_env).cloned() else {
-            continue;
-        };
-
-        let Some(to_version) = row.values.get(&to_env).cloned() else {
-            continue;
-        };
-
-        let app_clone = app.clone();
-
-        futures.push(tokio::task::spawn(async move {
-            let permit = semaphore.acquire().await;
-            if let Err(e) = permit {
-                return (
-                    app_clone,
-                    Err(anyhow::anyhow!("couldn't acquire semaphore: {e}")),
-                );
-            }
-
-            let result = fetch_commit_log(FetchCommitLogParams {
-                github_org,
-                app,
-                from_env,
-                to_env,
-                from_version,
-                to_version,
-                token,
-                tag_transform,
-            })
-            .await;
-
-            (app_clone, result)
-        }));
-    }
+    let mut url = format!(
+        "{}/repos/{}/{}/compare/{}...{}?per_page={}",
+        api_url, &params.owner, &params.repo, base_tag, head_tag, COMPARE_PAGE_SIZE
+    );
 
-    let mut commit_logs = Vec::new();
-    let mut errors = CommitLogFetchErrors::new();
-
-    while let Some(task_result) = futures.next().await {
-        match task_result {
-            Ok((_app, Ok(log))) => commit_logs.push(log),
-            Ok((app, Err(e))) => {
-                errors.add_app_error(app, e);
-            }
-            Err(e) => {
-                errors.add_system_error(anyhow::anyhow!("task panicked: {e}"));
-            }
-        }
-    }
+    let client = reqwest::Client::builder()
+        .build()
+        .context("failed to build HTTP client")?;
 
-    commit_logs.sort_by(|a, b| a.app.cmp(&b.app));
+    let mut commits = Vec::new();
+    let (html_url, total_commits) = loop {
+        let response = send_with_retries(
+            || build_request(&client, &url, token),
+            retry_policy,
+            "GitHub",
+        )
+        .await?;
 
-    CommitLogResults {
-        logs: commit_logs,
-        errors,
-    }
-}
+        let next_page_url = get_next_page_url(response.headers());
 
-pub async fn fetch_commit_log(params: FetchCommitLogParams) -> anyhow::Result<CommitLog> {
-    let base_tag = if let Some(ref template) = params.tag_transform {
-        build_tag(template, &params.from_version)
-    } else {
-        params.from_version.to_string()
-    };
+        let compare_response: CompareResponse = response
+            .json()
+            .await
+            .context("failed to parse GitHub API response")?;
+
+        commits.extend(compare_response.commits);
 
-    let head_tag = if let Some(ref template) = params.tag_transform {
-        build_tag(template, &params.to_version)
-    } else {
-        params.to_version.to_string()
+        match next_page_url {
+            Some(next) if commits.len() < params.max_commits => url = next,
+            _ => break (compare_response.html_url, compare_response.total_commits),
+        }
     };
 
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/compare/{}...{}",
-        &params.github_org, &params.app, base_tag, head_tag
-    );
+    // pages are in chronological order, so the commits closest to the base are kept when the
+    // limit is hit
+    commits.truncate(params.max_commits);
+    commits.reverse();
+
+    Ok(CommitLog {
+        app: params.app,
+        display_name: None,
+        from_env: params.from_env,
+        to_env: params.to_env,
+        from_version: params.from_version,
+        to_version: params.to_version,
+        commits,
+        html_url,
+        total_commits,
+        from_cache: false,
+    })
+}
 
+// querying the rate limit doesn't count against it
+pub async fn get_rate_limit(api_url: &ApiUrl, token: &str) -> anyhow::Result<RateLimit> {
     let client = reqwest::Client::builder()
         .build()
         .context("failed to build HTTP client")?;
 
-    let response = client
-        .get(&url)
-        .header("Accept", "application/vnd.github+json")
-        .header("Authorization", format!("Bearer {}", params.token))
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "envee@v0.1.0")
+    let response = build_request(&client, &format!("{api_url}/rate_limit"), token)
         .send()
         .await
         .context("failed to send request to GitHub API")?;
@@ -158,40 +109,83 @@ pub async fn fetch_commit_log(params: FetchCommitLogParams) -> anyhow::Result<Co
         );
     }
 
-    let mut compare_response: CompareResponse = response
+    let rate_limit_response: RateLimitResponse = response
         .json()
         .await
         .context("failed to parse GitHub API response")?;
 
-    compare_response.commits.reverse();
+    Ok(rate_limit_response.rate)
+}
 
-    Ok(CommitLog {
-        app: params.app,
-        from_env: params.from_env,
-        to_env: params.to_env,
-        from_version: params.from_version,
-        to_version: params.to_version,
-        commits: compare_response.commits,
-        html_url: compare_response.html_url,
-    })
+fn build_request(client: &reqwest::Client, url: &str, token: &str) -> reqwest::RequestBuilder {
+    client
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", format!("Bearer {token}"))
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .header("User-Agent", "envee@v0.1.0")
 }
 
-fn build_tag(template: &str, version: &str) -> String {
-    template.replacen("{{version}}", version, 1)
+// parses the URL with rel="next" from a Link header, eg.
+// <https://api.github.com/...&page=2>; rel="next", <https://api.github.com/...&page=5>; rel="last"
+fn get_next_page_url(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let link = headers.get(reqwest::header::LINK)?.to_str().ok()?;
+
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#);
+
+        is_next.then(|| {
+            url.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue, LINK};
 
     #[test]
-    fn building_tag_works() {
-        assert_eq!(build_tag("v{{version}}", "0.83.0"), "v0.83.0");
-        assert_eq!(build_tag("release-{{version}}", "1.2.3"), "release-1.2.3");
-        assert_eq!(build_tag("{{version}}", "2.0.0"), "2.0.0");
+    fn getting_next_page_url_works() {
+        // GIVEN
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                r#"<https://api.github.com/repositories/1/compare/a...b?per_page=100&page=1>; rel="prev", <https://api.github.com/repositories/1/compare/a...b?per_page=100&page=3>; rel="next", <https://api.github.com/repositories/1/compare/a...b?per_page=100&page=5>; rel="last""#,
+            ),
+        );
+
+        // WHEN
+        let result = get_next_page_url(&headers);
+
+        // THEN
         assert_eq!(
-            build_tag("v{{version}}-{{version}}", "2.0.0"),
-            "v2.0.0-{{version}}"
+            result.as_deref(),
+            Some("https://api.github.com/repositories/1/compare/a...b?per_page=100&page=3")
+        );
+    }
+
+    #[test]
+    fn getting_next_page_url_on_last_page_returns_none() {
+        // GIVEN
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                r#"<https://api.github.com/repositories/1/compare/a...b?per_page=100&page=4>; rel="prev", <https://api.github.com/repositories/1/compare/a...b?per_page=100&page=1>; rel="first""#,
+            ),
         );
+
+        // WHEN
+        // THEN
+        assert!(get_next_page_url(&headers).is_none());
+        assert!(get_next_page_url(&HeaderMap::new()).is_none());
     }
 }
@@ -0,0 +1,352 @@
+use crate::config::FailOn;
+use crate::domain::{CommitLogFetchError, CommitLogFetchErrors, DiffResult, SyncStatus};
+use anyhow::Context;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// set to "true" by GitHub Actions for every step it runs
+pub const ENV_VAR_GITHUB_ACTIONS: &str = "GITHUB_ACTIONS";
+pub const ENV_VAR_GITHUB_STEP_SUMMARY: &str = "GITHUB_STEP_SUMMARY";
+pub const ENV_VAR_GITHUB_OUTPUT: &str = "GITHUB_OUTPUT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationLevel {
+    Warning,
+    Error,
+}
+
+// a workflow command that makes GitHub show a message on the run's summary page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub level: AnnotationLevel,
+    pub title: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let command = match self.level {
+            AnnotationLevel::Warning => "warning",
+            AnnotationLevel::Error => "error",
+        };
+
+        write!(
+            f,
+            "::{} title={}::{}",
+            command,
+            escape_property(&self.title),
+            escape_data(&self.message)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepOutputs {
+    pub app_count: usize,
+    pub in_sync_count: usize,
+    pub out_of_sync_count: usize,
+    pub commit_log_error_count: usize,
+}
+
+impl StepOutputs {
+    pub fn new(diff_result: &DiffResult) -> Self {
+        let count = |matches: fn(&SyncStatus) -> bool| {
+            diff_result
+                .app_results
+                .iter()
+                .filter(|app_result| matches(&app_result.sync_status))
+                .count()
+        };
+
+        Self {
+            app_count: diff_result.app_results.len(),
+            in_sync_count: count(|status| *status == SyncStatus::InSync),
+            out_of_sync_count: count(SyncStatus::is_out_of_sync),
+            commit_log_error_count: 0,
+        }
+    }
+
+    fn to_lines(&self) -> String {
+        format!(
+            "app_count={}\nin_sync_count={}\nout_of_sync_count={}\ncommit_log_error_count={}\n",
+            self.app_count, self.in_sync_count, self.out_of_sync_count, self.commit_log_error_count
+        )
+    }
+}
+
+// files GitHub Actions reads after a step finishes; either of them can be missing, eg. when the
+// env vars pointing to them aren't passed to a container
+#[derive(Debug, Clone, Default)]
+pub struct GithubActionsFiles {
+    pub step_summary_path: Option<PathBuf>,
+    pub output_path: Option<PathBuf>,
+}
+
+impl GithubActionsFiles {
+    pub fn append_step_summary(&self, markdown: &str) -> anyhow::Result<()> {
+        let Some(path) = &self.step_summary_path else {
+            return Ok(());
+        };
+
+        append(path, &format!("{markdown}\n"))
+            .with_context(|| format!("couldn't write to the step summary file {:?}", path))
+    }
+
+    pub fn set_outputs(&self, outputs: &StepOutputs) -> anyhow::Result<()> {
+        let Some(path) = &self.output_path else {
+            return Ok(());
+        };
+
+        append(path, &outputs.to_lines())
+            .with_context(|| format!("couldn't write to the step outputs file {:?}", path))
+    }
+}
+
+fn append(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+
+    file.write_all(contents.as_bytes())
+}
+
+// apps whose sync status is passed to --fail-on get errors, the rest get warnings
+pub fn get_app_annotations(diff_result: &DiffResult, fail_on: &[FailOn]) -> Vec<Annotation> {
+    diff_result
+        .app_results
+        .iter()
+        .filter(|app_result| app_result.sync_status.is_out_of_sync())
+        .map(|app_result| {
+            let level = if fail_on
+                .iter()
+                .any(|condition| condition.is_met_by(&app_result.sync_status))
+            {
+                AnnotationLevel::Error
+            } else {
+                AnnotationLevel::Warning
+            };
+
+            Annotation {
+                level,
+                title: format!("{} is out of sync", app_result.name()),
+                message: app_result
+                    .drifts
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }
+        })
+        .collect()
+}
+
+// fetch errors fail envee, unless --fail-on is used without fetch-error
+pub fn get_fetch_error_annotations(
+    errors: &CommitLogFetchErrors,
+    fail_on: &[FailOn],
+) -> Vec<Annotation> {
+    let level = if fail_on.is_empty() || fail_on.contains(&FailOn::FetchError) {
+        AnnotationLevel::Error
+    } else {
+        AnnotationLevel::Warning
+    };
+
+    errors
+        .iter()
+        .map(|error| match error {
+            CommitLogFetchError::App { app, pair, error } => Annotation {
+                level,
+                title: format!(
+                    "couldn't fetch commit log for {} ({}..{})",
+                    app, pair.from, pair.to
+                ),
+                message: format!("{error:#}"),
+            },
+            CommitLogFetchError::System { error } => Annotation {
+                level,
+                title: "couldn't fetch commit logs".to_string(),
+                message: format!("{error:#}"),
+            },
+        })
+        .collect()
+}
+
+// https://github.com/actions/toolkit/blob/main/packages/core/src/command.ts
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AppVersion, EnvPair};
+    use crate::service::get_diff_result;
+
+    fn get_test_diff_result() -> DiffResult {
+        let versions = [
+            ("app-a", "dev", "1.0.0"),
+            ("app-a", "prod", "1.0.0"),
+            ("app-b", "dev", "1.2.0"),
+            ("app-b", "prod", "1.0.0"),
+            ("app-c", "dev", "1.0.0"),
+            ("app-c", "prod", "1.1.0"),
+            ("app-d", "dev", "1.0.0"),
+        ]
+        .into_iter()
+        .map(|(app, env, version)| AppVersion {
+            app: app.into(),
+            env: env.into(),
+            version: version.into(),
+        })
+        .collect();
+
+        get_diff_result(vec!["dev".into(), "prod".into()], &versions)
+    }
+
+    fn get_test_fetch_errors() -> CommitLogFetchErrors {
+        let mut errors = CommitLogFetchErrors::new();
+        errors.add_app_error(
+            "app-b".into(),
+            EnvPair {
+                from: "prod".into(),
+                to: "dev".into(),
+            },
+            anyhow::anyhow!("GitHub API request failed with status 404 Not Found")
+                .context("couldn't fetch commits"),
+        );
+        errors.add_system_error(anyhow::anyhow!("couldn't build HTTP client"));
+
+        errors
+    }
+
+    fn render(annotations: &[Annotation]) -> String {
+        annotations
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn app_annotations_are_warnings_by_default() {
+        // GIVEN
+        let diff_result = get_test_diff_result();
+
+        // WHEN
+        let annotations = get_app_annotations(&diff_result, &[]);
+
+        // THEN
+        insta::assert_snapshot!(render(&annotations), @r"
+        ::warning title=app-b is out of sync::prod 2 minors behind dev
+        ::warning title=app-c is out of sync::prod 1 minor ahead of dev
+        ");
+    }
+
+    #[test]
+    fn app_annotations_are_errors_for_statuses_passed_to_fail_on() {
+        // GIVEN
+        let diff_result = get_test_diff_result();
+
+        // WHEN
+        let annotations = get_app_annotations(&diff_result, &[FailOn::Behind, FailOn::Missing]);
+
+        // THEN
+        insta::assert_snapshot!(render(&annotations), @r"
+        ::error title=app-b is out of sync::prod 2 minors behind dev
+        ::warning title=app-c is out of sync::prod 1 minor ahead of dev
+        ");
+    }
+
+    #[test]
+    fn app_annotations_are_errors_for_all_out_of_sync_apps_if_fail_on_includes_out_of_sync() {
+        // GIVEN
+        let diff_result = get_test_diff_result();
+
+        // WHEN
+        let annotations = get_app_annotations(&diff_result, &[FailOn::OutOfSync]);
+
+        // THEN
+        insta::assert_snapshot!(render(&annotations), @r"
+        ::error title=app-b is out of sync::prod 2 minors behind dev
+        ::error title=app-c is out of sync::prod 1 minor ahead of dev
+        ");
+    }
+
+    #[test]
+    fn fetch_error_annotations_are_errors_by_default() {
+        // GIVEN
+        let errors = get_test_fetch_errors();
+
+        // WHEN
+        let annotations = get_fetch_error_annotations(&errors, &[]);
+
+        // THEN
+        insta::assert_snapshot!(render(&annotations), @r"
+        ::error title=couldn't fetch commit log for app-b (prod..dev)::couldn't fetch commits: GitHub API request failed with status 404 Not Found
+        ::error title=couldn't fetch commit logs::couldn't build HTTP client
+        ");
+    }
+
+    #[test]
+    fn fetch_error_annotations_are_warnings_if_fail_on_does_not_include_them() {
+        // GIVEN
+        let errors = get_test_fetch_errors();
+
+        // WHEN
+        let annotations = get_fetch_error_annotations(&errors, &[FailOn::Behind]);
+
+        // THEN
+        assert!(
+            annotations
+                .iter()
+                .all(|a| a.level == AnnotationLevel::Warning)
+        );
+    }
+
+    #[test]
+    fn annotations_escape_special_characters() {
+        // GIVEN
+        let annotation = Annotation {
+            level: AnnotationLevel::Warning,
+            title: "app-a: 100%, really".to_string(),
+            message: "line one: 50%\nline two, done\r".to_string(),
+        };
+
+        // WHEN
+        let result = annotation.to_string();
+
+        // THEN
+        assert_eq!(
+            result,
+            "::warning title=app-a%3A 100%25%2C really::line one: 50%25%0Aline two, done%0D"
+        );
+    }
+
+    #[test]
+    fn step_outputs_are_computed_correctly() {
+        // GIVEN
+        let diff_result = get_test_diff_result();
+
+        // WHEN
+        let outputs = StepOutputs::new(&diff_result);
+
+        // THEN
+        insta::assert_snapshot!(outputs.to_lines(), @r"
+        app_count=4
+        in_sync_count=1
+        out_of_sync_count=2
+        commit_log_error_count=0
+        ");
+    }
+}
@@ -0,0 +1,151 @@
+use super::FetchCommitLogParams;
+use super::http::{RetryPolicy, send_with_retries};
+use crate::domain::{ApiUrl, Author, Commit, CommitDetail, CommitLog};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct CompareResponse {
+    commits: Vec<GitlabCommit>,
+    // only present in newer versions of GitLab
+    web_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabCommit {
+    id: String,
+    message: String,
+    author_name: String,
+    authored_date: DateTime<Utc>,
+    web_url: String,
+}
+
+impl From<GitlabCommit> for Commit {
+    fn from(commit: GitlabCommit) -> Self {
+        Self {
+            sha: commit.id,
+            commit: CommitDetail {
+                message: commit.message,
+                author: Author {
+                    name: commit.author_name,
+                    date: commit.authored_date,
+                },
+            },
+            html_url: commit.web_url,
+        }
+    }
+}
+
+pub(super) async fn fetch_commit_log(
+    params: FetchCommitLogParams,
+    api_url: &ApiUrl,
+    token: &str,
+    retry_policy: RetryPolicy,
+) -> anyhow::Result<CommitLog> {
+    let url = build_compare_url(&params, api_url)?;
+
+    let client = reqwest::Client::builder()
+        .build()
+        .context("failed to build HTTP client")?;
+
+    let response = send_with_retries(
+        || {
+            client
+                .get(url.clone())
+                .header("PRIVATE-TOKEN", token)
+                .header("User-Agent", "envee@v0.1.0")
+        },
+        retry_policy,
+        "GitLab",
+    )
+    .await?;
+
+    let compare_response: CompareResponse = response
+        .json()
+        .await
+        .context("failed to parse GitLab API response")?;
+
+    // GitLab doesn't paginate compare results, so all commits are returned in one go, in
+    // chronological order
+    let total_commits = compare_response.commits.len();
+    let mut commits: Vec<Commit> = compare_response
+        .commits
+        .into_iter()
+        .take(params.max_commits)
+        .map(Commit::from)
+        .collect();
+    commits.reverse();
+
+    Ok(CommitLog {
+        app: params.app,
+        display_name: None,
+        from_env: params.from_env,
+        to_env: params.to_env,
+        from_version: params.from_version,
+        to_version: params.to_version,
+        commits,
+        html_url: compare_response.web_url.unwrap_or_default(),
+        total_commits,
+        from_cache: false,
+    })
+}
+
+// eg. https://gitlab.com/api/v4/projects/group%2Fproject/repository/compare?from=v1.0.0&to=v1.1.0
+fn build_compare_url(
+    params: &FetchCommitLogParams,
+    api_url: &ApiUrl,
+) -> anyhow::Result<reqwest::Url> {
+    let (from, to) = params.refs();
+    let project = format!("{}/{}", params.owner, params.repo);
+
+    let mut url = reqwest::Url::parse(api_url)
+        .with_context(|| format!(r#"GitLab API URL "{api_url}" is invalid"#))?;
+
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!(r#"GitLab API URL "{api_url}" is invalid"#))?
+        .pop_if_empty()
+        .extend(["projects", &project, "repository", "compare"]);
+
+    url.query_pairs_mut()
+        .append_pair("from", &from)
+        .append_pair("to", &to);
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ApiUrl, GitTagTransform};
+
+    #[test]
+    fn building_compare_url_works() {
+        // GIVEN
+        let api_url = ApiUrl::parse("gitlab_api_url", "https://gitlab.example.com/api/v4".into())
+            .expect("API URL should've been valid");
+        let params = FetchCommitLogParams {
+            owner: "group/subgroup".into(),
+            repo: "service-x".into(),
+            app: "service-x".into(),
+            from_env: "prod".into(),
+            to_env: "dev".into(),
+            from_version: "1.0.0".into(),
+            to_version: "1.1.0".into(),
+            tag_transform: Some(
+                GitTagTransform::try_from("service-x/v{{version}}".to_string())
+                    .expect("tag transform should've been valid"),
+            ),
+            max_commits: 100,
+        };
+
+        // WHEN
+        let url = build_compare_url(&params, &api_url).expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(
+            url.as_str(),
+            "https://gitlab.example.com/api/v4/projects/group%2Fsubgroup%2Fservice-x/repository/compare?from=service-x%2Fv1.0.0&to=service-x%2Fv1.1.0"
+        );
+    }
+}
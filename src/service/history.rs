@@ -0,0 +1,636 @@
+use crate::domain::{
+    AppHistory, Deployment, DiffResult, EnvDeployments, EnvTimeline, HISTORY_SCHEMA_VERSION,
+    HistoryApp, HistoryRecord, HistoryReport, SyncStatus,
+};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+
+const SECONDS_PER_WEEK: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+// a JSON lines file with a record of every run that saw a change; it's only ever appended to
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    // a run is only appended if its results differ from the latest record's, so that the history
+    // doesn't fill up with identical records (eg. from serve refreshing every few minutes); either
+    // way, the run is part of the records out of sync since is computed from, so that an app that
+    // only just got out of sync is out of sync since this run
+    pub fn record(&self, diff_result: &mut DiffResult, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut records = self.read()?;
+        let record = get_history_record(diff_result, now);
+        if !records
+            .last()
+            .is_some_and(|latest| have_same_results(latest, &record))
+        {
+            self.append(&record)?;
+            records.push(record);
+        }
+        add_out_of_sync_since(diff_result, &records);
+
+        Ok(())
+    }
+
+    fn append(&self, record: &HistoryRecord) -> anyhow::Result<()> {
+        let mut line =
+            serde_json::to_string(record).context("failed to serialize history record")?;
+        line.push('\n');
+
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {:?}", parent))?;
+        }
+
+        // the record is written in one go, so that records from concurrent runs don't interleave
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("failed to write to history file {:?}", self.path))
+    }
+
+    // returns records oldest first; a history file that doesn't exist yet has no records
+    pub fn read(&self) -> anyhow::Result<Vec<HistoryRecord>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("couldn't read history file {:?}", self.path));
+            }
+        };
+
+        let mut records = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: HistoryRecord = serde_json::from_str(line).with_context(|| {
+                format!(
+                    "line {} of history file {:?} is invalid",
+                    index + 1,
+                    self.path
+                )
+            })?;
+            if record.schema_version != HISTORY_SCHEMA_VERSION {
+                anyhow::bail!(
+                    "line {} of history file {:?} has schema version {}, which isn't supported (expected {})",
+                    index + 1,
+                    self.path,
+                    record.schema_version,
+                    HISTORY_SCHEMA_VERSION
+                );
+            }
+            records.push(record);
+        }
+
+        // runs can finish out of order, and so be appended out of order
+        records.sort_by_key(|record| record.recorded_at);
+
+        Ok(records)
+    }
+}
+
+// apps are sorted by name, so that records of the same results are the same regardless of --sort-by
+fn get_history_record(diff_result: &DiffResult, now: DateTime<Utc>) -> HistoryRecord {
+    let mut apps: Vec<HistoryApp> = diff_result
+        .app_results
+        .iter()
+        .map(|app_result| HistoryApp {
+            app: app_result.app.to_string(),
+            versions: app_result
+                .values
+                .iter()
+                .map(|(env, version)| (env.to_string(), version.to_string()))
+                .collect(),
+            sync_status: app_result.sync_status.clone(),
+        })
+        .collect();
+    apps.sort_by(|a, b| a.app.cmp(&b.app));
+
+    HistoryRecord {
+        schema_version: HISTORY_SCHEMA_VERSION,
+        recorded_at: now,
+        envs: diff_result.envs.iter().map(|e| e.to_string()).collect(),
+        apps,
+    }
+}
+
+fn have_same_results(a: &HistoryRecord, b: &HistoryRecord) -> bool {
+    a.envs == b.envs && a.apps == b.apps
+}
+
+// records when every out of sync app first got out of sync; `records` are expected to include the
+// current run
+fn add_out_of_sync_since(diff_result: &mut DiffResult, records: &[HistoryRecord]) {
+    let since = get_out_of_sync_since(records);
+
+    for row in &mut diff_result.app_results {
+        if row.sync_status.is_out_of_sync() {
+            row.out_of_sync_since = since.get(row.app.as_str()).copied();
+        }
+    }
+
+    diff_result.history_used = true;
+}
+
+// an app is out of sync since the first of the latest unbroken run of records it was out of sync
+// in; records the app isn't a part of don't break the run
+fn get_out_of_sync_since<'a>(
+    records: impl IntoIterator<Item = &'a HistoryRecord>,
+) -> HashMap<&'a str, DateTime<Utc>> {
+    let mut since: HashMap<&str, Option<DateTime<Utc>>> = HashMap::new();
+
+    for record in records {
+        for app in &record.apps {
+            let entry = since.entry(app.app.as_str()).or_default();
+            if app.sync_status.is_out_of_sync() {
+                entry.get_or_insert(record.recorded_at);
+            } else {
+                *entry = None;
+            }
+        }
+    }
+
+    since
+        .into_iter()
+        .filter_map(|(app, since)| since.map(|since| (app, since)))
+        .collect()
+}
+
+// `records` are expected to be sorted oldest first, as returned by HistoryStore::read
+pub fn get_history_report(
+    records: &[HistoryRecord],
+    app_filter: Option<&Regex>,
+    since: Option<DateTime<Utc>>,
+) -> HistoryReport {
+    let records: Vec<&HistoryRecord> = records
+        .iter()
+        .filter(|record| since.is_none_or(|since| record.recorded_at >= since))
+        .collect();
+
+    let mut apps: BTreeMap<&str, (BTreeMap<&str, Vec<Deployment>>, SyncStatus)> = BTreeMap::new();
+    let mut env_order: Vec<&str> = Vec::new();
+
+    for record in &records {
+        for env in &record.envs {
+            if !env_order.contains(&env.as_str()) {
+                env_order.push(env);
+            }
+        }
+
+        for app in &record.apps {
+            if app_filter.is_some_and(|filter| !filter.is_match(&app.app)) {
+                continue;
+            }
+
+            let (timelines, sync_status) = apps
+                .entry(app.app.as_str())
+                .or_insert_with(|| (BTreeMap::new(), app.sync_status.clone()));
+            *sync_status = app.sync_status.clone();
+
+            for (env, version) in &app.versions {
+                let deployments = timelines.entry(env.as_str()).or_default();
+                if deployments.last().is_some_and(|d| &d.version == version) {
+                    continue;
+                }
+
+                deployments.push(Deployment {
+                    version: version.clone(),
+                    seen_at: record.recorded_at,
+                    first_seen: deployments.is_empty(),
+                });
+            }
+        }
+    }
+
+    let out_of_sync_since = get_out_of_sync_since(records.iter().copied());
+
+    let app_histories: Vec<AppHistory> = apps
+        .into_iter()
+        .map(|(app, (mut timelines, sync_status))| AppHistory {
+            app: app.to_string(),
+            timelines: env_order
+                .iter()
+                .filter_map(|env| {
+                    timelines.remove(env).map(|deployments| EnvTimeline {
+                        env: env.to_string(),
+                        deployments,
+                    })
+                })
+                .collect(),
+            out_of_sync_since: out_of_sync_since.get(app).copied(),
+            sync_status,
+        })
+        .collect();
+
+    let first_change_at = records.first().map(|r| r.recorded_at);
+    let last_change_at = records.last().map(|r| r.recorded_at);
+    // runs that don't change anything aren't recorded, so rates are over the time between the
+    // first and the last change, rather than up to the last run
+    let weeks = match (first_change_at, last_change_at) {
+        (Some(first), Some(last)) if last > first => {
+            Some((last - first).as_seconds_f64() / SECONDS_PER_WEEK)
+        }
+        _ => None,
+    };
+
+    let env_deployments = env_order
+        .iter()
+        .map(|env| {
+            let deployments = app_histories
+                .iter()
+                .flat_map(|app| &app.timelines)
+                .filter(|timeline| timeline.env == *env)
+                .map(|timeline| {
+                    timeline
+                        .deployments
+                        .iter()
+                        .filter(|d| !d.first_seen)
+                        .count()
+                })
+                .sum();
+
+            EnvDeployments {
+                env: env.to_string(),
+                deployments,
+                per_week: weeks.map(|weeks| deployments as f64 / weeks),
+            }
+        })
+        .collect();
+
+    HistoryReport {
+        changes: records.len(),
+        first_change_at,
+        last_change_at,
+        apps: app_histories,
+        env_deployments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AppResult;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn app_result(app: &str, versions: &[(&str, &str)], sync_status: SyncStatus) -> AppResult {
+        AppResult {
+            app: app.into(),
+            display_name: None,
+            values: versions
+                .iter()
+                .map(|(env, version)| ((*env).into(), (*version).into()))
+                .collect::<HashMap<_, _>>(),
+            sync_status,
+            drifts: vec![],
+            policy_violations: vec![],
+            unknown_envs: vec![],
+            out_of_sync_since: None,
+        }
+    }
+
+    fn diff_result(app_results: Vec<AppResult>) -> DiffResult {
+        DiffResult {
+            envs: vec!["dev".into(), "prod".into()],
+            app_results,
+            policies_checked: false,
+            history_used: false,
+        }
+    }
+
+    fn record(day: u32, apps: &[(&str, &str, &str, SyncStatus)]) -> HistoryRecord {
+        HistoryRecord {
+            schema_version: HISTORY_SCHEMA_VERSION,
+            recorded_at: at(day),
+            envs: vec!["dev".to_string(), "prod".to_string()],
+            apps: apps
+                .iter()
+                .map(|(app, dev, prod, sync_status)| HistoryApp {
+                    app: app.to_string(),
+                    versions: [("dev", dev), ("prod", prod)]
+                        .into_iter()
+                        .filter(|(_, version)| !version.is_empty())
+                        .map(|(env, version)| (env.to_string(), version.to_string()))
+                        .collect(),
+                    sync_status: sync_status.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap()
+    }
+
+    fn get_summary(report: &HistoryReport) -> String {
+        let mut lines = vec![format!("changes: {}", report.changes)];
+        for app in &report.apps {
+            lines.push(format!(
+                "{} ({:?}, out of sync since: {})",
+                app.app,
+                app.sync_status,
+                app.out_of_sync_since
+                    .map_or("-".to_string(), |since| since.to_rfc3339())
+            ));
+            for timeline in &app.timelines {
+                let deployments = timeline
+                    .deployments
+                    .iter()
+                    .map(|d| {
+                        format!(
+                            "{}@{}{}",
+                            d.version,
+                            d.seen_at.format("%d"),
+                            if d.first_seen { "*" } else { "" }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                lines.push(format!("  {}: {}", timeline.env, deployments));
+            }
+        }
+        for env in &report.env_deployments {
+            lines.push(format!(
+                "{}: {} deployments, {}/week",
+                env.env,
+                env.deployments,
+                env.per_week.map_or("-".to_string(), |n| format!("{n:.1}"))
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn recording_runs_works() {
+        // GIVEN
+        let temp_dir = TempDir::new().expect("temporary directory should've been created");
+        let store = HistoryStore::new(temp_dir.path().join("nested").join("history.jsonl"));
+        let mut first = diff_result(vec![
+            app_result(
+                "app-a",
+                &[("dev", "1.1.0"), ("prod", "1.0.0")],
+                SyncStatus::Behind,
+            ),
+            app_result(
+                "app-b",
+                &[("dev", "2.0.0"), ("prod", "2.0.0")],
+                SyncStatus::InSync,
+            ),
+        ]);
+        let mut second = first.clone();
+        second.app_results[1] = app_result(
+            "app-b",
+            &[("dev", "2.1.0"), ("prod", "2.0.0")],
+            SyncStatus::Behind,
+        );
+
+        // WHEN
+        store
+            .record(&mut first, at(1))
+            .expect("result should've been Ok");
+        store
+            .record(&mut second, at(2))
+            .expect("result should've been Ok");
+
+        // THEN
+        let records = store.read().expect("result should've been Ok");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].recorded_at, at(1));
+        assert_eq!(
+            records[1].apps[1].versions.get("dev"),
+            Some(&"2.1.0".to_string())
+        );
+        assert!(second.history_used);
+        assert_eq!(second.app_results[0].out_of_sync_since, Some(at(1)));
+        assert_eq!(second.app_results[1].out_of_sync_since, Some(at(2)));
+    }
+
+    #[test]
+    fn runs_with_the_same_results_as_the_latest_record_are_not_recorded() {
+        // GIVEN
+        let temp_dir = TempDir::new().expect("temporary directory should've been created");
+        let store = HistoryStore::new(temp_dir.path().join("history.jsonl"));
+        let mut first = diff_result(vec![
+            app_result(
+                "app-a",
+                &[("dev", "1.1.0"), ("prod", "1.0.0")],
+                SyncStatus::Behind,
+            ),
+            app_result(
+                "app-b",
+                &[("dev", "2.0.0"), ("prod", "2.0.0")],
+                SyncStatus::InSync,
+            ),
+        ]);
+        let mut second = first.clone();
+        // the order of apps doesn't matter
+        second.app_results.reverse();
+
+        // WHEN
+        store
+            .record(&mut first, at(1))
+            .expect("result should've been Ok");
+        store
+            .record(&mut second, at(2))
+            .expect("result should've been Ok");
+
+        // THEN
+        let records = store.read().expect("result should've been Ok");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].recorded_at, at(1));
+        assert!(second.history_used);
+        assert_eq!(second.app_results[1].out_of_sync_since, Some(at(1)));
+    }
+
+    #[test]
+    fn reading_a_history_file_that_doesnt_exist_returns_no_records() {
+        // GIVEN
+        let temp_dir = TempDir::new().expect("temporary directory should've been created");
+        let store = HistoryStore::new(temp_dir.path().join("history.jsonl"));
+
+        // WHEN
+        let records = store.read().expect("result should've been Ok");
+
+        // THEN
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn records_are_read_oldest_first() {
+        // GIVEN
+        let temp_dir = TempDir::new().expect("temporary directory should've been created");
+        let path = temp_dir.path().join("history.jsonl");
+        let lines = [
+            record(3, &[("app-a", "1.0.0", "1.0.0", SyncStatus::InSync)]),
+            record(1, &[("app-a", "1.0.0", "1.0.0", SyncStatus::InSync)]),
+        ]
+        .iter()
+        .map(|r| serde_json::to_string(r).expect("record should've been serialized"))
+        .collect::<Vec<_>>()
+        .join("\n");
+        std::fs::write(&path, lines).expect("history file should've been written");
+
+        // WHEN
+        let records = HistoryStore::new(path)
+            .read()
+            .expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(
+            records.iter().map(|r| r.recorded_at).collect::<Vec<_>>(),
+            vec![at(1), at(3)]
+        );
+    }
+
+    #[test]
+    fn history_report_is_computed_correctly() {
+        // GIVEN
+        let records = vec![
+            record(
+                1,
+                &[
+                    ("app-a", "1.0.0", "1.0.0", SyncStatus::InSync),
+                    ("app-b", "2.0.0", "1.9.0", SyncStatus::Behind),
+                ],
+            ),
+            record(
+                2,
+                &[
+                    ("app-a", "1.1.0", "1.0.0", SyncStatus::Behind),
+                    ("app-b", "2.0.0", "2.0.0", SyncStatus::InSync),
+                ],
+            ),
+            // app-b isn't a part of this run, which doesn't count as a change
+            record(4, &[("app-a", "1.1.0", "1.0.0", SyncStatus::Behind)]),
+            record(
+                8,
+                &[
+                    ("app-a", "1.2.0", "1.0.0", SyncStatus::Behind),
+                    ("app-b", "2.1.0", "2.0.0", SyncStatus::Behind),
+                ],
+            ),
+        ];
+
+        // WHEN
+        let report = get_history_report(&records, None, None);
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&report), @r"
+        changes: 4
+        app-a (Behind, out of sync since: 2025-01-02T10:00:00+00:00)
+          dev: 1.0.0@01* -> 1.1.0@02 -> 1.2.0@08
+          prod: 1.0.0@01*
+        app-b (Behind, out of sync since: 2025-01-08T10:00:00+00:00)
+          dev: 2.0.0@01* -> 2.1.0@08
+          prod: 1.9.0@01* -> 2.0.0@02
+        dev: 3 deployments, 3.0/week
+        prod: 1 deployments, 1.0/week
+        ");
+    }
+
+    #[test]
+    fn history_report_only_considers_matching_apps_and_recent_runs() {
+        // GIVEN
+        let records = vec![
+            record(
+                1,
+                &[
+                    ("app-a", "1.0.0", "1.0.0", SyncStatus::InSync),
+                    ("app-b", "2.0.0", "2.0.0", SyncStatus::InSync),
+                ],
+            ),
+            record(
+                2,
+                &[
+                    ("app-a", "1.1.0", "1.0.0", SyncStatus::Behind),
+                    ("app-b", "2.1.0", "2.0.0", SyncStatus::Behind),
+                ],
+            ),
+        ];
+        let app_filter = Regex::new("app-a").expect("regex should've been valid");
+
+        // WHEN
+        let report = get_history_report(&records, Some(&app_filter), Some(at(2)));
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&report), @r"
+        changes: 1
+        app-a (Behind, out of sync since: 2025-01-02T10:00:00+00:00)
+          dev: 1.1.0@02*
+          prod: 1.0.0@02*
+        dev: 0 deployments, -/week
+        prod: 0 deployments, -/week
+        ");
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn reading_an_invalid_history_file_fails() {
+        // GIVEN
+        let temp_dir = TempDir::new().expect("temporary directory should've been created");
+        let path = temp_dir.path().join("history.jsonl");
+        let valid =
+            serde_json::to_string(&record(1, &[])).expect("record should've been serialized");
+        std::fs::write(&path, format!("{valid}\nnot json\n"))
+            .expect("history file should've been written");
+
+        // WHEN
+        let error = HistoryStore::new(path)
+            .read()
+            .expect_err("result should've been an error");
+
+        // THEN
+        assert!(error.to_string().starts_with("line 2 of history file"));
+    }
+
+    #[test]
+    fn reading_records_with_an_unsupported_schema_version_fails() {
+        // GIVEN
+        let temp_dir = TempDir::new().expect("temporary directory should've been created");
+        let path = temp_dir.path().join("history.jsonl");
+        let mut record = record(1, &[]);
+        record.schema_version = HISTORY_SCHEMA_VERSION + 1;
+        std::fs::write(
+            &path,
+            serde_json::to_string(&record).expect("record should've been serialized"),
+        )
+        .expect("history file should've been written");
+
+        // WHEN
+        let error = HistoryStore::new(path)
+            .read()
+            .expect_err("result should've been an error");
+
+        // THEN
+        assert!(
+            error
+                .to_string()
+                .contains("has schema version 2, which isn't supported (expected 1)")
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const HEADER_RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+const HEADER_RATE_LIMIT_RESET: &str = "x-ratelimit-reset";
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    // includes the first attempt, so 1 means requests are never retried
+    pub max_attempts: u32,
+    // the longest envee will wait before retrying a request, whether the wait comes from backing
+    // off, or from the API asking us to slow down
+    pub max_wait: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+enum RetryDecision {
+    DontRetry,
+    RetryWithBackoff,
+    RetryAfter(Duration),
+}
+
+// sends the request built by build_request, retrying it on network errors, server errors, and
+// when rate limited; build_request is called once per attempt since requests can't be reused
+pub(super) async fn send_with_retries<F>(
+    build_request: F,
+    policy: RetryPolicy,
+    api_name: &str,
+) -> anyhow::Result<Response>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut attempt = 1;
+
+    loop {
+        let (decision, error) = match build_request().send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                let decision = get_retry_decision(status, response.headers(), Utc::now());
+                let error_body = response.text().await.unwrap_or_default();
+                let error = anyhow::anyhow!(
+                    "{api_name} API request failed with status {}: {}",
+                    status,
+                    error_body
+                );
+
+                (decision, error)
+            }
+            Err(e) => {
+                let decision = if e.is_connect() || e.is_timeout() || e.is_request() {
+                    RetryDecision::RetryWithBackoff
+                } else {
+                    RetryDecision::DontRetry
+                };
+                let error = anyhow::Error::new(e)
+                    .context(format!("failed to send request to {api_name} API"));
+
+                (decision, error)
+            }
+        };
+
+        let wait = match decision {
+            RetryDecision::DontRetry => return Err(error),
+            RetryDecision::RetryWithBackoff => with_jitter(get_backoff(attempt, policy.max_wait)),
+            RetryDecision::RetryAfter(wait) if wait > policy.max_wait => {
+                anyhow::bail!(
+                    "{error:#} (not retrying, since the {api_name} API asked to wait for {}s, which is longer than the maximum wait of {}s)",
+                    wait.as_secs(),
+                    policy.max_wait.as_secs()
+                );
+            }
+            RetryDecision::RetryAfter(wait) => wait,
+        };
+
+        if attempt >= policy.max_attempts {
+            if policy.max_attempts == 1 {
+                return Err(error);
+            }
+            anyhow::bail!("{error:#} (gave up after {attempt} attempts)");
+        }
+
+        tokio::time::sleep(wait).await;
+        attempt += 1;
+    }
+}
+
+fn get_retry_decision(
+    status: StatusCode,
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+) -> RetryDecision {
+    // GitHub responds with a 403 (instead of a 429) when some rate limits are hit; other 403s
+    // are permission errors that won't go away by retrying
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN
+            && (headers.contains_key(RETRY_AFTER)
+                || get_header(headers, HEADER_RATE_LIMIT_REMAINING) == Some("0")));
+
+    if rate_limited || status.is_server_error() {
+        if let Some(wait) = get_wait_from_headers(headers, now) {
+            return RetryDecision::RetryAfter(wait);
+        }
+
+        return RetryDecision::RetryWithBackoff;
+    }
+
+    RetryDecision::DontRetry
+}
+
+// Retry-After holds the number of seconds to wait for, whereas X-RateLimit-Reset holds the time
+// (in seconds since the epoch) the rate limit resets at
+fn get_wait_from_headers(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    if let Some(seconds) =
+        get_header(headers, RETRY_AFTER.as_str()).and_then(|value| value.trim().parse::<u64>().ok())
+    {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    if get_header(headers, HEADER_RATE_LIMIT_REMAINING) == Some("0") {
+        let reset_at = get_header(headers, HEADER_RATE_LIMIT_RESET)
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))?;
+
+        // the reset time is rounded down to the second, so an extra second is added to avoid
+        // retrying just before it
+        let wait = (reset_at - now).to_std().unwrap_or_default() + Duration::from_secs(1);
+        return Some(wait);
+    }
+
+    None
+}
+
+fn get_header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+// 0.5s, 1s, 2s, 4s, ... capped at max_wait
+fn get_backoff(attempt: u32, max_wait: Duration) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    BASE_BACKOFF.saturating_mul(1 << exponent).min(max_wait)
+}
+
+// spreads out retries from concurrent requests that failed at the same time, by waiting for a
+// random duration between half of the backoff and the full backoff
+fn with_jitter(backoff: Duration) -> Duration {
+    let half = backoff / 2;
+    let jitter_millis = fastrand::u64(0..=half.as_millis() as u64);
+    half + Duration::from_millis(jitter_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn server_errors_are_retried_with_backoff() {
+        for status in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::GATEWAY_TIMEOUT,
+        ] {
+            assert_eq!(
+                get_retry_decision(status, &HeaderMap::new(), now()),
+                RetryDecision::RetryWithBackoff,
+                "status: {status}"
+            );
+        }
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        for status in [
+            StatusCode::BAD_REQUEST,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::FORBIDDEN,
+            StatusCode::NOT_FOUND,
+        ] {
+            assert_eq!(
+                get_retry_decision(status, &HeaderMap::new(), now()),
+                RetryDecision::DontRetry,
+                "status: {status}"
+            );
+        }
+    }
+
+    #[test]
+    fn retry_after_header_is_honoured() {
+        // GIVEN
+        let headers = headers(&[("retry-after", "30")]);
+
+        // WHEN
+        // THEN
+        for status in [StatusCode::TOO_MANY_REQUESTS, StatusCode::FORBIDDEN] {
+            assert_eq!(
+                get_retry_decision(status, &headers, now()),
+                RetryDecision::RetryAfter(Duration::from_secs(30)),
+                "status: {status}"
+            );
+        }
+    }
+
+    #[test]
+    fn rate_limit_reset_header_is_honoured() {
+        // GIVEN
+        let reset_at = (now() + chrono::Duration::seconds(90))
+            .timestamp()
+            .to_string();
+        let mut headers = headers(&[("x-ratelimit-remaining", "0")]);
+        headers.insert(
+            "x-ratelimit-reset",
+            HeaderValue::from_str(&reset_at).expect("header value should've been valid"),
+        );
+
+        // WHEN
+        let decision = get_retry_decision(StatusCode::FORBIDDEN, &headers, now());
+
+        // THEN
+        assert_eq!(decision, RetryDecision::RetryAfter(Duration::from_secs(91)));
+    }
+
+    #[test]
+    fn rate_limits_without_wait_headers_are_retried_with_backoff() {
+        // GIVEN
+        // WHEN
+        let decision = get_retry_decision(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), now());
+
+        // THEN
+        assert_eq!(decision, RetryDecision::RetryWithBackoff);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max_wait() {
+        // GIVEN
+        let max_wait = Duration::from_secs(5);
+
+        // WHEN
+        let backoffs: Vec<_> = (1..=6)
+            .map(|attempt| get_backoff(attempt, max_wait).as_millis())
+            .collect();
+
+        // THEN
+        assert_eq!(backoffs, vec![500, 1000, 2000, 4000, 5000, 5000]);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        for _ in 0..100 {
+            let wait = with_jitter(Duration::from_secs(4));
+            assert!(wait >= Duration::from_secs(2) && wait <= Duration::from_secs(4));
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use super::FetchCommitLogParams;
+use crate::domain::{Author, Commit, CommitDetail, CommitLog};
+use crate::git;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+// fields and commits are separated by the ASCII unit and record separators respectively, since
+// neither of them shows up in commit messages
+const FIELD_SEPARATOR: char = '\x1f';
+const RECORD_SEPARATOR: char = '\x1e';
+const LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%aI%x1f%B%x1e";
+
+pub(super) async fn fetch_commit_log(
+    params: FetchCommitLogParams,
+    repo_path: &Path,
+) -> anyhow::Result<CommitLog> {
+    let (base_ref, head_ref) = params.refs();
+    let base_sha = resolve_ref(repo_path, &base_ref).await?;
+    let head_sha = resolve_ref(repo_path, &head_ref).await?;
+
+    let output = git::run_async(
+        repo_path,
+        &[
+            "log",
+            "--reverse",
+            LOG_FORMAT,
+            "--end-of-options",
+            &format!("{base_sha}..{head_sha}"),
+        ],
+    )
+    .await?;
+
+    // same as with the remote providers, the commits closest to the base are kept when the
+    // limit is hit
+    let mut commits = parse_log(&output)?;
+    let total_commits = commits.len();
+    commits.truncate(params.max_commits);
+    commits.reverse();
+
+    Ok(CommitLog {
+        app: params.app,
+        display_name: None,
+        from_env: params.from_env,
+        to_env: params.to_env,
+        from_version: params.from_version,
+        to_version: params.to_version,
+        commits,
+        // local commits can't be linked to
+        html_url: String::new(),
+        total_commits,
+        from_cache: false,
+    })
+}
+
+async fn resolve_ref(repo_path: &Path, git_ref: &str) -> anyhow::Result<String> {
+    // refs come from the versions file, and would otherwise be taken as options by git
+    if git_ref.starts_with('-') {
+        anyhow::bail!(r#"git ref "{git_ref}" is invalid: it can't start with "-""#);
+    }
+
+    let output = git::run_async(
+        repo_path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{git_ref}^{{commit}}"),
+        ],
+    )
+    .await
+    .with_context(|| {
+        format!(
+            r#"couldn't resolve "{git_ref}" in local repository "{}""#,
+            repo_path.display()
+        )
+    })?;
+
+    Ok(output.trim().to_string())
+}
+
+fn parse_log(output: &str) -> anyhow::Result<Vec<Commit>> {
+    output
+        .split(RECORD_SEPARATOR)
+        .map(str::trim)
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let mut fields = record.splitn(4, FIELD_SEPARATOR);
+            let (Some(sha), Some(author), Some(date), Some(message)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                anyhow::bail!("unexpected git log output: {record:?}");
+            };
+
+            let date = DateTime::parse_from_rfc3339(date)
+                .with_context(|| format!(r#"couldn't parse commit date "{date}""#))?
+                .with_timezone(&Utc);
+
+            Ok(Commit {
+                sha: sha.to_string(),
+                commit: CommitDetail {
+                    message: message.trim().to_string(),
+                    author: Author {
+                        name: author.to_string(),
+                        date,
+                    },
+                },
+                html_url: String::new(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_git_log_output_works() {
+        // GIVEN
+        let output = "ae7de14f\x1fUser A\x1f2025-01-15T11:00:00+01:00\x1ffirst commit\n\x1e\n1443d43c\x1fUser B\x1f2025-01-16T11:30:00Z\x1fsecond commit\n\nwith a body\n\x1e\n";
+
+        // WHEN
+        let commits = parse_log(output).expect("result should've been Ok");
+
+        // THEN
+        let result = commits
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} | {} | {} | {:?}",
+                    c.sha, c.commit.author.name, c.commit.author.date, c.commit.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(result, @r#"
+        ae7de14f | User A | 2025-01-15 10:00:00 UTC | "first commit"
+        1443d43c | User B | 2025-01-16 11:30:00 UTC | "second commit\n\nwith a body"
+        "#);
+    }
+
+    #[test]
+    fn parsing_empty_git_log_output_works() {
+        // GIVEN
+        // WHEN
+        let commits = parse_log("").expect("result should've been Ok");
+
+        // THEN
+        assert!(commits.is_empty());
+    }
+
+    #[test]
+    fn parsing_malformed_git_log_output_fails() {
+        // GIVEN
+        let output = "ae7de14f\x1fUser A\x1e";
+
+        // WHEN
+        let error = parse_log(output).expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error.to_string(), @r#"unexpected git log output: "ae7de14f\u{1f}User A""#);
+    }
+
+    #[tokio::test]
+    async fn resolving_refs_that_look_like_options_fails() {
+        // GIVEN
+        let repo = tempfile::tempdir().expect("temporary directory should've been created");
+
+        // WHEN
+        let error = resolve_ref(repo.path(), "--output=/tmp/envee")
+            .await
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error.to_string(), @r#"git ref "--output=/tmp/envee" is invalid: it can't start with "-""#);
+    }
+}
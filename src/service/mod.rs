@@ -1,5 +1,25 @@
+mod cache;
+mod changes;
+mod commit_log;
 mod diff;
+mod endpoint;
+mod fail_on;
 mod github;
+mod github_actions;
+mod gitlab;
+mod history;
+mod http;
+mod local;
+mod policy;
 
+pub use cache::CommitLogCache;
+pub use changes::*;
+pub use commit_log::*;
 pub use diff::*;
-pub use github::*;
+pub use endpoint::*;
+pub use fail_on::*;
+pub use github::get_rate_limit;
+pub use github_actions::*;
+pub use history::*;
+pub use http::RetryPolicy;
+pub use policy::*;
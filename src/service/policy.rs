@@ -0,0 +1,220 @@
+use crate::domain::{
+    AppResult, DiffResult, Env, Policy, PolicyRule, PolicyViolation, VersionDrift,
+};
+
+// fills in the policy violations for every app in the diff result; policies only look at the
+// versions present, so envs an app isn't deployed to never cause violations
+pub fn check_policies(diff_result: &mut DiffResult, policies: &[Policy]) {
+    if policies.is_empty() {
+        return;
+    }
+
+    diff_result.policies_checked = true;
+    let envs = diff_result.envs.clone();
+
+    for row in &mut diff_result.app_results {
+        row.policy_violations = policies
+            .iter()
+            .filter(|policy| policy.applies_to(&row.app))
+            .flat_map(|policy| get_violations(policy, row, &envs))
+            .collect();
+    }
+}
+
+fn get_violations(policy: &Policy, row: &AppResult, envs: &[Env]) -> Vec<PolicyViolation> {
+    let violation = |env: &Env, message: String| PolicyViolation {
+        policy: policy.name.clone(),
+        env: env.clone(),
+        message,
+    };
+
+    match &policy.rule {
+        PolicyRule::MaxBehind {
+            env,
+            reference_env,
+            max,
+        } => {
+            let (Some(version), Some(reference)) =
+                (row.values.get(env), row.values.get(reference_env))
+            else {
+                return vec![];
+            };
+
+            match VersionDrift::between(version, reference) {
+                Some(VersionDrift::Behind(amount)) if amount.exceeds(max) => vec![violation(
+                    env,
+                    format!(
+                        "{env} is {amount} behind {reference_env}, more than the {max} allowed"
+                    ),
+                )],
+                _ => vec![],
+            }
+        }
+        PolicyRule::NoAhead => {
+            let versions: Vec<_> = envs
+                .iter()
+                .filter_map(|env| row.values.get(env).map(|version| (env, version)))
+                .collect();
+
+            versions
+                .windows(2)
+                .filter_map(|pair| {
+                    let [(previous_env, previous), (env, version)] = pair else {
+                        return None;
+                    };
+
+                    match VersionDrift::between(version, previous) {
+                        Some(VersionDrift::Ahead(amount)) => Some(violation(
+                            env,
+                            format!("{env} is {amount} ahead of {previous_env}"),
+                        )),
+                        _ => None,
+                    }
+                })
+                .collect()
+        }
+        PolicyRule::Pinned { env, requirement } => {
+            let Some(version) = row.values.get(env) else {
+                return vec![];
+            };
+
+            let satisfied = version
+                .as_semver()
+                .is_some_and(|semver| requirement.matches(&semver));
+            if satisfied {
+                return vec![];
+            }
+
+            vec![violation(
+                env,
+                format!(r#"{env} is on {version}, which doesn't satisfy "{requirement}""#),
+            )]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{RawVersions, SyncStatus, Versions};
+    use crate::service::get_diff_result;
+
+    fn get_versions(policies: &str) -> Versions {
+        let contents = format!(
+            r#"
+envs = ["dev", "staging", "prod"]
+github_org = "org"
+
+versions = [
+    {{ app = "app-a", env = "dev",     version = "1.3.0" }},
+    {{ app = "app-a", env = "staging", version = "1.3.0" }},
+    {{ app = "app-a", env = "prod",    version = "1.1.0" }},
+    {{ app = "app-b", env = "dev",     version = "2.1.0" }},
+    {{ app = "app-b", env = "staging", version = "2.0.0" }},
+    {{ app = "app-b", env = "prod",    version = "2.0.1" }},
+    {{ app = "app-c", env = "dev",     version = "1.4.0" }},
+    {{ app = "app-c", env = "prod",    version = "main" }},
+]
+
+{policies}
+"#
+        );
+        let raw: RawVersions = toml::from_str(&contents).expect("versions should've been parsed");
+        Versions::try_from(raw).expect("versions should've been valid")
+    }
+
+    fn get_result(policies: &str) -> String {
+        let versions = get_versions(policies);
+        let mut diff_result = get_diff_result(versions.envs.clone(), &versions.versions);
+        check_policies(&mut diff_result, &versions.policies);
+
+        diff_result
+            .app_results
+            .iter()
+            .flat_map(|row| {
+                row.policy_violations
+                    .iter()
+                    .map(move |v| format!("{} ({}): {}", row.app, v.env, v))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn max_behind_policy_is_checked_correctly() {
+        // GIVEN
+        let policies = r#"
+[policies.prod-lag]
+rule = "max-behind"
+env = "prod"
+reference_env = "staging"
+max = "1 minor"
+"#;
+
+        // WHEN
+        let result = get_result(policies);
+
+        // THEN
+        insta::assert_snapshot!(result, @"app-a (prod): prod-lag: prod is 2 minors behind staging, more than the 1 minor allowed");
+    }
+
+    #[test]
+    fn no_ahead_policy_is_checked_correctly() {
+        // GIVEN
+        let policies = r#"
+[policies.no-ahead]
+rule = "no-ahead"
+"#;
+
+        // WHEN
+        let result = get_result(policies);
+
+        // THEN
+        insta::assert_snapshot!(result, @"app-b (prod): no-ahead: prod is 1 patch ahead of staging");
+    }
+
+    #[test]
+    fn pinned_policy_is_checked_correctly() {
+        // GIVEN
+        let policies = r#"
+[policies.pin-1x]
+rule = "pinned"
+apps = ["app-b", "app-c"]
+env = "prod"
+version = "1.x"
+"#;
+
+        // WHEN
+        let result = get_result(policies);
+
+        // THEN
+        insta::assert_snapshot!(result, @r#"
+        app-b (prod): pin-1x: prod is on 2.0.1, which doesn't satisfy "1.x"
+        app-c (prod): pin-1x: prod is on main, which doesn't satisfy "1.x"
+        "#);
+    }
+
+    #[test]
+    fn policies_are_not_checked_when_none_are_configured() {
+        // GIVEN
+        let versions = get_versions("");
+        let mut diff_result = get_diff_result(versions.envs.clone(), &versions.versions);
+
+        // WHEN
+        check_policies(&mut diff_result, &versions.policies);
+
+        // THEN
+        assert!(!diff_result.policies_checked);
+        assert!(
+            diff_result
+                .app_results
+                .iter()
+                .all(|row| row.policy_violations.is_empty())
+        );
+        assert_eq!(diff_result.app_results[0].sync_status, SyncStatus::Behind);
+    }
+}
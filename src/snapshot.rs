@@ -0,0 +1,163 @@
+use crate::git;
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+// the files in a git repository as they were at some revision, checked out into a temporary
+// directory (which is removed once the snapshot is dropped); the repository's own index and working
+// tree are left untouched
+pub struct GitSnapshot {
+    dir: TempDir,
+    pub commit: String,
+}
+
+impl GitSnapshot {
+    pub fn create(repo: &Path, git_ref: &str) -> anyhow::Result<Self> {
+        let commit = git::run(
+            repo,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{git_ref}^{{commit}}"),
+            ],
+            None,
+        )
+        .with_context(|| {
+            format!(
+                r#"couldn't resolve "{git_ref}" in git repository "{}""#,
+                repo.display()
+            )
+        })?
+        .trim()
+        .to_string();
+
+        let dir = tempfile::tempdir().context("couldn't create a temporary directory")?;
+        let snapshot = Self { dir, commit };
+
+        // a throwaway index lets git write the revision's files out without touching the
+        // repository's own index
+        let index = snapshot.dir.path().join("index");
+        let prefix = format!("--prefix={}/", snapshot.root().to_string_lossy());
+        git::run(repo, &["read-tree", &snapshot.commit], Some(&index))
+            .and_then(|_| git::run(repo, &["checkout-index", "--all", &prefix], Some(&index)))
+            .with_context(|| {
+                format!(
+                    r#"couldn't check out "{git_ref}" from git repository "{}""#,
+                    repo.display()
+                )
+            })?;
+
+        Ok(snapshot)
+    }
+
+    pub fn root(&self) -> PathBuf {
+        self.dir.path().join("tree")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(repo: &Path, args: &[&str]) {
+        let config = [
+            "-c",
+            "commit.gpgsign=false",
+            "-c",
+            "user.name=User A",
+            "-c",
+            "user.email=user-a@example.com",
+        ];
+        git::run(repo, &[&config[..], args].concat(), None).expect("git should've run");
+    }
+
+    fn write_and_commit(repo: &Path, files: &[(&str, &str)], message: &str) {
+        for (name, contents) in files {
+            let path = repo.join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("directory should've been created");
+            }
+            std::fs::write(path, contents).expect("file should've been written");
+        }
+        git(repo, &["add", "--all"]);
+        git(repo, &["commit", "--quiet", "-m", message]);
+    }
+
+    fn get_repo() -> TempDir {
+        let repo = tempfile::tempdir().expect("temporary directory should've been created");
+        git(repo.path(), &["init", "--quiet"]);
+        write_and_commit(
+            repo.path(),
+            &[("versions.toml", "old"), ("gitops/dev/app.yaml", "old")],
+            "first",
+        );
+        write_and_commit(
+            repo.path(),
+            &[("versions.toml", "new"), ("gitops/prod/app.yaml", "new")],
+            "second",
+        );
+
+        repo
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn snapshot_has_files_at_the_revision() {
+        // GIVEN
+        let repo = get_repo();
+        std::fs::write(repo.path().join("versions.toml"), "uncommitted")
+            .expect("file should've been written");
+
+        // WHEN
+        let snapshot =
+            GitSnapshot::create(repo.path(), "HEAD~1").expect("result should've been Ok");
+
+        // THEN
+        let read = |path: &str| std::fs::read_to_string(snapshot.root().join(path)).ok();
+        assert_eq!(read("versions.toml").as_deref(), Some("old"));
+        assert_eq!(read("gitops/dev/app.yaml").as_deref(), Some("old"));
+        assert_eq!(read("gitops/prod/app.yaml"), None);
+        assert_eq!(snapshot.commit.len(), 40);
+    }
+
+    #[test]
+    fn creating_snapshot_leaves_the_repository_untouched() {
+        // GIVEN
+        let repo = get_repo();
+        std::fs::write(repo.path().join("versions.toml"), "staged")
+            .expect("file should've been written");
+        git(repo.path(), &["add", "versions.toml"]);
+
+        // WHEN
+        GitSnapshot::create(repo.path(), "HEAD~1").expect("result should've been Ok");
+
+        // THEN
+        let status =
+            git::run(repo.path(), &["status", "--porcelain"], None).expect("git should've run");
+        assert_eq!(status, "M  versions.toml\n");
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn snapshot_of_unknown_revision_fails() {
+        // GIVEN
+        let repo = get_repo();
+
+        // WHEN
+        let error = GitSnapshot::create(repo.path(), "does-not-exist")
+            .err()
+            .expect("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{error:#}").replace(&repo.path().to_string_lossy().to_string(), "<REPO>");
+        insta::assert_snapshot!(message, @r#"couldn't resolve "does-not-exist" in git repository "<REPO>": git rev-parse failed with exit status: 1"#);
+    }
+}
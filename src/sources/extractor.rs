@@ -0,0 +1,565 @@
+use crate::domain::{RawAppVersion, RawExtractor};
+use anyhow::Context;
+use regex::Regex;
+use serde::Deserialize;
+use serde_yaml_ng::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+const ENV_PLACEHOLDER: &str = "{{env}}";
+const APP_CAPTURE_GROUP: &str = "app";
+const PRESET_ARGOCD_APPLICATION: &str = "argocd-application";
+const PRESET_HELM_VALUES: &str = "helm-values";
+
+struct Extractor {
+    files: String,
+    kind: Option<String>,
+    app: AppLocator,
+    version_path: KeyPath,
+}
+
+// where an app's name comes from
+enum AppLocator {
+    Path(KeyPath),
+    Pattern(Regex),
+    // the directory the file is in, eg. "charts/app-a/values-dev.yaml" belongs to "app-a"
+    ParentDir,
+}
+
+// a JSONPath-like path to a value in a document, eg. "spec.sources[0].targetRevision"
+#[derive(Debug)]
+struct KeyPath {
+    path: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+pub(super) fn get_versions(
+    raw_extractors: &[RawExtractor],
+    envs: &[String],
+    root: &Path,
+) -> anyhow::Result<Vec<RawAppVersion>> {
+    let mut versions = Vec::new();
+
+    for (i, raw) in raw_extractors.iter().enumerate() {
+        let extractor = Extractor::parse(raw)
+            .map_err(|e| anyhow::anyhow!("sources.extractors[{i}] is invalid: {e}"))?;
+        versions.extend(
+            extractor
+                .get_versions(envs, root)
+                .with_context(|| format!("couldn't get versions using sources.extractors[{i}]"))?,
+        );
+    }
+
+    Ok(versions)
+}
+
+impl Extractor {
+    fn parse(raw: &RawExtractor) -> Result<Self, String> {
+        let (kind, app_path, version_path) = match raw.preset.as_deref() {
+            Some(PRESET_ARGOCD_APPLICATION) => (
+                Some("Application"),
+                Some("metadata.name"),
+                Some("spec.source.targetRevision"),
+            ),
+            Some(PRESET_HELM_VALUES) => (None, None, Some("image.tag")),
+            Some(preset) => {
+                return Err(format!(
+                    r#"preset "{preset}" is invalid, expected one of: {PRESET_ARGOCD_APPLICATION}, {PRESET_HELM_VALUES}"#
+                ));
+            }
+            None => (None, None, None),
+        };
+
+        if !raw.files.contains(ENV_PLACEHOLDER) {
+            return Err(format!(
+                r#"files doesn't include the placeholder "{ENV_PLACEHOLDER}""#
+            ));
+        }
+
+        let app = match (&raw.app_path, &raw.app_pattern) {
+            (Some(_), Some(_)) => {
+                return Err("only one of app_path and app_pattern can be set".into());
+            }
+            (Some(path), None) => AppLocator::Path(KeyPath::parse(path)?),
+            (None, Some(pattern)) => {
+                let regex =
+                    Regex::new(pattern).map_err(|e| format!("app_pattern is invalid: {e}"))?;
+                if !regex
+                    .capture_names()
+                    .flatten()
+                    .any(|name| name == APP_CAPTURE_GROUP)
+                {
+                    return Err(format!(
+                        r#"app_pattern needs a capture group named "{APP_CAPTURE_GROUP}""#
+                    ));
+                }
+                AppLocator::Pattern(regex)
+            }
+            (None, None) => match app_path {
+                Some(path) => AppLocator::Path(KeyPath::parse(path)?),
+                None => AppLocator::ParentDir,
+            },
+        };
+
+        let version_path = raw
+            .version_path
+            .as_deref()
+            .or(version_path)
+            .ok_or("version_path is required, unless a preset sets it")?;
+
+        Ok(Self {
+            files: raw.files.clone(),
+            kind: raw.kind.clone().or(kind.map(String::from)),
+            app,
+            version_path: KeyPath::parse(version_path)?,
+        })
+    }
+
+    // documents without a version (or an app) are skipped, since the files being read are usually
+    // only partly about versions; finding nothing at all is an error though
+    fn get_versions(&self, envs: &[String], root: &Path) -> anyhow::Result<Vec<RawAppVersion>> {
+        let mut versions = Vec::new();
+        // the same app can be deployed in several documents, eg. one Application per cluster
+        let mut seen = HashSet::new();
+
+        for env in envs {
+            let pattern = self.files.replace(ENV_PLACEHOLDER, env);
+            let rooted_pattern = if root.as_os_str().is_empty() || Path::new(&pattern).is_absolute()
+            {
+                pattern.clone()
+            } else {
+                format!(
+                    "{}/{pattern}",
+                    glob::Pattern::escape(&root.to_string_lossy())
+                )
+            };
+            let mut files = glob::glob(&rooted_pattern)
+                .with_context(|| format!(r#"files pattern "{pattern}" is invalid"#))?
+                .collect::<Result<Vec<_>, _>>()
+                .context("couldn't list files")?;
+            files.sort();
+
+            for rooted_file in files {
+                if rooted_file.is_dir() {
+                    continue;
+                }
+
+                // files are reported (and matched against app_pattern) relative to the root
+                let file = rooted_file.strip_prefix(root).unwrap_or(&rooted_file);
+                let contents = std::fs::read_to_string(&rooted_file).with_context(|| {
+                    format!("couldn't read file \"{}\"", file.to_string_lossy())
+                })?;
+                let found = self
+                    .get_versions_in_file(file, &contents)
+                    .with_context(|| {
+                        format!("couldn't parse file \"{}\"", file.to_string_lossy())
+                    })?;
+
+                for (app, version) in found {
+                    if seen.insert((app.clone(), env.clone(), version.clone())) {
+                        versions.push(RawAppVersion {
+                            app,
+                            env: env.clone(),
+                            version,
+                            source: Some(file.to_string_lossy().to_string()),
+                        });
+                    }
+                }
+            }
+        }
+
+        if versions.is_empty() {
+            anyhow::bail!(r#"no versions found in files matching "{}""#, self.files);
+        }
+
+        Ok(versions)
+    }
+
+    fn get_versions_in_file(
+        &self,
+        file: &Path,
+        contents: &str,
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        let mut found = Vec::new();
+
+        for document in serde_yaml_ng::Deserializer::from_str(contents) {
+            let value = Value::deserialize(document)?;
+
+            if let Some(kind) = &self.kind
+                && value.get("kind").and_then(Value::as_str) != Some(kind.as_str())
+            {
+                continue;
+            }
+
+            let Some(version) = self.version_path.get_string(&value)? else {
+                continue;
+            };
+
+            let app = match &self.app {
+                AppLocator::Path(path) => path.get_string(&value)?,
+                AppLocator::Pattern(regex) => regex
+                    .captures(&file.to_string_lossy())
+                    .and_then(|c| c.name(APP_CAPTURE_GROUP).map(|m| m.as_str().to_string())),
+                AppLocator::ParentDir => file
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string()),
+            };
+            let Some(app) = app else {
+                continue;
+            };
+
+            found.push((app, version));
+        }
+
+        Ok(found)
+    }
+}
+
+impl KeyPath {
+    fn parse(path: &str) -> Result<Self, String> {
+        let invalid = || format!(r#"key path "{path}" is invalid"#);
+        let trimmed = path.strip_prefix("$.").unwrap_or(path);
+
+        let mut segments = Vec::new();
+        for part in trimmed.split('.') {
+            let (key, mut indices) = match part.find('[') {
+                Some(i) => part.split_at(i),
+                None => (part, ""),
+            };
+
+            if key.is_empty() && (indices.is_empty() || segments.is_empty()) {
+                return Err(invalid());
+            }
+            if !key.is_empty() {
+                segments.push(Segment::Key(key.to_string()));
+            }
+
+            while !indices.is_empty() {
+                let (index, rest) = indices
+                    .strip_prefix('[')
+                    .and_then(|s| s.split_once(']'))
+                    .ok_or_else(invalid)?;
+                segments.push(Segment::Index(index.parse().map_err(|_| invalid())?));
+                indices = rest;
+            }
+        }
+
+        Ok(Self {
+            path: path.to_string(),
+            segments,
+        })
+    }
+
+    fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                Segment::Key(key) => current.get(key.as_str()),
+                Segment::Index(index) => current.get(*index),
+            })
+    }
+
+    // unquoted numbers aren't taken as strings, since YAML reads them as floats, and so changes
+    // them, eg. 1.10 becomes 1.1
+    fn get_string(&self, value: &Value) -> anyhow::Result<Option<String>> {
+        match self.get(value) {
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(Value::Number(n)) => anyhow::bail!(
+                r#"the value at "{}" is the number {n}, which may not be what's in the file (eg. 1.10 is read as 1.1); quote the value"#,
+                self.path
+            ),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARGOCD_APPLICATIONS: &str = r#"
+apiVersion: argoproj.io/v1alpha1
+kind: Application
+metadata:
+  name: app-a
+spec:
+  source:
+    repoURL: https://github.com/dhth/app-a
+    targetRevision: v1.2.0
+---
+apiVersion: argoproj.io/v1alpha1
+kind: AppProject
+metadata:
+  name: team
+spec:
+  source:
+    targetRevision: main
+"#;
+
+    fn write_files(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        for (name, contents) in files {
+            let path = dir.path().join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("directory should've been created");
+            }
+            std::fs::write(path, contents).expect("file should've been written");
+        }
+
+        dir
+    }
+
+    fn raw_extractor(preset: Option<&str>, files: String) -> RawExtractor {
+        RawExtractor {
+            preset: preset.map(String::from),
+            files,
+            kind: None,
+            app_path: None,
+            app_pattern: None,
+            version_path: None,
+        }
+    }
+
+    fn get_summary(versions: &[RawAppVersion], dir: &Path) -> String {
+        versions
+            .iter()
+            .map(|v| {
+                format!(
+                    "{} {} {} ({})",
+                    v.env,
+                    v.app,
+                    v.version,
+                    v.source
+                        .as_deref()
+                        .unwrap_or_default()
+                        .replace(&dir.to_string_lossy().to_string(), "<DIR>")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn envs() -> Vec<String> {
+        vec!["dev".to_string(), "prod".to_string()]
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn argocd_application_preset_works() {
+        // GIVEN
+        let dir = write_files(&[
+            ("apps/dev/app-a.yaml", ARGOCD_APPLICATIONS),
+            (
+                "apps/prod/app-a.yaml",
+                &ARGOCD_APPLICATIONS.replace("v1.2.0", "v1.1.0"),
+            ),
+        ]);
+        let raw = raw_extractor(
+            Some(PRESET_ARGOCD_APPLICATION),
+            format!("{}/apps/{{{{env}}}}/*.yaml", dir.path().to_string_lossy()),
+        );
+
+        // WHEN
+        let versions =
+            get_versions(&[raw], &envs(), Path::new("")).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&versions, dir.path()), @r"
+        dev app-a v1.2.0 (<DIR>/apps/dev/app-a.yaml)
+        prod app-a v1.1.0 (<DIR>/apps/prod/app-a.yaml)
+        ");
+    }
+
+    #[test]
+    fn helm_values_preset_works() {
+        // GIVEN
+        let dir = write_files(&[
+            ("charts/app-a/values-dev.yaml", "image:\n  tag: \"1.2.0\"\n"),
+            (
+                "charts/app-a/values-prod.yaml",
+                "image:\n  tag: \"1.1.0\"\n",
+            ),
+            ("charts/app-b/values-dev.yaml", "image:\n  tag: \"2.0.0\"\n"),
+            ("charts/app-b/values-prod.yaml", "replicas: 2\n"),
+        ]);
+        let raw = raw_extractor(
+            Some(PRESET_HELM_VALUES),
+            format!(
+                "{}/charts/*/values-{{{{env}}}}.yaml",
+                dir.path().to_string_lossy()
+            ),
+        );
+
+        // WHEN
+        let versions =
+            get_versions(&[raw], &envs(), Path::new("")).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&versions, dir.path()), @r"
+        dev app-a 1.2.0 (<DIR>/charts/app-a/values-dev.yaml)
+        dev app-b 2.0.0 (<DIR>/charts/app-b/values-dev.yaml)
+        prod app-a 1.1.0 (<DIR>/charts/app-a/values-prod.yaml)
+        ");
+    }
+
+    #[test]
+    fn custom_key_paths_and_app_pattern_work() {
+        // GIVEN
+        let dir = write_files(&[
+            (
+                "dev/releases.json",
+                r#"{"releases": [{"image": {"tag": "3.0.0"}}]}"#,
+            ),
+            (
+                "prod/releases.json",
+                r#"{"releases": [{"image": {"tag": "2.9.0"}}]}"#,
+            ),
+        ]);
+        let raw = RawExtractor {
+            app_pattern: Some("(?P<app>releases)".to_string()),
+            version_path: Some("$.releases[0].image.tag".to_string()),
+            ..raw_extractor(
+                None,
+                format!("{}/{{{{env}}}}/*.json", dir.path().to_string_lossy()),
+            )
+        };
+
+        // WHEN
+        let versions =
+            get_versions(&[raw], &envs(), Path::new("")).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&versions, dir.path()), @r"
+        dev releases 3.0.0 (<DIR>/dev/releases.json)
+        prod releases 2.9.0 (<DIR>/prod/releases.json)
+        ");
+    }
+
+    #[test]
+    fn parsing_key_paths_works() {
+        // GIVEN
+        // WHEN
+        let path = KeyPath::parse("$.spec.sources[0][1].targetRevision")
+            .expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(
+            path.segments,
+            vec![
+                Segment::Key("spec".to_string()),
+                Segment::Key("sources".to_string()),
+                Segment::Index(0),
+                Segment::Index(1),
+                Segment::Key("targetRevision".to_string()),
+            ]
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_invalid_key_paths_fails() {
+        // GIVEN
+        let paths = ["", "a..b", "a[x]", "a[0", "[0]", "a.[0]b"];
+
+        for path in paths {
+            // WHEN
+            let result = KeyPath::parse(path);
+
+            // THEN
+            assert!(result.is_err(), "path: {path:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_extractors_fail() {
+        // GIVEN
+        let cases = [
+            raw_extractor(Some("kustomize"), "{{env}}/*.yaml".to_string()),
+            raw_extractor(Some(PRESET_HELM_VALUES), "values.yaml".to_string()),
+            raw_extractor(None, "{{env}}/*.yaml".to_string()),
+            RawExtractor {
+                app_path: Some("metadata.name".to_string()),
+                app_pattern: Some("(?P<app>.+)".to_string()),
+                ..raw_extractor(Some(PRESET_HELM_VALUES), "{{env}}/*.yaml".to_string())
+            },
+            RawExtractor {
+                app_pattern: Some("charts/(.+)/".to_string()),
+                ..raw_extractor(Some(PRESET_HELM_VALUES), "{{env}}/*.yaml".to_string())
+            },
+        ];
+
+        // WHEN
+        let errors = cases
+            .iter()
+            .map(|raw| get_versions(std::slice::from_ref(raw), &envs(), Path::new("")))
+            .map(|result| match result {
+                Ok(_) => "Ok".to_string(),
+                Err(e) => e.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        insta::assert_snapshot!(errors, @r#"
+        sources.extractors[0] is invalid: preset "kustomize" is invalid, expected one of: argocd-application, helm-values
+        sources.extractors[0] is invalid: files doesn't include the placeholder "{{env}}"
+        sources.extractors[0] is invalid: version_path is required, unless a preset sets it
+        sources.extractors[0] is invalid: only one of app_path and app_pattern can be set
+        sources.extractors[0] is invalid: app_pattern needs a capture group named "app"
+        "#);
+    }
+
+    #[test]
+    fn extractor_finding_unquoted_numeric_versions_fails() {
+        // GIVEN
+        let dir = write_files(&[("charts/app-a/values-dev.yaml", "image:\n  tag: 1.10\n")]);
+        let raw = raw_extractor(
+            Some(PRESET_HELM_VALUES),
+            format!(
+                "{}/charts/*/values-{{{{env}}}}.yaml",
+                dir.path().to_string_lossy()
+            ),
+        );
+
+        // WHEN
+        let error = get_versions(&[raw], &envs(), Path::new(""))
+            .expect_err("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{error:#}").replace(&dir.path().to_string_lossy().to_string(), "<DIR>");
+        insta::assert_snapshot!(message, @r#"couldn't get versions using sources.extractors[0]: couldn't parse file "<DIR>/charts/app-a/values-dev.yaml": the value at "image.tag" is the number 1.1, which may not be what's in the file (eg. 1.10 is read as 1.1); quote the value"#);
+    }
+
+    #[test]
+    fn extractor_finding_no_versions_fails() {
+        // GIVEN
+        let dir = write_files(&[("dev/app.yaml", "replicas: 2\n")]);
+        let raw = raw_extractor(
+            Some(PRESET_HELM_VALUES),
+            format!("{}/{{{{env}}}}/*.yaml", dir.path().to_string_lossy()),
+        );
+
+        // WHEN
+        let error = get_versions(&[raw], &envs(), Path::new(""))
+            .expect_err("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{error:#}").replace(&dir.path().to_string_lossy().to_string(), "<DIR>");
+        insta::assert_snapshot!(message, @r#"couldn't get versions using sources.extractors[0]: no versions found in files matching "<DIR>/{{env}}/*.yaml""#);
+    }
+}
@@ -0,0 +1,500 @@
+use crate::domain::{RawAppVersion, RawKubernetesSource};
+use anyhow::Context;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const KIND_DEPLOYMENT: &str = "Deployment";
+const KIND_STATEFUL_SET: &str = "StatefulSet";
+const KIND_CRON_JOB: &str = "CronJob";
+const MANIFEST_EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+const APP_CAPTURE_GROUP: &str = "app";
+
+#[derive(Deserialize)]
+struct Workload {
+    kind: String,
+    metadata: Option<Metadata>,
+    spec: WorkloadSpec,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    name: Option<String>,
+}
+
+// Deployments and StatefulSets have a pod template, whereas CronJobs have a job template
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkloadSpec {
+    template: Option<PodTemplate>,
+    job_template: Option<JobTemplate>,
+}
+
+#[derive(Deserialize)]
+struct JobTemplate {
+    spec: JobSpec,
+}
+
+#[derive(Deserialize)]
+struct JobSpec {
+    template: PodTemplate,
+}
+
+#[derive(Deserialize)]
+struct PodTemplate {
+    spec: PodSpec,
+}
+
+#[derive(Deserialize)]
+struct PodSpec {
+    containers: Vec<Container>,
+}
+
+#[derive(Deserialize)]
+struct Container {
+    image: Option<String>,
+}
+
+// a container image, along with the workload it's used in (eg. `Deployment "api"`)
+#[derive(Debug)]
+struct WorkloadImage {
+    workload: String,
+    image: String,
+}
+
+// decides which app a container image belongs to
+enum ImagePattern {
+    // the last segment of the image name is the app
+    LastSegment,
+    // the "app" capture group is the app; images that don't match are ignored
+    Regex(Regex),
+}
+
+pub(super) fn get_versions(
+    source: &RawKubernetesSource,
+    envs: &[String],
+    root: &Path,
+) -> anyhow::Result<Vec<RawAppVersion>> {
+    let image_pattern = ImagePattern::parse(source.image_pattern.as_deref())?;
+
+    let mut versions = Vec::new();
+    // the same image is often used by several workloads, eg. an api and a worker; sidecars, on the
+    // other hand, can be used at different tags by workloads in the same env
+    let mut seen: HashMap<(String, String), (String, String)> = HashMap::new();
+
+    for (env, dir) in &source.manifests {
+        if !envs.contains(env) {
+            anyhow::bail!(r#"manifests are configured for env "{env}", which is not in envs"#);
+        }
+
+        for file in find_manifest_files(root, Path::new(dir))? {
+            let contents = std::fs::read_to_string(root.join(&file))
+                .with_context(|| format!("couldn't read file \"{}\"", file.to_string_lossy()))?;
+            let images = get_images(&contents).with_context(|| {
+                format!("couldn't parse manifests in \"{}\"", file.to_string_lossy())
+            })?;
+
+            for WorkloadImage { workload, image } in images {
+                let Some((app, version)) = image_pattern.get_app_and_version(&image) else {
+                    continue;
+                };
+                let location = format!(r#"{workload} in "{}""#, file.to_string_lossy());
+
+                match seen.get(&(app.clone(), env.clone())) {
+                    Some((seen_version, _)) if seen_version == &version => continue,
+                    Some((seen_version, seen_location)) => anyhow::bail!(
+                        r#"app "{app}" has different versions in env "{env}": "{seen_version}" ({seen_location}) and "{version}" ({location}); if it's not an app of its own (eg. a sidecar), use image_pattern to leave it out"#
+                    ),
+                    None => {
+                        seen.insert((app.clone(), env.clone()), (version.clone(), location));
+                    }
+                }
+
+                versions.push(RawAppVersion {
+                    app,
+                    env: env.clone(),
+                    version,
+                    source: Some(file.to_string_lossy().to_string()),
+                });
+            }
+        }
+    }
+
+    Ok(versions)
+}
+
+impl ImagePattern {
+    fn parse(pattern: Option<&str>) -> anyhow::Result<Self> {
+        let Some(pattern) = pattern else {
+            return Ok(Self::LastSegment);
+        };
+
+        let regex = Regex::new(pattern).context("image_pattern is not a valid regex")?;
+        if !regex
+            .capture_names()
+            .flatten()
+            .any(|name| name == APP_CAPTURE_GROUP)
+        {
+            anyhow::bail!(
+                r#"image_pattern needs a capture group named "{APP_CAPTURE_GROUP}", eg. "(?P<{APP_CAPTURE_GROUP}>[^/]+)$""#
+            );
+        }
+
+        Ok(Self::Regex(regex))
+    }
+
+    // images without a tag are ignored, since there's no version to take from them
+    fn get_app_and_version(&self, image: &str) -> Option<(String, String)> {
+        let (name, tag) = split_image(image)?;
+
+        let app = match self {
+            Self::LastSegment => name.rsplit('/').next()?.to_string(),
+            Self::Regex(regex) => regex
+                .captures(name)?
+                .name(APP_CAPTURE_GROUP)?
+                .as_str()
+                .to_string(),
+        };
+
+        Some((app, tag.to_string()))
+    }
+}
+
+// splits "registry:5000/team/app:1.2.0@sha256:..." into "registry:5000/team/app" and "1.2.0"
+fn split_image(image: &str) -> Option<(&str, &str)> {
+    let without_digest = image.split('@').next()?;
+    let (name, tag) = without_digest.rsplit_once(':')?;
+
+    // the colon belonged to the registry's port, so there's no tag
+    if tag.contains('/') || tag.is_empty() {
+        return None;
+    }
+
+    Some((name, tag))
+}
+
+// images used by Deployments, StatefulSets, and CronJobs in a (possibly multi-document) YAML file;
+// other kinds of resources are ignored
+fn get_images(contents: &str) -> anyhow::Result<Vec<WorkloadImage>> {
+    let mut images = Vec::new();
+
+    for document in serde_yaml_ng::Deserializer::from_str(contents) {
+        let value = serde_yaml_ng::Value::deserialize(document)?;
+        let kind = value.get("kind").and_then(|k| k.as_str());
+        if !matches!(
+            kind,
+            Some(KIND_DEPLOYMENT | KIND_STATEFUL_SET | KIND_CRON_JOB)
+        ) {
+            continue;
+        }
+
+        let workload: Workload = serde_yaml_ng::from_value(value)?;
+        let name = match workload.metadata.and_then(|m| m.name) {
+            Some(name) => format!(r#"{} "{name}""#, workload.kind),
+            None => format!("unnamed {}", workload.kind),
+        };
+        let pod_template = workload
+            .spec
+            .template
+            .or(workload.spec.job_template.map(|t| t.spec.template));
+
+        if let Some(template) = pod_template {
+            images.extend(template.spec.containers.into_iter().filter_map(|c| {
+                c.image.map(|image| WorkloadImage {
+                    workload: name.clone(),
+                    image,
+                })
+            }));
+        }
+    }
+
+    Ok(images)
+}
+
+// manifests can be nested in subdirectories; files are returned in a stable order so that results
+// (and errors) don't change between runs, and relative to the root, so that they're reported the way
+// they're configured
+fn find_manifest_files(root: &Path, dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(root.join(&dir))
+            .with_context(|| format!("couldn't read directory \"{}\"", dir.to_string_lossy()))?;
+
+        for entry in entries {
+            let entry = entry.with_context(|| {
+                format!("couldn't read directory \"{}\"", dir.to_string_lossy())
+            })?;
+            let path = dir.join(entry.file_name());
+            if root.join(&path).is_dir() {
+                dirs.push(path);
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| MANIFEST_EXTENSIONS.contains(&e))
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const DEV_MANIFESTS: &str = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: api
+spec:
+  template:
+    spec:
+      containers:
+        - name: api
+          image: ghcr.io/dhth/api:1.2.0
+        - name: proxy
+          image: envoyproxy/envoy:v1.31.0
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: api-worker
+spec:
+  template:
+    spec:
+      containers:
+        - name: worker
+          image: ghcr.io/dhth/api:1.2.0
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: api
+spec:
+  ports:
+    - port: 80
+"#;
+
+    const PROD_MANIFESTS: &str = r#"
+apiVersion: apps/v1
+kind: StatefulSet
+metadata:
+  name: db
+spec:
+  template:
+    spec:
+      containers:
+        - name: db
+          image: registry.example.com:5000/dhth/db:3.0.1@sha256:abcdef
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: report
+spec:
+  jobTemplate:
+    spec:
+      template:
+        spec:
+          containers:
+            - name: report
+              image: ghcr.io/dhth/report:0.4.0
+"#;
+
+    fn get_source(image_pattern: Option<&str>) -> (tempfile::TempDir, RawKubernetesSource) {
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let dev_dir = dir.path().join("dev");
+        let prod_dir = dir.path().join("prod").join("apps");
+        std::fs::create_dir_all(&dev_dir).expect("directory should've been created");
+        std::fs::create_dir_all(&prod_dir).expect("directory should've been created");
+        std::fs::write(dev_dir.join("api.yaml"), DEV_MANIFESTS)
+            .expect("file should've been written");
+        std::fs::write(prod_dir.join("apps.yml"), PROD_MANIFESTS)
+            .expect("file should've been written");
+        std::fs::write(dev_dir.join("README.md"), "not a manifest")
+            .expect("file should've been written");
+
+        let source = RawKubernetesSource {
+            manifests: BTreeMap::from([
+                ("dev".to_string(), dev_dir.to_string_lossy().to_string()),
+                (
+                    "prod".to_string(),
+                    dir.path().join("prod").to_string_lossy().to_string(),
+                ),
+            ]),
+            image_pattern: image_pattern.map(|p| p.to_string()),
+        };
+
+        (dir, source)
+    }
+
+    fn get_summary(versions: &[RawAppVersion]) -> String {
+        versions
+            .iter()
+            .map(|v| format!("{} {} {}", v.env, v.app, v.version))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn getting_versions_from_manifests_works() {
+        // GIVEN
+        let (_dir, source) = get_source(None);
+        let envs = ["dev".to_string(), "prod".to_string()];
+
+        // WHEN
+        let versions =
+            get_versions(&source, &envs, Path::new("")).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&versions), @r"
+        dev api 1.2.0
+        dev envoy v1.31.0
+        prod db 3.0.1
+        prod report 0.4.0
+        ");
+    }
+
+    #[test]
+    fn getting_versions_from_manifests_with_image_pattern_works() {
+        // GIVEN
+        let (_dir, source) = get_source(Some(r"^ghcr\.io/dhth/(?P<app>[^/]+)$"));
+        let envs = ["dev".to_string(), "prod".to_string()];
+
+        // WHEN
+        let versions =
+            get_versions(&source, &envs, Path::new("")).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_summary(&versions), @r"
+        dev api 1.2.0
+        prod report 0.4.0
+        ");
+    }
+
+    #[test]
+    fn splitting_images_works() {
+        // GIVEN
+        let cases = [
+            ("app:1.0.0", Some(("app", "1.0.0"))),
+            (
+                "ghcr.io/dhth/app:v1.0.0",
+                Some(("ghcr.io/dhth/app", "v1.0.0")),
+            ),
+            (
+                "registry:5000/team/app:1.0.0",
+                Some(("registry:5000/team/app", "1.0.0")),
+            ),
+            ("app:1.0.0@sha256:abcdef", Some(("app", "1.0.0"))),
+            ("registry:5000/team/app", None),
+            ("app@sha256:abcdef", None),
+            ("app", None),
+        ];
+
+        for (image, expected) in cases {
+            // WHEN
+            let got = split_image(image);
+
+            // THEN
+            assert_eq!(got, expected, "image: {image}");
+        }
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn image_pattern_without_app_group_fails() {
+        // GIVEN
+        let (_dir, source) = get_source(Some(r"^ghcr\.io/dhth/([^/]+)$"));
+        let envs = ["dev".to_string(), "prod".to_string()];
+
+        // WHEN
+        let error = get_versions(&source, &envs, Path::new(""))
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error.to_string(), @r#"image_pattern needs a capture group named "app", eg. "(?P<app>[^/]+)$""#);
+    }
+
+    #[test]
+    fn manifests_for_unknown_envs_fail() {
+        // GIVEN
+        let (_dir, source) = get_source(None);
+        let envs = ["dev".to_string(), "staging".to_string()];
+
+        // WHEN
+        let error = get_versions(&source, &envs, Path::new(""))
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error.to_string(), @r#"manifests are configured for env "prod", which is not in envs"#);
+    }
+
+    #[test]
+    fn images_used_at_different_tags_by_workloads_in_the_same_env_fail() {
+        // GIVEN
+        let (dir, source) = get_source(None);
+        std::fs::write(
+            dir.path().join("dev").join("gateway.yaml"),
+            r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: gateway
+spec:
+  template:
+    spec:
+      containers:
+        - name: gateway
+          image: ghcr.io/dhth/gateway:0.9.0
+        - name: proxy
+          image: envoyproxy/envoy:v1.32.0
+"#,
+        )
+        .expect("file should've been written");
+        let envs = ["dev".to_string(), "prod".to_string()];
+
+        // WHEN
+        let error = get_versions(&source, &envs, Path::new(""))
+            .expect_err("result should've been an error");
+
+        // THEN
+        let error = error
+            .to_string()
+            .replace(&dir.path().to_string_lossy().to_string(), "[DIR]");
+        insta::assert_snapshot!(error, @r#"app "envoy" has different versions in env "dev": "v1.31.0" (Deployment "api" in "[DIR]/dev/api.yaml") and "v1.32.0" (Deployment "gateway" in "[DIR]/dev/gateway.yaml"); if it's not an app of its own (eg. a sidecar), use image_pattern to leave it out"#);
+    }
+
+    #[test]
+    fn invalid_manifests_fail() {
+        // GIVEN
+        let contents = r#"
+kind: Deployment
+spec:
+  template:
+    spec:
+      containers: "not a list"
+"#;
+
+        // WHEN
+        let error = get_images(contents).expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error.to_string(), @r#"invalid type: string "not a list", expected a sequence"#);
+    }
+}
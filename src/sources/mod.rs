@@ -0,0 +1,30 @@
+mod extractor;
+mod kubernetes;
+
+use crate::domain::{RawAppVersion, RawSources};
+use anyhow::Context;
+use std::path::Path;
+
+// versions from all configured sources, to be added to the ones in the versions file; `envs` are the
+// envs from the versions file, which sources are only allowed to produce versions for, and relative
+// paths in the sources are resolved against `root`
+pub fn get_versions(
+    sources: RawSources,
+    envs: &[String],
+    root: &Path,
+) -> anyhow::Result<Vec<RawAppVersion>> {
+    let mut versions = Vec::new();
+
+    if let Some(source) = sources.kubernetes {
+        versions.extend(
+            kubernetes::get_versions(&source, envs, root)
+                .context("couldn't get versions from Kubernetes manifests")?,
+        );
+    }
+
+    if let Some(extractors) = sources.extractors {
+        versions.extend(extractor::get_versions(&extractors, envs, root)?);
+    }
+
+    Ok(versions)
+}
@@ -0,0 +1,154 @@
+mod model;
+#[cfg(test)]
+mod testdata;
+mod ui;
+
+use crate::domain::{DiffResult, Versions};
+use crate::service::{self, CommitLogCache, ProviderTokens, RetryPolicy};
+use anyhow::Context;
+use chrono::Utc;
+use model::{Cmd, Model, Msg};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Duration;
+
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct TuiContext {
+    pub diff_result: DiffResult,
+    pub versions: Versions,
+    // None when commit logs are turned off
+    pub tokens: Option<ProviderTokens>,
+    pub max_commits: usize,
+    pub cache: Option<CommitLogCache>,
+    pub retry_policy: RetryPolicy,
+}
+
+// everything needed to fetch commit logs in the background
+struct Fetcher {
+    runtime: tokio::runtime::Handle,
+    versions: Arc<Versions>,
+    tokens: Arc<ProviderTokens>,
+    max_commits: usize,
+    cache: Option<CommitLogCache>,
+    retry_policy: RetryPolicy,
+    sender: Sender<Msg>,
+}
+
+// blocks the current thread until the user quits; needs to be called from within a
+// multi-threaded tokio runtime, since commit logs are fetched on it in the background
+pub fn run(context: TuiContext) -> anyhow::Result<()> {
+    let env_pairs = context
+        .versions
+        .comparison_strategy
+        .env_pairs(&context.diff_result.envs);
+    let mut model = Model::new(
+        context.diff_result.envs,
+        context.diff_result.app_results,
+        env_pairs,
+        context.tokens.is_some(),
+    );
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let fetcher = Fetcher {
+        runtime: tokio::runtime::Handle::current(),
+        versions: Arc::new(context.versions),
+        tokens: Arc::new(context.tokens.unwrap_or_default()),
+        max_commits: context.max_commits,
+        cache: context.cache,
+        retry_policy: context.retry_policy,
+        sender,
+    };
+
+    let mut terminal = ratatui::init();
+    let result =
+        tokio::task::block_in_place(|| event_loop(&mut terminal, &mut model, &fetcher, &receiver));
+    ratatui::restore();
+
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    model: &mut Model,
+    fetcher: &Fetcher,
+    receiver: &Receiver<Msg>,
+) -> anyhow::Result<()> {
+    let mut cmds = model.init();
+
+    loop {
+        for cmd in std::mem::take(&mut cmds) {
+            if let Some(msg) = fetcher.execute(cmd, model) {
+                cmds.extend(model.update(msg));
+            }
+        }
+
+        while let Ok(msg) = receiver.try_recv() {
+            cmds.extend(model.update(msg));
+        }
+
+        terminal
+            .draw(|frame| ui::render(frame, model, Utc::now()))
+            .context("couldn't draw to the terminal")?;
+
+        if model.quit {
+            return Ok(());
+        }
+
+        if event::poll(EVENT_POLL_INTERVAL).context("couldn't read terminal events")?
+            && let Event::Key(key) = event::read().context("couldn't read terminal events")?
+            && key.kind == KeyEventKind::Press
+        {
+            cmds.extend(model.update(Msg::Key(key)));
+        }
+    }
+}
+
+impl Fetcher {
+    // runs a command, returning a message right away if it fails synchronously
+    fn execute(&self, cmd: Cmd, model: &Model) -> Option<Msg> {
+        match cmd {
+            Cmd::FetchCommitLog {
+                app_index,
+                pair_index,
+            } => {
+                let row = model.app_results.get(app_index)?.clone();
+                let pair = model.env_pairs.get(pair_index)?.clone();
+                let versions = Arc::clone(&self.versions);
+                let tokens = Arc::clone(&self.tokens);
+                let cache = self.cache.clone();
+                let max_commits = self.max_commits;
+                let retry_policy = self.retry_policy;
+                let sender = self.sender.clone();
+
+                self.runtime.spawn(async move {
+                    let result = service::fetch_commit_log_for_app(
+                        &row,
+                        &pair,
+                        &versions,
+                        &tokens,
+                        max_commits,
+                        cache.as_ref(),
+                        retry_policy,
+                    )
+                    .await
+                    .map_err(|e| format!("{e:#}"));
+
+                    // the receiver is gone only once the user has quit
+                    let _ = sender.send(Msg::CommitLogFetched {
+                        app_index,
+                        pair_index,
+                        result,
+                    });
+                });
+
+                None
+            }
+            Cmd::OpenUrl(url) => open::that_detached(&url)
+                .err()
+                .map(|e| Msg::UrlOpenFailed(e.to_string())),
+        }
+    }
+}
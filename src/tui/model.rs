@@ -0,0 +1,489 @@
+use crate::domain::{AppResult, CommitLog, Env, EnvPair};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Mode {
+    Normal,
+    Filter,
+}
+
+#[derive(Debug)]
+pub(super) enum CommitLogState {
+    Loading,
+    Loaded(CommitLog),
+    // the app doesn't have different versions in both envs of the pair
+    NothingToCompare,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum StatusMessage {
+    Info(String),
+    Error(String),
+}
+
+#[derive(Debug)]
+pub(super) enum Msg {
+    Key(KeyEvent),
+    CommitLogFetched {
+        app_index: usize,
+        pair_index: usize,
+        result: Result<Option<CommitLog>, String>,
+    },
+    UrlOpenFailed(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Cmd {
+    FetchCommitLog { app_index: usize, pair_index: usize },
+    OpenUrl(String),
+}
+
+pub(super) struct Model {
+    pub(super) envs: Vec<Env>,
+    pub(super) app_results: Vec<AppResult>,
+    pub(super) env_pairs: Vec<EnvPair>,
+    pub(super) commit_logs_enabled: bool,
+    // indices into app_results of the apps matching the filter
+    pub(super) visible: Vec<usize>,
+    // index into visible
+    pub(super) selected: usize,
+    pub(super) pair_index: usize,
+    pub(super) mode: Mode,
+    pub(super) filter: Option<Regex>,
+    pub(super) filter_input: String,
+    // keyed by (index into app_results, index into env_pairs)
+    pub(super) commit_logs: HashMap<(usize, usize), CommitLogState>,
+    pub(super) commits_scroll: u16,
+    pub(super) message: Option<StatusMessage>,
+    pub(super) quit: bool,
+}
+
+impl Model {
+    pub(super) fn new(
+        envs: Vec<Env>,
+        app_results: Vec<AppResult>,
+        env_pairs: Vec<EnvPair>,
+        commit_logs_enabled: bool,
+    ) -> Self {
+        let visible = (0..app_results.len()).collect();
+
+        Self {
+            envs,
+            app_results,
+            env_pairs,
+            commit_logs_enabled,
+            visible,
+            selected: 0,
+            pair_index: 0,
+            mode: Mode::Normal,
+            filter: None,
+            filter_input: String::new(),
+            commit_logs: HashMap::new(),
+            commits_scroll: 0,
+            message: None,
+            quit: false,
+        }
+    }
+
+    // commands to run before the first key press
+    pub(super) fn init(&mut self) -> Vec<Cmd> {
+        self.fetch_if_needed().into_iter().collect()
+    }
+
+    pub(super) fn update(&mut self, msg: Msg) -> Vec<Cmd> {
+        match msg {
+            Msg::Key(key) => {
+                self.message = None;
+                match self.mode {
+                    Mode::Normal => self.handle_normal_key(key),
+                    Mode::Filter => self.handle_filter_key(key),
+                }
+            }
+            Msg::CommitLogFetched {
+                app_index,
+                pair_index,
+                result,
+            } => {
+                let state = match result {
+                    Ok(Some(log)) => CommitLogState::Loaded(log),
+                    Ok(None) => CommitLogState::NothingToCompare,
+                    Err(e) => CommitLogState::Failed(e),
+                };
+                self.commit_logs.insert((app_index, pair_index), state);
+                vec![]
+            }
+            Msg::UrlOpenFailed(e) => {
+                self.message = Some(StatusMessage::Error(format!("couldn't open URL: {e}")));
+                vec![]
+            }
+        }
+    }
+
+    pub(super) fn selected_app(&self) -> Option<(usize, &AppResult)> {
+        let index = *self.visible.get(self.selected)?;
+        self.app_results.get(index).map(|row| (index, row))
+    }
+
+    pub(super) fn selected_pair(&self) -> Option<&EnvPair> {
+        self.env_pairs.get(self.pair_index)
+    }
+
+    pub(super) fn selected_commit_log(&self) -> Option<&CommitLogState> {
+        let (app_index, _) = self.selected_app()?;
+        self.commit_logs.get(&(app_index, self.pair_index))
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Vec<Cmd> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('j') | KeyCode::Down => self.select(self.selected.saturating_add(1)),
+            KeyCode::Char('k') | KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Char('g') | KeyCode::Home => self.select(0),
+            KeyCode::Char('G') | KeyCode::End => self.select(self.visible.len().saturating_sub(1)),
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Tab => self.cycle_pair(true),
+            KeyCode::Char('h') | KeyCode::Left | KeyCode::BackTab => self.cycle_pair(false),
+            KeyCode::Char('J') => self.commits_scroll = self.commits_scroll.saturating_add(1),
+            KeyCode::Char('K') => self.commits_scroll = self.commits_scroll.saturating_sub(1),
+            KeyCode::Char('/') => {
+                self.mode = Mode::Filter;
+                self.filter_input = self
+                    .filter
+                    .as_ref()
+                    .map(|f| f.as_str().to_string())
+                    .unwrap_or_default();
+            }
+            KeyCode::Esc if self.filter.is_some() => self.apply_filter(None),
+            KeyCode::Char('o') => return self.open_compare_url().into_iter().collect(),
+            _ => {}
+        }
+
+        self.fetch_if_needed().into_iter().collect()
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) -> Vec<Cmd> {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                self.filter_input.clear();
+            }
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                let input = std::mem::take(&mut self.filter_input);
+                if input.trim().is_empty() {
+                    self.apply_filter(None);
+                } else {
+                    match Regex::new(input.trim()) {
+                        Ok(regex) => self.apply_filter(Some(regex)),
+                        Err(e) => {
+                            self.message =
+                                Some(StatusMessage::Error(format!("invalid regex: {e}")));
+                        }
+                    }
+                }
+            }
+            KeyCode::Backspace => {
+                self.filter_input.pop();
+            }
+            KeyCode::Char(c) => self.filter_input.push(c),
+            _ => {}
+        }
+
+        self.fetch_if_needed().into_iter().collect()
+    }
+
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.visible.len().saturating_sub(1));
+        if index != self.selected {
+            self.selected = index;
+            self.commits_scroll = 0;
+        }
+    }
+
+    fn cycle_pair(&mut self, forward: bool) {
+        let count = self.env_pairs.len();
+        if count == 0 {
+            return;
+        }
+
+        self.pair_index = if forward {
+            (self.pair_index + 1) % count
+        } else {
+            (self.pair_index + count - 1) % count
+        };
+        self.commits_scroll = 0;
+    }
+
+    // matches app names the same way --filter does
+    fn apply_filter(&mut self, filter: Option<Regex>) {
+        self.visible = self
+            .app_results
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| filter.as_ref().is_none_or(|f| f.is_match(&row.app)))
+            .map(|(index, _)| index)
+            .collect();
+        self.filter = filter;
+        self.selected = 0;
+        self.commits_scroll = 0;
+
+        if self.visible.is_empty() {
+            self.message = Some(StatusMessage::Info("no apps match the filter".to_string()));
+        }
+    }
+
+    // commit logs are only fetched once the app and env pair are selected, so that browsing a
+    // handful of apps doesn't require fetching the logs for all of them
+    fn fetch_if_needed(&mut self) -> Option<Cmd> {
+        if !self.commit_logs_enabled {
+            return None;
+        }
+
+        let (app_index, row) = self.selected_app()?;
+        let pair = self.selected_pair()?;
+        let key = (app_index, self.pair_index);
+        if self.commit_logs.contains_key(&key) {
+            return None;
+        }
+
+        let has_diff = matches!(
+            (row.values.get(&pair.from), row.values.get(&pair.to)),
+            (Some(from), Some(to)) if from != to
+        );
+        if !has_diff {
+            self.commit_logs
+                .insert(key, CommitLogState::NothingToCompare);
+            return None;
+        }
+
+        self.commit_logs.insert(key, CommitLogState::Loading);
+        Some(Cmd::FetchCommitLog {
+            app_index,
+            pair_index: self.pair_index,
+        })
+    }
+
+    fn open_compare_url(&mut self) -> Option<Cmd> {
+        match self.selected_commit_log() {
+            Some(CommitLogState::Loaded(log)) if !log.html_url.is_empty() => {
+                Some(Cmd::OpenUrl(log.html_url.clone()))
+            }
+            _ => {
+                self.message = Some(StatusMessage::Info(
+                    "no compare URL available for the selected app and env pair".to_string(),
+                ));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testdata::{get_test_model, key};
+    use super::*;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn commit_log_for_first_app_is_fetched_on_init() {
+        // GIVEN
+        let mut model = get_test_model(true);
+
+        // WHEN
+        let cmds = model.init();
+
+        // THEN
+        assert_eq!(
+            cmds,
+            vec![Cmd::FetchCommitLog {
+                app_index: 0,
+                pair_index: 0
+            }]
+        );
+        assert!(matches!(
+            model.selected_commit_log(),
+            Some(CommitLogState::Loading)
+        ));
+    }
+
+    #[test]
+    fn commit_logs_are_only_fetched_once_per_app_and_pair() {
+        // GIVEN
+        let mut model = get_test_model(true);
+        model.init();
+
+        // WHEN
+        let down = model.update(Msg::Key(key(KeyCode::Char('j'))));
+        let up = model.update(Msg::Key(key(KeyCode::Char('k'))));
+        let next_pair = model.update(Msg::Key(key(KeyCode::Char('l'))));
+
+        // THEN
+        assert_eq!(
+            down,
+            vec![Cmd::FetchCommitLog {
+                app_index: 1,
+                pair_index: 0
+            }]
+        );
+        assert!(up.is_empty());
+        assert_eq!(
+            next_pair,
+            vec![Cmd::FetchCommitLog {
+                app_index: 0,
+                pair_index: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn commit_logs_are_not_fetched_for_apps_without_differing_versions() {
+        // GIVEN
+        let mut model = get_test_model(true);
+        model.init();
+
+        // WHEN
+        // app3 is in sync
+        let cmds = model.update(Msg::Key(key(KeyCode::Char('G'))));
+
+        // THEN
+        assert!(cmds.is_empty());
+        assert!(matches!(
+            model.selected_commit_log(),
+            Some(CommitLogState::NothingToCompare)
+        ));
+    }
+
+    #[test]
+    fn commit_logs_are_not_fetched_when_turned_off() {
+        // GIVEN
+        let mut model = get_test_model(false);
+
+        // WHEN
+        let cmds = model.init();
+
+        // THEN
+        assert!(cmds.is_empty());
+        assert!(model.selected_commit_log().is_none());
+    }
+
+    #[test]
+    fn filtering_apps_works() {
+        // GIVEN
+        let mut model = get_test_model(false);
+
+        // WHEN
+        for code in [
+            KeyCode::Char('/'),
+            KeyCode::Char('a'),
+            KeyCode::Char('p'),
+            KeyCode::Char('p'),
+            KeyCode::Char('['),
+            KeyCode::Char('2'),
+            KeyCode::Char('3'),
+            KeyCode::Char(']'),
+            KeyCode::Enter,
+        ] {
+            model.update(Msg::Key(key(code)));
+        }
+
+        // THEN
+        let visible: Vec<_> = model
+            .visible
+            .iter()
+            .map(|i| model.app_results[*i].app.to_string())
+            .collect();
+        assert_eq!(visible, vec!["app2", "app3"]);
+        assert_eq!(model.mode, Mode::Normal);
+
+        // WHEN
+        model.update(Msg::Key(key(KeyCode::Esc)));
+
+        // THEN
+        assert_eq!(model.visible.len(), 3);
+        assert!(model.filter.is_none());
+    }
+
+    #[test]
+    fn env_pairs_wrap_around() {
+        // GIVEN
+        let mut model = get_test_model(false);
+
+        // WHEN
+        model.update(Msg::Key(key(KeyCode::Char('h'))));
+
+        // THEN
+        assert_eq!(model.pair_index, 1);
+
+        // WHEN
+        model.update(Msg::Key(key(KeyCode::Tab)));
+
+        // THEN
+        assert_eq!(model.pair_index, 0);
+    }
+
+    #[test]
+    fn compare_url_is_opened_for_loaded_commit_logs() {
+        // GIVEN
+        let mut model = get_test_model(true);
+        model.init();
+        let log = super::super::testdata::get_test_commit_log();
+        model.update(Msg::CommitLogFetched {
+            app_index: 0,
+            pair_index: 0,
+            result: Ok(Some(log)),
+        });
+
+        // WHEN
+        let cmds = model.update(Msg::Key(key(KeyCode::Char('o'))));
+
+        // THEN
+        assert_eq!(
+            cmds,
+            vec![Cmd::OpenUrl(
+                "https://github.com/dhth/app1/compare/v1.0.0...v1.1.0".to_string()
+            )]
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn invalid_filter_regex_is_reported() {
+        // GIVEN
+        let mut model = get_test_model(false);
+
+        // WHEN
+        for code in [KeyCode::Char('/'), KeyCode::Char('('), KeyCode::Enter] {
+            model.update(Msg::Key(key(code)));
+        }
+
+        // THEN
+        assert!(matches!(model.message, Some(StatusMessage::Error(_))));
+        assert!(model.filter.is_none());
+        assert_eq!(model.visible.len(), 3);
+    }
+
+    #[test]
+    fn opening_compare_url_before_commit_log_is_loaded_shows_message() {
+        // GIVEN
+        let mut model = get_test_model(true);
+        model.init();
+
+        // WHEN
+        let cmds = model.update(Msg::Key(key(KeyCode::Char('o'))));
+
+        // THEN
+        assert!(cmds.is_empty());
+        assert!(matches!(model.message, Some(StatusMessage::Info(_))));
+    }
+}
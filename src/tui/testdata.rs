@@ -0,0 +1,125 @@
+use super::model::Model;
+use crate::domain::{
+    AppResult, Author, Commit, CommitDetail, CommitLog, DriftAmount, EnvDrift, EnvPair, SyncStatus,
+    VersionDrift,
+};
+use chrono::{TimeZone, Utc};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+pub(super) fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn app_result(
+    app: &str,
+    versions: [&str; 3],
+    sync_status: SyncStatus,
+    drifts: Vec<EnvDrift>,
+) -> AppResult {
+    let values: HashMap<_, _> = ["qa", "staging", "prod"]
+        .into_iter()
+        .zip(versions)
+        .map(|(env, version)| (env.into(), version.into()))
+        .collect();
+
+    AppResult {
+        app: app.into(),
+        display_name: None,
+        values,
+        sync_status,
+        drifts,
+        policy_violations: vec![],
+        unknown_envs: vec![],
+        out_of_sync_since: None,
+    }
+}
+
+pub(super) fn get_test_model(commit_logs_enabled: bool) -> Model {
+    let app_results = vec![
+        app_result(
+            "app1",
+            ["1.2.0", "1.1.0", "1.0.0"],
+            SyncStatus::Behind,
+            vec![
+                EnvDrift {
+                    env: "staging".into(),
+                    reference_env: "qa".into(),
+                    drift: VersionDrift::Behind(DriftAmount::Minor(1)),
+                },
+                EnvDrift {
+                    env: "prod".into(),
+                    reference_env: "qa".into(),
+                    drift: VersionDrift::Behind(DriftAmount::Minor(2)),
+                },
+            ],
+        ),
+        app_result(
+            "app2",
+            ["2.0.0", "2.0.0", "2.0.1"],
+            SyncStatus::Ahead,
+            vec![EnvDrift {
+                env: "prod".into(),
+                reference_env: "qa".into(),
+                drift: VersionDrift::Ahead(DriftAmount::Patch(1)),
+            }],
+        ),
+        app_result(
+            "app3",
+            ["0.1.0", "0.1.0", "0.1.0"],
+            SyncStatus::InSync,
+            vec![],
+        ),
+    ];
+
+    Model::new(
+        vec!["qa".into(), "staging".into(), "prod".into()],
+        app_results,
+        vec![
+            EnvPair {
+                from: "prod".into(),
+                to: "staging".into(),
+            },
+            EnvPair {
+                from: "staging".into(),
+                to: "qa".into(),
+            },
+        ],
+        commit_logs_enabled,
+    )
+}
+
+pub(super) fn get_test_commit_log() -> CommitLog {
+    let commit = |sha: &str, message: &str, author: &str, day: u32| Commit {
+        sha: sha.to_string(),
+        commit: CommitDetail {
+            message: message.to_string(),
+            author: Author {
+                name: author.to_string(),
+                date: Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap(),
+            },
+        },
+        html_url: format!("https://github.com/dhth/app1/commit/{sha}"),
+    };
+
+    CommitLog {
+        app: "app1".into(),
+        display_name: None,
+        from_env: "prod".into(),
+        to_env: "staging".into(),
+        from_version: "1.0.0".into(),
+        to_version: "1.1.0".into(),
+        commits: vec![
+            commit(
+                "b2c3d4e5f6a7",
+                "feat: add retries\n\nsome details",
+                "alice",
+                14,
+            ),
+            commit("a1b2c3d4e5f6", "fix: handle empty responses", "bob", 10),
+        ],
+        html_url: "https://github.com/dhth/app1/compare/v1.0.0...v1.1.0".to_string(),
+        total_commits: 2,
+        from_cache: false,
+    }
+}
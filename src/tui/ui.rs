@@ -0,0 +1,320 @@
+use super::model::{CommitLogState, Mode, Model, StatusMessage};
+use crate::domain::{CommitLog, SyncStatus};
+use crate::view::{get_humanized_date, get_status_text};
+use chrono::{DateTime, Utc};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table, TableState, Tabs};
+
+const KEY_HINTS: &str =
+    "j/k: select app  h/l: env pair  /: filter  o: open compare URL  J/K: scroll commits  q: quit";
+
+pub(super) fn render(frame: &mut Frame, model: &Model, now: DateTime<Utc>) {
+    let [apps_area, commits_area, footer_area] = Layout::vertical([
+        Constraint::Percentage(45),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    render_apps(frame, model, apps_area);
+    render_commits(frame, model, commits_area, now);
+    render_footer(frame, model, footer_area);
+}
+
+fn get_status_color(sync_status: &SyncStatus) -> Option<Color> {
+    match sync_status {
+        SyncStatus::InSync => None,
+        SyncStatus::Behind => Some(Color::Yellow),
+        SyncStatus::Ahead => Some(Color::Magenta),
+        SyncStatus::OutOfSync => Some(Color::Red),
+        SyncStatus::NotApplicable => Some(Color::Gray),
+    }
+}
+
+fn render_apps(frame: &mut Frame, model: &Model, area: Rect) {
+    let mut header = vec![Cell::from("app")];
+    header.extend(model.envs.iter().map(|env| Cell::from(env.to_string())));
+    header.push(Cell::from("status"));
+
+    let rows = model.visible.iter().filter_map(|index| {
+        let row = model.app_results.get(*index)?;
+
+        let mut cells = vec![Cell::from(row.name().to_string())];
+        for env in &model.envs {
+            cells.push(Cell::from(row.version_text(env).to_string()));
+        }
+        cells.push(Cell::from(get_status_text(row)));
+
+        let style = get_status_color(&row.sync_status)
+            .map(|color| Style::new().fg(color))
+            .unwrap_or_default();
+
+        Some(Row::new(cells).style(style))
+    });
+
+    // columns are sized to fit their content, so that the status column gets the remaining space
+    let column_width = |header: &str, values: &mut dyn Iterator<Item = usize>| {
+        values
+            .chain([header.chars().count()])
+            .max()
+            .unwrap_or_default() as u16
+    };
+    let mut widths = vec![Constraint::Length(column_width(
+        "app",
+        &mut model
+            .app_results
+            .iter()
+            .map(|row| row.name().chars().count()),
+    ))];
+    widths.extend(model.envs.iter().map(|env| {
+        Constraint::Length(column_width(
+            env,
+            &mut model
+                .app_results
+                .iter()
+                .map(|row| row.version_text(env).chars().count()),
+        ))
+    }));
+    widths.push(Constraint::Fill(1));
+
+    let title = match &model.filter {
+        Some(filter) => format!(
+            " apps ({}/{}, filter: {}) ",
+            model.visible.len(),
+            model.app_results.len(),
+            filter.as_str()
+        ),
+        None => format!(" apps ({}) ", model.app_results.len()),
+    };
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).bold())
+        .block(Block::bordered().title(title))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ")
+        .column_spacing(2);
+
+    let mut state =
+        TableState::default().with_selected((!model.visible.is_empty()).then_some(model.selected));
+
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_commits(frame: &mut Frame, model: &Model, area: Rect, now: DateTime<Utc>) {
+    let title = match model.selected_app() {
+        Some((_, row)) => format!(" commits: {} ", row.name()),
+        None => " commits ".to_string(),
+    };
+    let block = Block::bordered()
+        .title(title)
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [tabs_area, content_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+
+    let tabs = Tabs::new(
+        model
+            .env_pairs
+            .iter()
+            .map(|pair| format!("{}..{}", pair.from, pair.to)),
+    )
+    .select(model.pair_index)
+    .highlight_style(Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    frame.render_widget(tabs, tabs_area);
+
+    let lines = match (model.selected_app(), model.selected_pair()) {
+        (None, _) => vec![Line::from("no apps to show")],
+        (_, None) => vec![Line::from("no env pairs to compare")],
+        (Some(_), Some(_)) if !model.commit_logs_enabled => {
+            vec![Line::from("commit logs are turned off")]
+        }
+        (Some(_), Some(pair)) => match model.selected_commit_log() {
+            None | Some(CommitLogState::Loading) => {
+                vec![Line::from("fetching commit log...").fg(Color::Gray)]
+            }
+            Some(CommitLogState::NothingToCompare) => vec![Line::from(format!(
+                "no different versions to compare between {} and {}",
+                pair.from, pair.to
+            ))],
+            Some(CommitLogState::Failed(e)) => {
+                vec![Line::from(format!("couldn't fetch commit log: {e}")).fg(Color::Red)]
+            }
+            Some(CommitLogState::Loaded(log)) => get_commit_lines(log, now),
+        },
+    };
+
+    let paragraph = Paragraph::new(lines).scroll((model.commits_scroll, 0));
+    frame.render_widget(paragraph, content_area);
+}
+
+fn get_commit_lines(log: &CommitLog, now: DateTime<Utc>) -> Vec<Line<'_>> {
+    let mut summary = format!("{}..{}", log.from_version, log.to_version);
+    if log.is_truncated() {
+        summary.push_str(&format!(
+            " [showing {} of {} commits]",
+            log.commits.len(),
+            log.total_commits
+        ));
+    } else {
+        summary.push_str(&format!(
+            " [{} commit{}]",
+            log.commits.len(),
+            if log.commits.len() == 1 { "" } else { "s" }
+        ));
+    }
+    if log.from_cache {
+        summary.push_str(" (cached)");
+    }
+
+    let mut lines = vec![Line::from(summary).bold(), Line::default()];
+
+    if log.commits.is_empty() {
+        lines.push(Line::from("no commits"));
+        return lines;
+    }
+
+    lines.extend(log.commits.iter().map(|commit| {
+        let short_sha = commit.sha.chars().take(7).collect::<String>();
+        let message = commit
+            .commit
+            .message
+            .lines()
+            .next()
+            .unwrap_or(&commit.commit.message);
+
+        Line::from(vec![
+            Span::from(short_sha).fg(Color::Yellow),
+            Span::from("  "),
+            Span::from(message),
+            Span::from("  "),
+            Span::from(commit.commit.author.name.as_str()).fg(Color::Cyan),
+            Span::from("  "),
+            Span::from(get_humanized_date(&commit.commit.author.date, &now)).fg(Color::Gray),
+        ])
+    }));
+
+    lines
+}
+
+fn render_footer(frame: &mut Frame, model: &Model, area: Rect) {
+    let line = match (&model.mode, &model.message) {
+        (Mode::Filter, _) => Line::from(format!("/{}", model.filter_input)),
+        (Mode::Normal, Some(StatusMessage::Info(message))) => Line::from(message.as_str()),
+        (Mode::Normal, Some(StatusMessage::Error(message))) => {
+            Line::from(message.as_str()).fg(Color::Red)
+        }
+        (Mode::Normal, None) => Line::from(KEY_HINTS).fg(Color::Gray),
+    };
+
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::Msg;
+    use super::super::testdata::{get_test_commit_log, get_test_model};
+    use super::*;
+    use chrono::TimeZone;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn render_to_string(model: &Model) -> String {
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 10, 0, 0).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| render(frame, model, now)).unwrap();
+
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(100)
+            .map(|cells| {
+                cells
+                    .iter()
+                    .map(|cell| cell.symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn tui_is_rendered_correctly_with_a_loaded_commit_log() {
+        // GIVEN
+        let mut model = get_test_model(true);
+        model.init();
+        model.update(Msg::CommitLogFetched {
+            app_index: 0,
+            pair_index: 0,
+            result: Ok(Some(get_test_commit_log())),
+        });
+
+        // WHEN
+        let output = render_to_string(&model);
+
+        // THEN
+        insta::assert_snapshot!(output, @r"
+        ┌ apps (3) ────────────────────────────────────────────────────────────────────────────────────────┐
+        │  app   qa     staging  prod   status                                                             │
+        │> app1  1.2.0  1.1.0    1.0.0  staging 1 minor behind qa, prod 2 minors behind qa                 │
+        │  app2  2.0.0  2.0.0    2.0.1  prod 1 patch ahead of qa                                           │
+        │  app3  0.1.0  0.1.0    0.1.0  ✓                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        └──────────────────────────────────────────────────────────────────────────────────────────────────┘
+        ┌ commits: app1 ───────────────────────────────────────────────────────────────────────────────────┐
+        │  prod..staging │ staging..qa                                                                     │
+        │                                                                                                  │
+        │ 1.0.0..1.1.0 [2 commits]                                                                         │
+        │                                                                                                  │
+        │ b2c3d4e  feat: add retries  alice  2d ago                                                        │
+        │ a1b2c3d  fix: handle empty responses  bob  6d ago                                                │
+        │                                                                                                  │
+        │                                                                                                  │
+        └──────────────────────────────────────────────────────────────────────────────────────────────────┘
+        j/k: select app  h/l: env pair  /: filter  o: open compare URL  J/K: scroll commits  q: quit
+        ");
+    }
+
+    #[test]
+    fn tui_is_rendered_correctly_while_fetching_a_commit_log() {
+        // GIVEN
+        let mut model = get_test_model(true);
+        model.init();
+
+        // WHEN
+        let output = render_to_string(&model);
+
+        // THEN
+        insta::assert_snapshot!(output, @r"
+        ┌ apps (3) ────────────────────────────────────────────────────────────────────────────────────────┐
+        │  app   qa     staging  prod   status                                                             │
+        │> app1  1.2.0  1.1.0    1.0.0  staging 1 minor behind qa, prod 2 minors behind qa                 │
+        │  app2  2.0.0  2.0.0    2.0.1  prod 1 patch ahead of qa                                           │
+        │  app3  0.1.0  0.1.0    0.1.0  ✓                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        └──────────────────────────────────────────────────────────────────────────────────────────────────┘
+        ┌ commits: app1 ───────────────────────────────────────────────────────────────────────────────────┐
+        │  prod..staging │ staging..qa                                                                     │
+        │                                                                                                  │
+        │ fetching commit log...                                                                           │
+        │                                                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        │                                                                                                  │
+        └──────────────────────────────────────────────────────────────────────────────────────────────────┘
+        j/k: select app  h/l: env pair  /: filter  o: open compare URL  J/K: scroll commits  q: quit
+        ");
+    }
+}
@@ -1,39 +1,181 @@
-use crate::domain::{RawVersions, Versions};
+use crate::config::VersionsFormat;
+use crate::domain::{RawAppVersion, RawVersions, RawVersionsFile, Versions};
+use crate::sources;
 use anyhow::Context;
 use regex::Regex;
-use std::path::Path;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-pub fn get_from_file<P>(path: P, app_filter: Option<&Regex>) -> anyhow::Result<Versions>
+// passing this as a path reads versions from stdin
+pub const STDIN_PATH: &str = "-";
+
+// reads versions from one or more paths, each of which can be a versions file, a directory of
+// them, or stdin; a single file is read as is, whereas multiple files are merged into one, with
+// `envs` (when non empty) taking the place of the envs setting in the files. Relative paths, both
+// the ones passed here and the ones in the versions files, are resolved against `root`, which is
+// empty (ie. the working directory) unless versions are read from a git revision
+pub fn get_from_paths(
+    root: &Path,
+    paths: &[PathBuf],
+    envs: &[String],
+    format: Option<VersionsFormat>,
+    app_filter: Option<&Regex>,
+) -> anyhow::Result<Versions> {
+    let files = resolve_versions_files(root, paths)?;
+    if let [file] = files.as_slice()
+        && envs.is_empty()
+    {
+        return get_from_file(root, file, format, app_filter);
+    }
+
+    let mut versions_files = Vec::with_capacity(files.len());
+    for file in &files {
+        let contents = read_versions_file(root, file)?;
+        let versions_file: RawVersionsFile = parse(&contents, get_format(file, &contents, format))
+            .with_context(|| format!("couldn't get versions from {}", describe_path(file)))?;
+        versions_files.push((file.to_string_lossy().to_string(), versions_file));
+    }
+
+    let paths_description = paths
+        .iter()
+        .map(|p| format!("\"{}\"", p.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    merge_versions_files(versions_files, envs)
+        .and_then(|raw| get_versions_from_raw(raw, root, app_filter))
+        .with_context(|| format!("couldn't get versions from {paths_description}"))
+}
+
+pub fn get_from_file<P>(
+    root: &Path,
+    path: P,
+    format: Option<VersionsFormat>,
+    app_filter: Option<&Regex>,
+) -> anyhow::Result<Versions>
 where
     P: AsRef<Path>,
 {
-    let contents = std::fs::read_to_string(&path).with_context(|| {
-        format!(
-            "couldn't read file \"{}\"",
-            &path.as_ref().to_string_lossy()
-        )
-    })?;
+    let contents = read_versions_file(root, path.as_ref())?;
+    let format = get_format(path.as_ref(), &contents, format);
 
-    let versions = get_versions(&contents, app_filter).with_context(|| {
+    let versions = get_versions(&contents, format, root, app_filter).with_context(|| {
         format!(
-            "couldn't get versions from file \"{}\"",
-            &path.as_ref().to_string_lossy()
+            "couldn't get versions from {}",
+            describe_path(path.as_ref())
         )
     })?;
 
     Ok(versions)
 }
 
-pub fn get_versions<S>(contents: S, app_filter: Option<&Regex>) -> anyhow::Result<Versions>
+pub fn get_versions<S>(
+    contents: S,
+    format: VersionsFormat,
+    root: &Path,
+    app_filter: Option<&Regex>,
+) -> anyhow::Result<Versions>
 where
     S: AsRef<str>,
 {
-    let mut raw: RawVersions = toml::from_str(contents.as_ref())?;
+    let raw: RawVersions = parse(contents.as_ref(), format)?;
+
+    get_versions_from_raw(raw, root, app_filter)
+}
+
+fn parse<T>(contents: &str, format: VersionsFormat) -> anyhow::Result<T>
+where
+    T: DeserializeOwned,
+{
+    let parsed = match format {
+        VersionsFormat::Toml => toml::from_str(contents)?,
+        VersionsFormat::Yaml => serde_yaml_ng::from_str(contents)?,
+        VersionsFormat::Json => serde_json::from_str(contents)?,
+    };
+
+    Ok(parsed)
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == STDIN_PATH
+}
+
+fn describe_path(path: &Path) -> String {
+    if is_stdin(path) {
+        "stdin".to_string()
+    } else {
+        format!("file \"{}\"", path.to_string_lossy())
+    }
+}
+
+fn read_versions_file(root: &Path, path: &Path) -> anyhow::Result<String> {
+    if is_stdin(path) {
+        return std::io::read_to_string(std::io::stdin()).context("couldn't read from stdin");
+    }
+
+    std::fs::read_to_string(root.join(path))
+        .with_context(|| format!("couldn't read file \"{}\"", path.to_string_lossy()))
+}
+
+// an explicitly passed format wins; otherwise it's decided by the file's extension, falling back to
+// TOML. Stdin has no extension, so JSON is recognised by its opening brace
+fn get_format(path: &Path, contents: &str, format: Option<VersionsFormat>) -> VersionsFormat {
+    if let Some(format) = format {
+        return format;
+    }
+
+    if is_stdin(path) {
+        return if contents.trim_start().starts_with('{') {
+            VersionsFormat::Json
+        } else {
+            VersionsFormat::Toml
+        };
+    }
+
+    path.extension()
+        .and_then(|e| e.to_str())
+        .and_then(VersionsFormat::from_extension)
+        .unwrap_or(VersionsFormat::Toml)
+}
+
+fn get_versions_from_raw(
+    mut raw: RawVersions,
+    root: &Path,
+    app_filter: Option<&Regex>,
+) -> anyhow::Result<Versions> {
+    if let Some(raw_sources) = raw.sources.take() {
+        let source_versions = sources::get_versions(raw_sources, &raw.envs, root)?;
+        raw.versions.extend(source_versions);
+    }
+
+    let has_endpoints = raw.endpoints.as_ref().is_some_and(|e| !e.is_empty());
+    if raw.versions.is_empty() && !has_endpoints {
+        anyhow::bail!(
+            "no versions found; add [[versions]] entries to the versions file, configure a source under [sources], or configure [endpoints]"
+        );
+    }
 
     if let Some(regex) = app_filter {
         raw.versions.retain(|v| regex.is_match(&v.app));
+        if let Some(endpoints) = raw.endpoints.as_mut() {
+            endpoints.retain(|app, _| regex.is_match(app));
+        }
+        if let Some(apps) = raw.apps.as_mut() {
+            apps.retain(|app, _| regex.is_match(app));
+        }
+        // policies scoped to apps that are all filtered out would otherwise fail validation
+        if let Some(policies) = raw.policies.as_mut() {
+            policies.retain(|_, policy| match policy.apps.as_mut() {
+                Some(apps) => {
+                    apps.retain(|app| regex.is_match(app));
+                    !apps.is_empty()
+                }
+                None => true,
+            });
+        }
 
-        if raw.versions.is_empty() {
+        if raw.versions.is_empty() && raw.endpoints.as_ref().is_none_or(|e| e.is_empty()) {
             anyhow::bail!("no versions match the provided filter");
         }
     }
@@ -43,9 +185,240 @@ where
     Ok(versions)
 }
 
+// directories are expanded to the versions files directly inside them, in alphabetical order
+fn resolve_versions_files(root: &Path, paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if !root.join(path).is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let entries = std::fs::read_dir(root.join(path))
+            .with_context(|| format!("couldn't read directory \"{}\"", path.to_string_lossy()))?;
+
+        let mut dir_files = Vec::new();
+        for entry in entries {
+            let entry = entry.with_context(|| {
+                format!("couldn't read directory \"{}\"", path.to_string_lossy())
+            })?;
+            // paths stay relative to the root, so that they're reported the way they were passed
+            let entry_path = path.join(entry.file_name());
+            if root.join(&entry_path).is_file()
+                && entry_path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .and_then(VersionsFormat::from_extension)
+                    .is_some()
+            {
+                dir_files.push(entry_path);
+            }
+        }
+
+        if dir_files.is_empty() {
+            anyhow::bail!(
+                "directory \"{}\" doesn't contain any versions files (with one of these extensions: {})",
+                path.to_string_lossy(),
+                VersionsFormat::EXTENSIONS.join(", ")
+            );
+        }
+
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+
+    // a file passed both on its own and via its directory is only read once
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+
+    Ok(files)
+}
+
+// versions from all files are combined, whereas every other setting can only be set in one of
+// them; each version remembers the file it came from, so that duplicates can be traced back
+fn merge_versions_files(
+    versions_files: Vec<(String, RawVersionsFile)>,
+    envs_override: &[String],
+) -> anyhow::Result<RawVersions> {
+    let mut envs = None;
+    let mut github_org = None;
+    let mut versions: Vec<RawAppVersion> = Vec::new();
+    let mut git_tag_transform = None;
+    let mut comparison_strategy = None;
+    let mut comparison_pairs = None;
+    let mut provider = None;
+    let mut github_api_url = None;
+    let mut gitlab_api_url = None;
+    let mut local_repos_dir = None;
+    let mut apps = None;
+    let mut policies = None;
+    let mut sources = None;
+    let mut endpoints = None;
+
+    for (source, file) in versions_files {
+        set_once("envs", &mut envs, file.envs, &source)?;
+        set_once("github_org", &mut github_org, file.github_org, &source)?;
+        set_once(
+            "git_tag_transform",
+            &mut git_tag_transform,
+            file.git_tag_transform,
+            &source,
+        )?;
+        set_once(
+            "comparison_strategy",
+            &mut comparison_strategy,
+            file.comparison_strategy,
+            &source,
+        )?;
+        set_once(
+            "comparison_pairs",
+            &mut comparison_pairs,
+            file.comparison_pairs,
+            &source,
+        )?;
+        set_once("provider", &mut provider, file.provider, &source)?;
+        set_once(
+            "github_api_url",
+            &mut github_api_url,
+            file.github_api_url,
+            &source,
+        )?;
+        set_once(
+            "gitlab_api_url",
+            &mut gitlab_api_url,
+            file.gitlab_api_url,
+            &source,
+        )?;
+        set_once(
+            "local_repos_dir",
+            &mut local_repos_dir,
+            file.local_repos_dir,
+            &source,
+        )?;
+        set_once("apps", &mut apps, file.apps, &source)?;
+        set_once("policies", &mut policies, file.policies, &source)?;
+        set_once("sources", &mut sources, file.sources, &source)?;
+        set_once("endpoints", &mut endpoints, file.endpoints, &source)?;
+
+        versions.extend(file.versions.into_iter().map(|version| RawAppVersion {
+            source: Some(source.clone()),
+            ..version
+        }));
+    }
+
+    let envs = match (envs_override, envs) {
+        ([], Some((envs, _))) => envs,
+        ([], None) => anyhow::bail!(
+            "envs isn't set in any of the versions files; set it in one of them, or pass --envs"
+        ),
+        (envs_override, _) => envs_override.to_vec(),
+    };
+
+    let Some((github_org, _)) = github_org else {
+        anyhow::bail!("github_org isn't set in any of the versions files");
+    };
+
+    Ok(RawVersions {
+        envs,
+        github_org,
+        versions,
+        git_tag_transform: git_tag_transform.map(|(v, _)| v),
+        comparison_strategy: comparison_strategy.map(|(v, _)| v),
+        comparison_pairs: comparison_pairs.map(|(v, _)| v),
+        provider: provider.map(|(v, _)| v),
+        github_api_url: github_api_url.map(|(v, _)| v),
+        gitlab_api_url: gitlab_api_url.map(|(v, _)| v),
+        local_repos_dir: local_repos_dir.map(|(v, _)| v),
+        apps: apps.map(|(v, _)| v),
+        policies: policies.map(|(v, _)| v),
+        sources: sources.map(|(v, _)| v),
+        endpoints: endpoints.map(|(v, _)| v),
+    })
+}
+
+// keeps track of the file a setting was set in, so that it can be reported if another file sets it
+// as well
+fn set_once<T>(
+    setting: &str,
+    current: &mut Option<(T, String)>,
+    value: Option<T>,
+    source: &str,
+) -> anyhow::Result<()> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+
+    if let Some((_, first_source)) = current {
+        anyhow::bail!(
+            r#"{setting} is set in both "{first_source}" and "{source}", but can only be set in one versions file"#
+        );
+    }
+
+    *current = Some((value, source.to_string()));
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
+
+    const TOP_LEVEL_TOML: &str = r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+"#;
+
+    const DEV_TOML: &str = r#"
+[[versions]]
+app = "repo-a"
+env = "dev"
+version = "0.2.0"
+
+[[versions]]
+app = "repo-b"
+env = "dev"
+version = "1.2.0"
+"#;
+
+    const PROD_TOML: &str = r#"
+[[versions]]
+app = "repo-a"
+env = "prod"
+version = "0.1.0"
+
+[[versions]]
+app = "repo-b"
+env = "prod"
+version = "1.0.0"
+"#;
+
+    fn write_files(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        for (name, contents) in files {
+            std::fs::write(dir.path().join(name), contents).expect("file should've been written");
+        }
+
+        dir
+    }
+
+    fn get_versions_summary(versions: &Versions) -> String {
+        let envs = versions
+            .envs
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let lines = versions
+            .versions
+            .iter()
+            .map(|v| format!("{} {} {}", v.app, v.env, v.version))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("envs: {envs}\n{lines}")
+    }
 
     const VALID_TOML: &str = r#"
 envs = ["dev", "prod"]
@@ -92,7 +465,8 @@ version = "2.0.0"
         // GIVEN
 
         // WHEN
-        let versions = get_versions(VALID_TOML, None).expect("result should've been Ok");
+        let versions = get_versions(VALID_TOML, VersionsFormat::Toml, Path::new(""), None)
+            .expect("result should've been Ok");
 
         // THEN
         let mut settings = insta::Settings::clone_current();
@@ -123,6 +497,14 @@ version = "2.0.0"
                 env: dev
                 version: 2.0.0
             git_tag_transform: "v{{version}}"
+            comparison_strategy: adjacent
+            provider: github
+            github_api_url: "https://api.github.com"
+            gitlab_api_url: "https://gitlab.com/api/v4"
+            local_repos_dir: ~
+            app_configs: {}
+            policies: []
+            endpoints: []
             "#);
         });
     }
@@ -133,7 +515,13 @@ version = "2.0.0"
         let filter = Regex::new("repo-[ab]").unwrap();
 
         // WHEN
-        let versions = get_versions(VALID_TOML, Some(&filter)).expect("result should've been Ok");
+        let versions = get_versions(
+            VALID_TOML,
+            VersionsFormat::Toml,
+            Path::new(""),
+            Some(&filter),
+        )
+        .expect("result should've been Ok");
 
         // THEN
         let mut settings = insta::Settings::clone_current();
@@ -158,10 +546,233 @@ version = "2.0.0"
                 env: dev
                 version: 1.2.0
             git_tag_transform: "v{{version}}"
+            comparison_strategy: adjacent
+            provider: github
+            github_api_url: "https://api.github.com"
+            gitlab_api_url: "https://gitlab.com/api/v4"
+            local_repos_dir: ~
+            app_configs: {}
+            policies: []
+            endpoints: []
             "#);
         });
     }
 
+    #[test]
+    fn reading_versions_from_a_directory_merges_its_files() {
+        // GIVEN
+        let dir = write_files(&[
+            ("dev.toml", DEV_TOML),
+            ("prod.toml", PROD_TOML),
+            ("envee.toml", TOP_LEVEL_TOML),
+            ("README.md", "not a versions file"),
+        ]);
+
+        // WHEN
+        let versions = get_from_paths(Path::new(""), &[dir.path().to_path_buf()], &[], None, None)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_versions_summary(&versions), @r"
+        envs: dev, prod
+        repo-a dev 0.2.0
+        repo-b dev 1.2.0
+        repo-a prod 0.1.0
+        repo-b prod 1.0.0
+        ");
+    }
+
+    #[test]
+    fn reading_versions_from_multiple_files_uses_envs_override() {
+        // GIVEN
+        let dir = write_files(&[
+            ("dev.toml", DEV_TOML),
+            ("prod.toml", PROD_TOML),
+            ("top.toml", TOP_LEVEL_TOML),
+        ]);
+        let paths = ["top.toml", "prod.toml", "dev.toml"].map(|name| dir.path().join(name));
+
+        // WHEN
+        let versions = get_from_paths(
+            Path::new(""),
+            &paths,
+            &["prod".to_string(), "dev".to_string()],
+            None,
+            None,
+        )
+        .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_versions_summary(&versions), @r"
+        envs: prod, dev
+        repo-a prod 0.1.0
+        repo-b prod 1.0.0
+        repo-a dev 0.2.0
+        repo-b dev 1.2.0
+        ");
+    }
+
+    #[test]
+    fn envs_override_works_for_a_single_file_without_envs() {
+        // GIVEN
+        let dir = write_files(&[(
+            "versions.toml",
+            &format!("github_org = \"dhth\"\n{DEV_TOML}{PROD_TOML}"),
+        )]);
+
+        // WHEN
+        let versions = get_from_paths(
+            Path::new(""),
+            &[dir.path().join("versions.toml")],
+            &["dev".to_string(), "prod".to_string()],
+            None,
+            None,
+        )
+        .expect("result should've been Ok");
+
+        // THEN
+        assert_eq!(versions.envs, vec!["dev".into(), "prod".into()]);
+    }
+
+    #[test]
+    fn parsing_yaml_versions_works() {
+        // GIVEN
+        let contents = r#"
+envs: [dev, prod]
+github_org: dhth
+versions:
+  - app: repo-a
+    env: dev
+    version: "0.2.0"
+  - app: repo-a
+    env: prod
+    version: "0.1.0"
+"#;
+
+        // WHEN
+        let versions = get_versions(contents, VersionsFormat::Yaml, Path::new(""), None)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_versions_summary(&versions), @r"
+        envs: dev, prod
+        repo-a dev 0.2.0
+        repo-a prod 0.1.0
+        ");
+    }
+
+    #[test]
+    fn parsing_json_versions_works() {
+        // GIVEN
+        let contents = r#"{
+  "envs": ["dev", "prod"],
+  "github_org": "dhth",
+  "versions": [
+    { "app": "repo-a", "env": "dev", "version": "0.2.0" },
+    { "app": "repo-a", "env": "prod", "version": "0.1.0" }
+  ]
+}"#;
+
+        // WHEN
+        let versions = get_versions(contents, VersionsFormat::Json, Path::new(""), None)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_versions_summary(&versions), @r"
+        envs: dev, prod
+        repo-a dev 0.2.0
+        repo-a prod 0.1.0
+        ");
+    }
+
+    #[test]
+    fn versions_files_in_different_formats_can_be_merged() {
+        // GIVEN
+        let dir = write_files(&[
+            ("envee.toml", TOP_LEVEL_TOML),
+            (
+                "dev.json",
+                r#"{"versions": [{"app": "repo-a", "env": "dev", "version": "0.2.0"}]}"#,
+            ),
+            (
+                "prod.yml",
+                "versions:\n  - app: repo-a\n    env: prod\n    version: \"0.1.0\"\n",
+            ),
+        ]);
+
+        // WHEN
+        let versions = get_from_paths(Path::new(""), &[dir.path().to_path_buf()], &[], None, None)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_versions_summary(&versions), @r"
+        envs: dev, prod
+        repo-a dev 0.2.0
+        repo-a prod 0.1.0
+        ");
+    }
+
+    #[test]
+    fn versions_format_is_detected_from_the_file_extension() {
+        // GIVEN
+        let cases = [
+            ("versions.toml", "", None, VersionsFormat::Toml),
+            ("versions.yaml", "", None, VersionsFormat::Yaml),
+            ("versions.yml", "", None, VersionsFormat::Yaml),
+            ("versions.json", "", None, VersionsFormat::Json),
+            ("versions.txt", "", None, VersionsFormat::Toml),
+            (
+                "versions.txt",
+                "",
+                Some(VersionsFormat::Yaml),
+                VersionsFormat::Yaml,
+            ),
+            (STDIN_PATH, "  {\"envs\": []}", None, VersionsFormat::Json),
+            (STDIN_PATH, "envs = []", None, VersionsFormat::Toml),
+            (
+                STDIN_PATH,
+                "envs: []",
+                Some(VersionsFormat::Yaml),
+                VersionsFormat::Yaml,
+            ),
+        ];
+
+        for (path, contents, format, expected) in cases {
+            // WHEN
+            let got = get_format(Path::new(path), contents, format);
+
+            // THEN
+            assert_eq!(got, expected, "path: {path}, format: {format:?}");
+        }
+    }
+
+    #[test]
+    fn parsing_yaml_versions_keeps_unquoted_versions_as_written() {
+        // GIVEN
+        let contents = r#"
+envs: [dev, prod]
+github_org: dhth
+versions:
+  - app: repo-a
+    env: dev
+    version: 1.10
+  - app: repo-a
+    env: prod
+    version: 1.9
+"#;
+
+        // WHEN
+        let versions = get_versions(contents, VersionsFormat::Yaml, Path::new(""), None)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(get_versions_summary(&versions), @r"
+        envs: dev, prod
+        repo-a dev 1.10
+        repo-a prod 1.9
+        ");
+    }
+
     //------------//
     //  FAILURES  //
     //------------//
@@ -172,7 +783,8 @@ version = "2.0.0"
         let contents = "invalid toml";
 
         // WHEN
-        let error = get_versions(contents, None).expect_err("result should've been an error");
+        let error = get_versions(contents, VersionsFormat::Toml, Path::new(""), None)
+            .expect_err("result should've been an error");
 
         // THEN
         insta::assert_snapshot!(error.to_string(), @r"
@@ -192,7 +804,8 @@ envs = "not an array"
 "#;
 
         // WHEN
-        let error = get_versions(contents, None).expect_err("result should've been an error");
+        let error = get_versions(contents, VersionsFormat::Toml, Path::new(""), None)
+            .expect_err("result should've been an error");
 
         // THEN
 
@@ -214,16 +827,11 @@ github_org = "dhth"
 "#;
 
         // WHEN
-        let error = get_versions(contents, None).expect_err("result should've been an error");
+        let error = get_versions(contents, VersionsFormat::Toml, Path::new(""), None)
+            .expect_err("result should've been an error");
 
         // THEN
-        insta::assert_snapshot!(error.to_string(), @r"
-        TOML parse error at line 1, column 1
-          |
-        1 | 
-          | ^
-        missing field `versions`
-        ");
+        insta::assert_snapshot!(error.to_string(), @"no versions found; add [[versions]] entries to the versions file, configure a source under [sources], or configure [endpoints]");
     }
 
     #[test]
@@ -232,8 +840,13 @@ github_org = "dhth"
         let filter = Regex::new("^nonexistent").unwrap();
 
         // WHEN
-        let error =
-            get_versions(VALID_TOML, Some(&filter)).expect_err("result should've been an error");
+        let error = get_versions(
+            VALID_TOML,
+            VersionsFormat::Toml,
+            Path::new(""),
+            Some(&filter),
+        )
+        .expect_err("result should've been an error");
 
         // THEN
         insta::assert_snapshot!(error.to_string(), @"no versions match the provided filter");
@@ -264,7 +877,8 @@ version = ""
 "#;
 
         // WHEN
-        let error = get_versions(contents, None).expect_err("result should've been an error");
+        let error = get_versions(contents, VersionsFormat::Toml, Path::new(""), None)
+            .expect_err("result should've been an error");
 
         // THEN
         insta::assert_snapshot!(error.to_string(), @r#"
@@ -282,4 +896,176 @@ version = ""
            - version is empty
         "#);
     }
+
+    #[test]
+    fn parsing_versions_with_invalid_policies_fails() {
+        // GIVEN
+        let contents = format!(
+            r#"{VALID_TOML}
+[policies.lag]
+rule = "max-behind"
+env = "prod"
+reference_env = "qa"
+max = "a few minors"
+version = "1.x"
+
+[policies.pin]
+rule = "pinned"
+apps = ["repo-a", "repo-z"]
+env = "prod"
+
+[policies.ahead]
+rule = "no-ahead"
+max = "1 minor"
+
+[policies.unknown]
+rule = "max-ahead"
+"#
+        );
+
+        // WHEN
+        let error = get_versions(contents, VersionsFormat::Toml, Path::new(""), None)
+            .expect_err("result should've been an error");
+
+        // THEN
+        insta::assert_snapshot!(error.to_string(), @r#"
+        versions config has errors:
+         - policies.ahead: max is not used by rule "no-ahead"
+         - policies.lag: reference_env "qa" is not present in envs
+         - policies.lag: max "a few minors" is invalid, expected a count followed by one of major, minor, patch (eg. "1 minor")
+         - policies.lag: version is not used by rule "max-behind"
+         - policies.pin: app "repo-z" is not present in any of the versions configured
+         - policies.pin: version is required for rule "pinned"
+         - policies.unknown: rule "max-ahead" is invalid, expected one of: max-behind, no-ahead, pinned
+        "#);
+    }
+
+    #[test]
+    fn filtering_apps_drops_policies_scoped_to_filtered_out_apps() {
+        // GIVEN
+        let contents = format!(
+            r#"{VALID_TOML}
+[policies.pin-a]
+rule = "pinned"
+apps = ["repo-a"]
+env = "prod"
+version = "0.x"
+
+[policies.pin-bc]
+rule = "pinned"
+apps = ["repo-b", "repo-c"]
+env = "prod"
+version = "1.x"
+
+[policies.pin-c]
+rule = "pinned"
+apps = ["repo-c"]
+env = "prod"
+version = "1.x"
+"#
+        );
+        let filter = Regex::new("repo-[ab]").unwrap();
+
+        // WHEN
+        let versions = get_versions(contents, VersionsFormat::Toml, Path::new(""), Some(&filter))
+            .expect("result should've been Ok");
+
+        // THEN
+        let policies = versions
+            .policies
+            .iter()
+            .map(|p| {
+                let apps = p
+                    .apps
+                    .iter()
+                    .flatten()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>();
+                format!("{}: {}", p.name, apps.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(policies, @r"
+        pin-a: repo-a
+        pin-bc: repo-b
+        ");
+    }
+
+    #[test]
+    fn merging_versions_files_fails_for_duplicate_versions() {
+        // GIVEN
+        let dir = write_files(&[
+            ("dev.toml", DEV_TOML),
+            ("prod.toml", PROD_TOML),
+            ("prod-hotfix.toml", PROD_TOML),
+            ("top.toml", TOP_LEVEL_TOML),
+        ]);
+        let paths = ["top.toml", "dev.toml", "prod.toml", "prod-hotfix.toml"]
+            .map(|name| dir.path().join(name));
+
+        // WHEN
+        let error = get_from_paths(Path::new(""), &paths, &[], None, None)
+            .expect_err("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{:#}", error).replace(&dir.path().to_string_lossy().to_string(), "<DIR>");
+        insta::assert_snapshot!(message, @r#"
+        couldn't get versions from "<DIR>/top.toml", "<DIR>/dev.toml", "<DIR>/prod.toml", "<DIR>/prod-hotfix.toml": versions config has errors:
+         - app "repo-a" has more than one version for env "prod" (in "<DIR>/prod.toml" and "<DIR>/prod-hotfix.toml")
+         - app "repo-b" has more than one version for env "prod" (in "<DIR>/prod.toml" and "<DIR>/prod-hotfix.toml")
+        "#);
+    }
+
+    #[test]
+    fn merging_versions_files_fails_if_a_setting_is_set_in_more_than_one_file() {
+        // GIVEN
+        let dir = write_files(&[
+            ("dev.toml", &format!("github_org = \"other\"\n{DEV_TOML}")),
+            ("top.toml", TOP_LEVEL_TOML),
+        ]);
+        let paths = ["top.toml", "dev.toml"].map(|name| dir.path().join(name));
+
+        // WHEN
+        let error = get_from_paths(Path::new(""), &paths, &[], None, None)
+            .expect_err("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{:#}", error).replace(&dir.path().to_string_lossy().to_string(), "<DIR>");
+        insta::assert_snapshot!(message, @r#"couldn't get versions from "<DIR>/top.toml", "<DIR>/dev.toml": github_org is set in both "<DIR>/top.toml" and "<DIR>/dev.toml", but can only be set in one versions file"#);
+    }
+
+    #[test]
+    fn merging_versions_files_fails_without_envs() {
+        // GIVEN
+        let dir = write_files(&[
+            ("dev.toml", &format!("github_org = \"dhth\"\n{DEV_TOML}")),
+            ("prod.toml", PROD_TOML),
+        ]);
+
+        // WHEN
+        let error = get_from_paths(Path::new(""), &[dir.path().to_path_buf()], &[], None, None)
+            .expect_err("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{:#}", error).replace(&dir.path().to_string_lossy().to_string(), "<DIR>");
+        insta::assert_snapshot!(message, @r#"couldn't get versions from "<DIR>": envs isn't set in any of the versions files; set it in one of them, or pass --envs"#);
+    }
+
+    #[test]
+    fn reading_versions_from_a_directory_without_versions_files_fails() {
+        // GIVEN
+        let dir = write_files(&[("README.md", "not a versions file")]);
+
+        // WHEN
+        let error = get_from_paths(Path::new(""), &[dir.path().to_path_buf()], &[], None, None)
+            .expect_err("result should've been an error");
+
+        // THEN
+        let message =
+            format!("{:#}", error).replace(&dir.path().to_string_lossy().to_string(), "<DIR>");
+        insta::assert_snapshot!(message, @r#"directory "<DIR>" doesn't contain any versions files (with one of these extensions: toml, yaml, yml, json)"#);
+    }
 }
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-pub(super) fn get_humanized_date(dt: &DateTime<Utc>, reference: &DateTime<Utc>) -> String {
+pub(crate) fn get_humanized_date(dt: &DateTime<Utc>, reference: &DateTime<Utc>) -> String {
     let duration = reference.signed_duration_since(dt);
 
     let seconds = duration.num_seconds();
@@ -1,10 +1,12 @@
-use crate::domain::{CommitLog, DiffResult, SyncStatus};
+use super::status::{get_out_of_sync_since_text, get_policies_text, get_status_text};
+use crate::domain::{ChangeKind, CommitLog, DiffResult, SyncStatus, VersionChanges};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use tera::Tera;
 
 const BUILT_IN_TEMPLATE: &str = include_str!("assets/template.html");
+const CHANGES_TEMPLATE: &str = include_str!("assets/changes-template.html");
 
 #[derive(Serialize)]
 struct HtmlData {
@@ -13,6 +15,7 @@ struct HtmlData {
     columns: Vec<String>,
     rows: Vec<HtmlRow>,
     commit_logs: Vec<HtmlCommitLog>,
+    commit_log_groups: Vec<HtmlCommitLogGroup>,
 }
 
 #[derive(Serialize)]
@@ -22,6 +25,18 @@ struct HtmlRow {
 }
 
 #[derive(Serialize)]
+struct HtmlChangeRow {
+    data: Vec<String>,
+    kind: ChangeKind,
+}
+
+#[derive(Serialize)]
+struct HtmlCommitLogGroup {
+    app: String,
+    logs: Vec<HtmlCommitLog>,
+}
+
+#[derive(Clone, Serialize)]
 struct HtmlCommitLog {
     app: String,
     from_env: String,
@@ -30,9 +45,12 @@ struct HtmlCommitLog {
     to_version: String,
     compare_url: Option<String>,
     commits: Vec<HtmlCommit>,
+    total_commits: usize,
+    truncated: bool,
+    from_cache: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 struct HtmlCommit {
     short_sha: String,
     html_url: String,
@@ -67,6 +85,53 @@ pub fn render_html(
     context.insert("columns", &html_data.columns);
     context.insert("rows", &html_data.rows);
     context.insert("commit_logs", &html_data.commit_logs);
+    context.insert("commit_log_groups", &html_data.commit_log_groups);
+
+    tera.render("html", &context)
+        .context("failed to render HTML template")
+}
+
+pub fn render_changes_html(
+    changes: &VersionChanges,
+    commit_logs: &[CommitLog],
+    title: &str,
+    now: DateTime<Utc>,
+) -> Result<String> {
+    let mut tera = Tera::default();
+    tera.add_raw_template("html", CHANGES_TEMPLATE)
+        .context("failed to parse built-in HTML template")?;
+
+    let rows: Vec<HtmlChangeRow> = changes
+        .changes
+        .iter()
+        .map(|change| HtmlChangeRow {
+            data: vec![
+                change.name().to_string(),
+                change.env.to_string(),
+                change.kind.to_string(),
+                change
+                    .old_version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                change
+                    .new_version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            ],
+            kind: change.kind,
+        })
+        .collect();
+    let (_, commit_log_groups) = build_html_commit_logs(commit_logs);
+
+    let mut context = tera::Context::new();
+    context.insert("title", title);
+    context.insert("timestamp", &now.format("%Y-%m-%dT%H:%M:%SZ").to_string());
+    context.insert("columns", &["app", "env", "change", "old", "new"]);
+    context.insert("rows", &rows);
+    context.insert("unchanged", &changes.unchanged);
+    context.insert("commit_log_groups", &commit_log_groups);
 
     tera.render("html", &context)
         .context("failed to render HTML template")
@@ -80,29 +145,31 @@ fn build_html_data(
 ) -> HtmlData {
     let mut columns = vec!["app".to_string()];
     columns.extend(diff_result.envs.iter().map(|e| e.to_string()));
-    columns.push("in-sync".to_string());
+    columns.push("status".to_string());
+    if diff_result.policies_checked {
+        columns.push("policies".to_string());
+    }
+    if diff_result.history_used {
+        columns.push("out of sync since".to_string());
+    }
 
     let rows: Vec<HtmlRow> = diff_result
         .app_results
         .iter()
         .map(|app_result| {
-            let mut row_data = vec![app_result.app.to_string()];
+            let mut row_data = vec![app_result.name().to_string()];
 
             for env in &diff_result.envs {
-                let version_str = app_result
-                    .values
-                    .get(env)
-                    .map(|v| v.to_string())
-                    .unwrap_or_default();
-                row_data.push(version_str);
+                row_data.push(app_result.version_text(env).to_string());
             }
 
-            let sync_status_str = match app_result.sync_status {
-                SyncStatus::InSync => "✓",
-                SyncStatus::OutOfSync => "✗",
-                SyncStatus::NotApplicable => "-",
-            };
-            row_data.push(sync_status_str.to_string());
+            row_data.push(get_status_text(app_result));
+            if diff_result.policies_checked {
+                row_data.push(get_policies_text(app_result));
+            }
+            if diff_result.history_used {
+                row_data.push(get_out_of_sync_since_text(app_result));
+            }
 
             HtmlRow {
                 data: row_data,
@@ -111,6 +178,21 @@ fn build_html_data(
         })
         .collect();
 
+    let (html_commit_logs, commit_log_groups) = build_html_commit_logs(commit_logs);
+
+    HtmlData {
+        title: title.to_string(),
+        timestamp: now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        columns,
+        rows,
+        commit_logs: html_commit_logs,
+        commit_log_groups,
+    }
+}
+
+fn build_html_commit_logs(
+    commit_logs: &[CommitLog],
+) -> (Vec<HtmlCommitLog>, Vec<HtmlCommitLogGroup>) {
     let html_commit_logs: Vec<HtmlCommitLog> = commit_logs
         .iter()
         .map(|log| {
@@ -140,36 +222,45 @@ fn build_html_data(
                 })
                 .collect();
 
-            let compare_url = if !commits.is_empty() {
+            let compare_url = if !commits.is_empty() && !log.html_url.is_empty() {
                 Some(log.html_url.clone())
             } else {
                 None
             };
 
             HtmlCommitLog {
-                app: log.app.to_string(),
+                app: log.name().to_string(),
                 from_env: log.from_env.to_string(),
                 to_env: log.to_env.to_string(),
                 from_version: log.from_version.to_string(),
                 to_version: log.to_version.to_string(),
                 compare_url,
                 commits,
+                total_commits: log.total_commits,
+                truncated: log.is_truncated(),
+                from_cache: log.from_cache,
             }
         })
         .collect();
 
-    HtmlData {
-        title: title.to_string(),
-        timestamp: now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
-        columns,
-        rows,
-        commit_logs: html_commit_logs,
-    }
+    let commit_log_groups = html_commit_logs
+        .chunk_by(|a, b| a.app == b.app)
+        .filter_map(|group| {
+            group.first().map(|first| HtmlCommitLogGroup {
+                app: first.app.clone(),
+                logs: group.to_vec(),
+            })
+        })
+        .collect();
+
+    (html_commit_logs, commit_log_groups)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::testdata::{TEST_HTML_TEMPLATE, get_result_and_commit_logs};
+    use super::super::testdata::{
+        TEST_HTML_TEMPLATE, get_changes_and_commit_logs, get_result_and_commit_logs,
+    };
     use super::*;
     use chrono::TimeZone;
 
@@ -227,21 +318,21 @@ mod tests {
                                     <th class="px-10 py-2">app</th>
                                     <th class="px-10 py-2">dev</th>
                                     <th class="px-10 py-2">prod</th>
-                                    <th class="px-10 py-2">in-sync</th>
+                                    <th class="px-10 py-2">status</th>
                                 </tr>
                             </thead>
                             <tbody>
-                                <tr class="text-[#fb4934]">
+                                <tr class="text-[#fabd2f]">
                                     <td class="px-10 py-2">app-one</td>
                                     <td class="px-10 py-2">1.1.0</td>
                                     <td class="px-10 py-2">1.0.0</td>
-                                    <td class="px-10 py-2">✗</td>
+                                    <td class="px-10 py-2">prod 1 minor behind dev</td>
                                 </tr>
-                                <tr class="text-[#fb4934]">
+                                <tr class="text-[#fabd2f]">
                                     <td class="px-10 py-2">app-two</td>
                                     <td class="px-10 py-2">2.1.0</td>
                                     <td class="px-10 py-2">2.0.0</td>
-                                    <td class="px-10 py-2">✗</td>
+                                    <td class="px-10 py-2">prod 1 minor behind dev</td>
                                 </tr>
                                 <tr class="text-[#b8bb26]">
                                     <td class="px-10 py-2">app-three</td>
@@ -262,7 +353,7 @@ mod tests {
                         <div class="my-4 overflow-x-auto commit-log">
                             <details>
                                 <summary class="text-[#83a598] cursor-pointer max-sm:text-sm">app-one</summary>
-                                <div class="mt-2 max-sm:p-2 p-4 bg-[#2e2c2c] changes-section max-sm:text-xs text-sm">
+                                <div class="mt-2 max-sm:p-2 p-4 bg-[#2e2c2c] changes-section max-sm:text-xs text-sm flex flex-col gap-6">
                                     <div class="flex flex-col items-left gap-4 overflow-x-auto">
                                         <a class="text-[#928374]" href="https://github.com/org/app-one/compare/1.0.0...1.1.0" target="_blank">prod..dev (1.0.0...1.1.0)</a>
                                         <table class="w-full text-left max-sm:text-xs text-sm whitespace-nowrap">
@@ -282,9 +373,10 @@ mod tests {
                         <div class="my-4 overflow-x-auto commit-log">
                             <details>
                                 <summary class="text-[#83a598] cursor-pointer max-sm:text-sm">app-two</summary>
-                                <div class="mt-2 max-sm:p-2 p-4 bg-[#2e2c2c] changes-section max-sm:text-xs text-sm">
+                                <div class="mt-2 max-sm:p-2 p-4 bg-[#2e2c2c] changes-section max-sm:text-xs text-sm flex flex-col gap-6">
                                     <div class="flex flex-col items-left gap-4 overflow-x-auto">
                                         <a class="text-[#928374]" href="https://github.com/org/app-two/compare/2.0.0...2.1.0" target="_blank">prod..dev (2.0.0...2.1.0)</a>
+                                        <p class="text-[#928374] italic" title="served from the local cache">cached</p>
                                         <table class="w-full text-left max-sm:text-xs text-sm whitespace-nowrap">
                                             <tbody>
                                                 <tr class="">
@@ -376,7 +468,7 @@ mod tests {
                 <th>app</th>
                 <th>dev</th>
                 <th>prod</th>
-                <th>in-sync</th>
+                <th>status</th>
               </tr>
             </thead>
             <tbody>
@@ -384,13 +476,13 @@ mod tests {
                 <td>app-one</td>
                 <td>1.1.0</td>
                 <td>1.0.0</td>
-                <td>✗</td>
+                <td>prod 1 minor behind dev</td>
               </tr>
               <tr>
                 <td>app-two</td>
                 <td>2.1.0</td>
                 <td>2.0.0</td>
-                <td>✗</td>
+                <td>prod 1 minor behind dev</td>
               </tr>
               <tr>
                 <td>app-three</td>
@@ -462,15 +554,27 @@ mod tests {
             app_results: vec![
                 AppResult {
                     app: "multi-env-app".into(),
+                    display_name: None,
                     values: app1_values,
                     sync_status: SyncStatus::InSync,
+                    drifts: vec![],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
                 },
                 AppResult {
                     app: "single-env-app".into(),
+                    display_name: None,
                     values: app2_values,
                     sync_status: SyncStatus::NotApplicable,
+                    drifts: vec![],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
                 },
             ],
+            policies_checked: false,
+            history_used: false,
         };
 
         let commit_logs = vec![];
@@ -503,7 +607,7 @@ mod tests {
                 <th>app</th>
                 <th>dev</th>
                 <th>prod</th>
-                <th>in-sync</th>
+                <th>status</th>
               </tr>
             </thead>
             <tbody>
@@ -525,4 +629,154 @@ mod tests {
         </html>
         ");
     }
+
+    #[test]
+    fn changes_html_template_is_rendered_correctly() {
+        // GIVEN
+        let (changes, commit_logs) = get_changes_and_commit_logs();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let html = render_changes_html(&changes, &commit_logs, "changes", now)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(html, @r#"
+        <!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="UTF-8">
+                <meta name="viewport" content="width=device-width, initial-scale=1.0">
+                <script src="https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4"></script>
+                <link rel="icon" href="data:image/svg+xml,<svg xmlns=%22http://www.w3.org/2000/svg%22 viewBox=%220 0 100 100%22><text y=%22.9em%22 font-size=%2290%22>🔢</text></svg>">
+                <title>changes</title>
+                <link rel="preconnect" href="https://fonts.googleapis.com">
+                <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+                <link href="https://fonts.googleapis.com/css2?family=Fira+Mono:wght@400;500;700&family=Open+Sans:ital,wght@0,300..800;1,300..800&display=swap" rel="stylesheet">
+                <style>
+                    body {
+                        font-family: "Open Sans", sans-serif;
+                    }
+                    .changes-section {
+                        font-family: "Fira Mono", monospace;
+                    }
+                    * {
+                        scrollbar-width: thin;
+                    }
+                    .diff-table {
+                        scrollbar-color: #928374 #282828;
+                    }
+                    .commit-log {
+                        scrollbar-color: #928374 #2e2c2c;
+                    }
+                </style>
+            </head>
+            <body class="bg-[#282828]">
+                <div class="w-4/5 max-sm:w-full max-sm:px-4 mx-auto min-h-screen pt-8">
+                    <h1 class="text-[#fbf1c7] text-3xl mb-4 font-semibold">
+                        changes
+                    </h1>
+                    <p class="text-[#928374] italic mt-4">Generated at 2025-01-16T12:00:00Z</p>
+                    <div class="mt-2 overflow-x-auto diff-table">
+                        <table class="table-auto w-full text-right max-sm:text-xs font-semibold whitespace-nowrap">
+                            <thead>
+                                <tr class="text-[#fbf1c7] bg-[#3c3836]">
+                                    <th class="px-10 py-2">app</th>
+                                    <th class="px-10 py-2">env</th>
+                                    <th class="px-10 py-2">change</th>
+                                    <th class="px-10 py-2">old</th>
+                                    <th class="px-10 py-2">new</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                <tr class="text-[#83a598]">
+                                    <td class="px-10 py-2">app-one</td>
+                                    <td class="px-10 py-2">prod</td>
+                                    <td class="px-10 py-2">upgraded</td>
+                                    <td class="px-10 py-2">1.0.0</td>
+                                    <td class="px-10 py-2">1.1.0</td>
+                                </tr>
+                                <tr class="text-[#fabd2f]">
+                                    <td class="px-10 py-2">App Three</td>
+                                    <td class="px-10 py-2">dev</td>
+                                    <td class="px-10 py-2">downgraded</td>
+                                    <td class="px-10 py-2">1.5.1</td>
+                                    <td class="px-10 py-2">1.5.0</td>
+                                </tr>
+                                <tr class="text-[#b8bb26]">
+                                    <td class="px-10 py-2">app-two</td>
+                                    <td class="px-10 py-2">dev</td>
+                                    <td class="px-10 py-2">added</td>
+                                    <td class="px-10 py-2"></td>
+                                    <td class="px-10 py-2">2.1.0</td>
+                                </tr>
+                                <tr class="text-[#fb4934]">
+                                    <td class="px-10 py-2">app-two</td>
+                                    <td class="px-10 py-2">prod</td>
+                                    <td class="px-10 py-2">removed</td>
+                                    <td class="px-10 py-2">2.0.0</td>
+                                    <td class="px-10 py-2"></td>
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                    <p class="text-[#928374] mt-4">Unchanged: 2</p>
+                    <div class="overflow-x-auto">
+                        <div class="flex gap-4 items-center mt-8">
+                            <p class="text-[#fabd2f] text-xl font-semibold">Commits</p>
+                            <button class="bg-[#83a598] text-[#282828] font-semibold text-xs p-2 hover:bg-[#fabd2f]" onclick="toggleAllDetails()">
+                            Toggle All
+                            </button>
+                        </div>
+                        <div class="my-4 overflow-x-auto commit-log">
+                            <details>
+                                <summary class="text-[#83a598] cursor-pointer max-sm:text-sm">app-one</summary>
+                                <div class="mt-2 max-sm:p-2 p-4 bg-[#2e2c2c] changes-section max-sm:text-xs text-sm flex flex-col gap-6">
+                                    <div class="flex flex-col items-left gap-4 overflow-x-auto">
+                                        <a class="text-[#928374]" href="https://github.com/org/app-one/compare/1.0.0...1.1.0" target="_blank">prod (1.0.0...1.1.0)</a>
+                                        <table class="w-full text-left max-sm:text-xs text-sm whitespace-nowrap">
+                                            <tbody>
+                                                <tr class="">
+                                                    <td class="px-4 py-1 text-[#fabd2f]"><a target="_blank" href="https://github.com/org/app-one/commit/abc1234567890">ae7de14</a></td>
+                                                    <td class="px-4 py-1 text-[#83a598]"><a target="_blank" href="https://github.com/org/app-one/commit/abc1234567890">First commit</a></td>
+                                                    <td class="px-4 py-1 text-[#d3869b]"><a target="_blank" href="https://github.com/org/app-one/commit/abc1234567890">User A</a></td>
+                                                    <td class="px-4 py-1 text-[#bdae93]"><a target="_blank" href="https://github.com/org/app-one/commit/abc1234567890">Jan 15, 2025</a></td>
+                                                </tr>
+                                            </tbody>
+                                        </table>
+                                    </div>
+                                </div>
+                            </details>
+                        </div>
+                    </div>
+                    <p class="text-[#928374] italic my-10 pt-2 border-t-2 border-[#92837433]">Built using <a class="font-bold" href="https://github.com/dhth/envee" target="_blank">envee</a></p>
+                </div>
+                <button id="scrollToTop" onclick="window.scrollTo({top: 0, behavior: 'instant'});"
+                    class="hidden fixed bottom-4 left-4 z-50 bg-[#928374] text-[#282828] px-4 py-2 rounded-full shadow-lg hover:bg-[#d3869b] font-bold transition"
+                    aria-label="Go to top">
+                ↑
+                </button>
+            </body>
+            <script>
+                const scrollToTopButton = document.getElementById("scrollToTop");
+                let allDetailsOpen = false;
+                
+                function toggleAllDetails() {
+                    allDetailsOpen = !allDetailsOpen;
+                    document.querySelectorAll("details").forEach((detail) => {
+                        detail.open = allDetailsOpen;
+                    });
+                }
+                
+                window.addEventListener("scroll", function () {
+                    if (window.scrollY > 100) {
+                        scrollToTopButton.classList.remove("hidden");
+                    } else {
+                        scrollToTopButton.classList.add("hidden");
+                    }
+                });
+            </script>
+        </html>
+        "#);
+    }
 }
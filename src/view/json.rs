@@ -0,0 +1,789 @@
+use crate::domain::{
+    ChangeKind, CommitLog, CommitLogFetchError, CommitLogResults, DiffResult, DriftAmount,
+    HistoryReport, SyncStatus, VersionChanges, VersionDrift,
+};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// bump this whenever a field is removed, renamed, or changes its meaning; adding new fields
+// doesn't require a bump
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonReport {
+    schema_version: u32,
+    generated_at: DateTime<Utc>,
+    envs: Vec<String>,
+    apps: Vec<JsonApp>,
+    commit_logs: Option<Vec<JsonCommitLog>>,
+    commit_log_errors: Option<Vec<JsonCommitLogError>>,
+}
+
+#[derive(Serialize)]
+struct JsonApp {
+    app: String,
+    display_name: Option<String>,
+    versions: BTreeMap<String, String>,
+    // envs whose version couldn't be fetched
+    unknown_envs: Vec<String>,
+    sync_status: SyncStatus,
+    drifts: Vec<JsonDrift>,
+    // None when the versions file has no policies
+    policy_violations: Option<Vec<JsonPolicyViolation>>,
+    // None when the app is in sync, or no history of runs is kept
+    out_of_sync_since: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct JsonDrift {
+    env: String,
+    reference_env: String,
+    direction: &'static str,
+    component: Option<&'static str>,
+    count: Option<u64>,
+    description: String,
+}
+
+#[derive(Serialize)]
+struct JsonPolicyViolation {
+    policy: String,
+    env: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct JsonChangesReport {
+    schema_version: u32,
+    generated_at: DateTime<Utc>,
+    envs: Vec<String>,
+    changes: Vec<JsonChange>,
+    // the number of (app, env) pairs whose version stayed the same
+    unchanged: usize,
+    commit_logs: Option<Vec<JsonCommitLog>>,
+    commit_log_errors: Option<Vec<JsonCommitLogError>>,
+}
+
+#[derive(Serialize)]
+struct JsonChange {
+    app: String,
+    display_name: Option<String>,
+    env: String,
+    kind: ChangeKind,
+    old_version: Option<String>,
+    new_version: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonHistoryReport {
+    schema_version: u32,
+    generated_at: DateTime<Utc>,
+    // the number of recorded changes the report is based on; runs that didn't change anything
+    // aren't recorded
+    changes: usize,
+    first_change_at: Option<DateTime<Utc>>,
+    last_change_at: Option<DateTime<Utc>>,
+    apps: Vec<JsonAppHistory>,
+    envs: Vec<JsonEnvDeployments>,
+}
+
+#[derive(Serialize)]
+struct JsonAppHistory {
+    app: String,
+    // as of the latest run the app was a part of
+    sync_status: SyncStatus,
+    out_of_sync_since: Option<DateTime<Utc>>,
+    timelines: BTreeMap<String, Vec<JsonDeployment>>,
+}
+
+#[derive(Serialize)]
+struct JsonDeployment {
+    version: String,
+    seen_at: DateTime<Utc>,
+    // whether this is the version the app had when it was first seen in the env
+    first_seen: bool,
+}
+
+#[derive(Serialize)]
+struct JsonEnvDeployments {
+    env: String,
+    deployments: usize,
+    per_week: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct JsonCommitLog {
+    app: String,
+    display_name: Option<String>,
+    from_env: String,
+    to_env: String,
+    from_version: String,
+    to_version: String,
+    compare_url: String,
+    commits: Vec<JsonCommit>,
+    total_commits: usize,
+    truncated: bool,
+}
+
+#[derive(Serialize)]
+struct JsonCommit {
+    sha: String,
+    message: String,
+    author: String,
+    date: DateTime<Utc>,
+    html_url: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JsonCommitLogError {
+    App {
+        app: String,
+        from_env: String,
+        to_env: String,
+        message: String,
+    },
+    System {
+        message: String,
+    },
+}
+
+pub fn render_json(
+    diff_result: &DiffResult,
+    commit_log_results: Option<&CommitLogResults>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<String> {
+    let report = build_json_report(diff_result, commit_log_results, now);
+
+    serde_json::to_string_pretty(&report).context("failed to serialize results to JSON")
+}
+
+pub fn render_changes_json(
+    changes: &VersionChanges,
+    commit_log_results: Option<&CommitLogResults>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<String> {
+    let report = JsonChangesReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        generated_at: now,
+        envs: changes.envs.iter().map(|e| e.to_string()).collect(),
+        changes: changes
+            .changes
+            .iter()
+            .map(|change| JsonChange {
+                app: change.app.to_string(),
+                display_name: change.display_name.as_ref().map(|n| n.to_string()),
+                env: change.env.to_string(),
+                kind: change.kind,
+                old_version: change.old_version.as_ref().map(|v| v.to_string()),
+                new_version: change.new_version.as_ref().map(|v| v.to_string()),
+            })
+            .collect(),
+        unchanged: changes.unchanged,
+        commit_logs: commit_log_results
+            .map(|results| results.logs.iter().map(build_json_commit_log).collect()),
+        commit_log_errors: commit_log_results.map(build_json_commit_log_errors),
+    };
+
+    serde_json::to_string_pretty(&report).context("failed to serialize changes to JSON")
+}
+
+pub fn render_history_json(report: &HistoryReport, now: DateTime<Utc>) -> anyhow::Result<String> {
+    let report = JsonHistoryReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        generated_at: now,
+        changes: report.changes,
+        first_change_at: report.first_change_at,
+        last_change_at: report.last_change_at,
+        apps: report
+            .apps
+            .iter()
+            .map(|app| JsonAppHistory {
+                app: app.app.clone(),
+                sync_status: app.sync_status.clone(),
+                out_of_sync_since: app.out_of_sync_since,
+                timelines: app
+                    .timelines
+                    .iter()
+                    .map(|timeline| {
+                        (
+                            timeline.env.clone(),
+                            timeline
+                                .deployments
+                                .iter()
+                                .map(|d| JsonDeployment {
+                                    version: d.version.clone(),
+                                    seen_at: d.seen_at,
+                                    first_seen: d.first_seen,
+                                })
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            })
+            .collect(),
+        envs: report
+            .env_deployments
+            .iter()
+            .map(|env| JsonEnvDeployments {
+                env: env.env.clone(),
+                deployments: env.deployments,
+                per_week: env.per_week,
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&report).context("failed to serialize history to JSON")
+}
+
+fn build_json_report(
+    diff_result: &DiffResult,
+    commit_log_results: Option<&CommitLogResults>,
+    now: DateTime<Utc>,
+) -> JsonReport {
+    let apps = diff_result
+        .app_results
+        .iter()
+        .map(|app_result| JsonApp {
+            app: app_result.app.to_string(),
+            display_name: app_result.display_name.as_ref().map(|n| n.to_string()),
+            versions: app_result
+                .values
+                .iter()
+                .map(|(env, version)| (env.to_string(), version.to_string()))
+                .collect(),
+            unknown_envs: app_result
+                .unknown_envs
+                .iter()
+                .map(|env| env.to_string())
+                .collect(),
+            sync_status: app_result.sync_status.clone(),
+            drifts: app_result
+                .drifts
+                .iter()
+                .map(|d| {
+                    let (direction, amount) = match d.drift {
+                        VersionDrift::Behind(amount) => ("behind", Some(amount)),
+                        VersionDrift::Ahead(amount) => ("ahead", Some(amount)),
+                        VersionDrift::Unordered => ("unordered", None),
+                    };
+                    let (component, count) = match amount {
+                        Some(DriftAmount::Major(n)) => (Some("major"), Some(n)),
+                        Some(DriftAmount::Minor(n)) => (Some("minor"), Some(n)),
+                        Some(DriftAmount::Patch(n)) => (Some("patch"), Some(n)),
+                        Some(DriftAmount::PreRelease) => (Some("pre_release"), None),
+                        None => (None, None),
+                    };
+
+                    JsonDrift {
+                        env: d.env.to_string(),
+                        reference_env: d.reference_env.to_string(),
+                        direction,
+                        component,
+                        count,
+                        description: d.to_string(),
+                    }
+                })
+                .collect(),
+            policy_violations: diff_result.policies_checked.then(|| {
+                app_result
+                    .policy_violations
+                    .iter()
+                    .map(|v| JsonPolicyViolation {
+                        policy: v.policy.clone(),
+                        env: v.env.to_string(),
+                        message: v.message.clone(),
+                    })
+                    .collect()
+            }),
+            out_of_sync_since: app_result
+                .out_of_sync_since
+                .filter(|_| app_result.sync_status.is_out_of_sync()),
+        })
+        .collect();
+
+    let commit_logs =
+        commit_log_results.map(|results| results.logs.iter().map(build_json_commit_log).collect());
+
+    let commit_log_errors = commit_log_results.map(build_json_commit_log_errors);
+
+    JsonReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        generated_at: now,
+        envs: diff_result.envs.iter().map(|e| e.to_string()).collect(),
+        apps,
+        commit_logs,
+        commit_log_errors,
+    }
+}
+
+fn build_json_commit_log_errors(results: &CommitLogResults) -> Vec<JsonCommitLogError> {
+    results
+        .errors
+        .iter()
+        .map(|error| match error {
+            CommitLogFetchError::App { app, pair, error } => JsonCommitLogError::App {
+                app: app.to_string(),
+                from_env: pair.from.to_string(),
+                to_env: pair.to.to_string(),
+                message: format!("{error:#}"),
+            },
+            CommitLogFetchError::System { error } => JsonCommitLogError::System {
+                message: format!("{error:#}"),
+            },
+        })
+        .collect()
+}
+
+fn build_json_commit_log(log: &CommitLog) -> JsonCommitLog {
+    JsonCommitLog {
+        app: log.app.to_string(),
+        display_name: log.display_name.as_ref().map(|n| n.to_string()),
+        from_env: log.from_env.to_string(),
+        to_env: log.to_env.to_string(),
+        from_version: log.from_version.to_string(),
+        to_version: log.to_version.to_string(),
+        compare_url: log.html_url.clone(),
+        commits: log
+            .commits
+            .iter()
+            .map(|commit| JsonCommit {
+                sha: commit.sha.clone(),
+                message: commit.commit.message.clone(),
+                author: commit.commit.author.name.clone(),
+                date: commit.commit.author.date,
+                html_url: commit.html_url.clone(),
+            })
+            .collect(),
+        total_commits: log.total_commits,
+        truncated: log.is_truncated(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testdata::{
+        get_changes_and_commit_logs, get_history_report, get_result_and_commit_logs,
+    };
+    use super::*;
+    use crate::domain::{CommitLogFetchErrors, EnvPair};
+    use chrono::TimeZone;
+
+    #[test]
+    fn json_report_is_rendered_correctly() {
+        // GIVEN
+        let (diff_result, logs) = get_result_and_commit_logs();
+        let mut errors = CommitLogFetchErrors::new();
+        errors.add_app_error(
+            "app-three".into(),
+            EnvPair {
+                from: "prod".into(),
+                to: "dev".into(),
+            },
+            anyhow::anyhow!("GitHub API request failed with status 404 Not Found"),
+        );
+        let results = CommitLogResults { logs, errors };
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let json =
+            render_json(&diff_result, Some(&results), now).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(json, @r#"
+        {
+          "schema_version": 1,
+          "generated_at": "2025-01-16T12:00:00Z",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "app-one",
+              "display_name": null,
+              "versions": {
+                "dev": "1.1.0",
+                "prod": "1.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            },
+            {
+              "app": "app-two",
+              "display_name": null,
+              "versions": {
+                "dev": "2.1.0",
+                "prod": "2.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            },
+            {
+              "app": "app-three",
+              "display_name": null,
+              "versions": {
+                "dev": "1.5.0",
+                "prod": "1.5.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "in_sync",
+              "drifts": [],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": [
+            {
+              "app": "app-one",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "dev",
+              "from_version": "1.0.0",
+              "to_version": "1.1.0",
+              "compare_url": "https://github.com/org/app-one/compare/1.0.0...1.1.0",
+              "commits": [
+                {
+                  "sha": "ae7de14",
+                  "message": "First commit",
+                  "author": "User A",
+                  "date": "2025-01-15T10:00:00Z",
+                  "html_url": "https://github.com/org/app-one/commit/abc1234567890"
+                }
+              ],
+              "total_commits": 1,
+              "truncated": false
+            },
+            {
+              "app": "app-two",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "dev",
+              "from_version": "2.0.0",
+              "to_version": "2.1.0",
+              "compare_url": "https://github.com/org/app-two/compare/2.0.0...2.1.0",
+              "commits": [
+                {
+                  "sha": "1443d43",
+                  "message": "add cli test for when no versions match app filter",
+                  "author": "User A",
+                  "date": "2025-01-16T11:30:00Z",
+                  "html_url": "https://github.com/org/app-two/commit/1443d43"
+                },
+                {
+                  "sha": "c536d77",
+                  "message": "allow filtering apps to run for (#3) commit",
+                  "author": "User B",
+                  "date": "2025-01-16T11:00:00Z",
+                  "html_url": "https://github.com/org/app-two/commit/c536d77"
+                },
+                {
+                  "sha": "2ff3e97",
+                  "message": "allow configuring table style (#2) commit",
+                  "author": "User A",
+                  "date": "2025-01-15T10:00:00Z",
+                  "html_url": "https://github.com/org/app-two/commit/2ff3e97"
+                }
+              ],
+              "total_commits": 3,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": [
+            {
+              "kind": "app",
+              "app": "app-three",
+              "from_env": "prod",
+              "to_env": "dev",
+              "message": "GitHub API request failed with status 404 Not Found"
+            }
+          ]
+        }
+        "#);
+    }
+
+    #[test]
+    fn json_report_without_commit_logs_is_rendered_correctly() {
+        // GIVEN
+        let (diff_result, _) = get_result_and_commit_logs();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let json = render_json(&diff_result, None, now).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(json, @r#"
+        {
+          "schema_version": 1,
+          "generated_at": "2025-01-16T12:00:00Z",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "app-one",
+              "display_name": null,
+              "versions": {
+                "dev": "1.1.0",
+                "prod": "1.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            },
+            {
+              "app": "app-two",
+              "display_name": null,
+              "versions": {
+                "dev": "2.1.0",
+                "prod": "2.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            },
+            {
+              "app": "app-three",
+              "display_name": null,
+              "versions": {
+                "dev": "1.5.0",
+                "prod": "1.5.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "in_sync",
+              "drifts": [],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": null,
+          "commit_log_errors": null
+        }
+        "#);
+    }
+
+    #[test]
+    fn json_changes_report_is_rendered_correctly() {
+        // GIVEN
+        let (changes, logs) = get_changes_and_commit_logs();
+        let results = CommitLogResults {
+            logs,
+            errors: CommitLogFetchErrors::new(),
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let json =
+            render_changes_json(&changes, Some(&results), now).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(json, @r#"
+        {
+          "schema_version": 1,
+          "generated_at": "2025-01-16T12:00:00Z",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "changes": [
+            {
+              "app": "app-one",
+              "display_name": null,
+              "env": "prod",
+              "kind": "upgraded",
+              "old_version": "1.0.0",
+              "new_version": "1.1.0"
+            },
+            {
+              "app": "app-three",
+              "display_name": "App Three",
+              "env": "dev",
+              "kind": "downgraded",
+              "old_version": "1.5.1",
+              "new_version": "1.5.0"
+            },
+            {
+              "app": "app-two",
+              "display_name": null,
+              "env": "dev",
+              "kind": "added",
+              "old_version": null,
+              "new_version": "2.1.0"
+            },
+            {
+              "app": "app-two",
+              "display_name": null,
+              "env": "prod",
+              "kind": "removed",
+              "old_version": "2.0.0",
+              "new_version": null
+            }
+          ],
+          "unchanged": 2,
+          "commit_logs": [
+            {
+              "app": "app-one",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "prod",
+              "from_version": "1.0.0",
+              "to_version": "1.1.0",
+              "compare_url": "https://github.com/org/app-one/compare/1.0.0...1.1.0",
+              "commits": [
+                {
+                  "sha": "ae7de14",
+                  "message": "First commit",
+                  "author": "User A",
+                  "date": "2025-01-15T10:00:00Z",
+                  "html_url": "https://github.com/org/app-one/commit/abc1234567890"
+                }
+              ],
+              "total_commits": 1,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": []
+        }
+        "#);
+    }
+
+    #[test]
+    fn json_history_report_is_rendered_correctly() {
+        // GIVEN
+        let report = get_history_report();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let result = render_history_json(&report, now).expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(result, @r#"
+        {
+          "schema_version": 1,
+          "generated_at": "2025-01-16T12:00:00Z",
+          "changes": 4,
+          "first_change_at": "2025-01-01T10:00:00Z",
+          "last_change_at": "2025-01-15T10:00:00Z",
+          "apps": [
+            {
+              "app": "app-one",
+              "sync_status": "behind",
+              "out_of_sync_since": "2025-01-08T10:00:00Z",
+              "timelines": {
+                "dev": [
+                  {
+                    "version": "1.0.0",
+                    "seen_at": "2025-01-01T10:00:00Z",
+                    "first_seen": true
+                  },
+                  {
+                    "version": "1.1.0",
+                    "seen_at": "2025-01-08T10:00:00Z",
+                    "first_seen": false
+                  }
+                ],
+                "prod": [
+                  {
+                    "version": "1.0.0",
+                    "seen_at": "2025-01-01T10:00:00Z",
+                    "first_seen": true
+                  }
+                ]
+              }
+            },
+            {
+              "app": "app-two",
+              "sync_status": "in_sync",
+              "out_of_sync_since": null,
+              "timelines": {
+                "dev": [
+                  {
+                    "version": "2.0.0",
+                    "seen_at": "2025-01-01T10:00:00Z",
+                    "first_seen": true
+                  },
+                  {
+                    "version": "2.1.0",
+                    "seen_at": "2025-01-02T10:00:00Z",
+                    "first_seen": false
+                  }
+                ],
+                "prod": [
+                  {
+                    "version": "2.0.0",
+                    "seen_at": "2025-01-01T10:00:00Z",
+                    "first_seen": true
+                  },
+                  {
+                    "version": "2.1.0",
+                    "seen_at": "2025-01-15T10:00:00Z",
+                    "first_seen": false
+                  }
+                ]
+              }
+            }
+          ],
+          "envs": [
+            {
+              "env": "dev",
+              "deployments": 2,
+              "per_week": 1.0
+            },
+            {
+              "env": "prod",
+              "deployments": 1,
+              "per_week": 0.5
+            }
+          ]
+        }
+        "#);
+    }
+}
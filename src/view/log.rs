@@ -15,6 +15,7 @@ const AUTHOR_COLOR_POOL: [TableColor; 6] = [
 ];
 
 const COMMIT_MESSAGE_MAX_LENGTH: usize = 80;
+const PAIR_INDENT: &str = "  ";
 
 pub fn render_commit_logs(
     logs: &[CommitLog],
@@ -22,50 +23,45 @@ pub fn render_commit_logs(
     plain_output: bool,
 ) -> String {
     let mut output = String::new();
+    let groups: Vec<_> = logs.chunk_by(|a, b| a.app == b.app).collect();
 
-    for (i, log) in logs.iter().enumerate() {
-        output.push_str(&format!(
-            "{} {}..{} ({}..{})\n\n",
-            log.app, log.from_env, log.to_env, log.from_version, log.to_version
-        ));
-
-        let mut table = Table::new();
-        table.load_preset(presets::NOTHING);
-
-        for commit in &log.commits {
-            let short_sha = &commit.sha[..7.min(commit.sha.len())];
-            let first_line = commit
-                .commit
-                .message
-                .lines()
-                .next()
-                .unwrap_or(&commit.commit.message);
-
-            let truncated_message = truncate_message(first_line, COMMIT_MESSAGE_MAX_LENGTH);
-            let relative_time = get_humanized_date(&commit.commit.author.date, &reference_time);
-
-            if plain_output {
-                table.add_row(vec![
-                    short_sha,
-                    &truncated_message,
-                    &commit.commit.author.name,
-                    &relative_time,
-                ]);
+    for (i, group) in groups.iter().enumerate() {
+        let Some(first) = group.first() else {
+            continue;
+        };
+
+        output.push_str(&format!("{}\n", first.name()));
+
+        for log in group.iter() {
+            // logs of an upgrade within a single env, eg. between two versions files, only
+            // have one env to show
+            let envs = if log.from_env == log.to_env {
+                log.to_env.to_string()
             } else {
-                let author_color = get_author_color(&commit.commit.author.name);
-                table.add_row(vec![
-                    Cell::new(short_sha).fg(TableColor::Grey),
-                    Cell::new(&truncated_message),
-                    Cell::new(&commit.commit.author.name).fg(author_color),
-                    Cell::new(&relative_time).fg(TableColor::Yellow),
-                ]);
+                format!("{}..{}", log.from_env, log.to_env)
+            };
+            output.push_str(&format!(
+                "\n{}{} ({}..{})",
+                PAIR_INDENT, envs, log.from_version, log.to_version
+            ));
+            if log.is_truncated() {
+                output.push_str(&format!(
+                    " [showing {} of {} commits]",
+                    log.commits.len(),
+                    log.total_commits
+                ));
             }
-        }
+            output.push_str("\n\n");
 
-        output.push_str(&table.to_string());
-        output.push('\n');
+            let table = render_commits_table(log, reference_time, plain_output);
+            for line in table.lines() {
+                output.push_str(PAIR_INDENT);
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
 
-        if i < logs.len() - 1 {
+        if i < groups.len() - 1 {
             output.push('\n');
         }
     }
@@ -73,6 +69,47 @@ pub fn render_commit_logs(
     output
 }
 
+fn render_commits_table(
+    log: &CommitLog,
+    reference_time: DateTime<Utc>,
+    plain_output: bool,
+) -> String {
+    let mut table = Table::new();
+    table.load_preset(presets::NOTHING);
+
+    for commit in &log.commits {
+        let short_sha = &commit.sha[..7.min(commit.sha.len())];
+        let first_line = commit
+            .commit
+            .message
+            .lines()
+            .next()
+            .unwrap_or(&commit.commit.message);
+
+        let truncated_message = truncate_message(first_line, COMMIT_MESSAGE_MAX_LENGTH);
+        let relative_time = get_humanized_date(&commit.commit.author.date, &reference_time);
+
+        if plain_output {
+            table.add_row(vec![
+                short_sha,
+                &truncated_message,
+                &commit.commit.author.name,
+                &relative_time,
+            ]);
+        } else {
+            let author_color = get_author_color(&commit.commit.author.name);
+            table.add_row(vec![
+                Cell::new(short_sha).fg(TableColor::Grey),
+                Cell::new(&truncated_message),
+                Cell::new(&commit.commit.author.name).fg(author_color),
+                Cell::new(&relative_time).fg(TableColor::Yellow),
+            ]);
+        }
+    }
+
+    table.to_string()
+}
+
 fn get_author_color(author_name: &str) -> TableColor {
     let mut hasher = DefaultHasher::new();
     author_name.hash(&mut hasher);
@@ -109,15 +146,19 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(result, @r"
-        app-one prod..dev (1.0.0..1.1.0)
+        app-one
+
+          prod..dev (1.0.0..1.1.0)
 
-         ae7de14  First commit  User A  1d ago 
+           ae7de14  First commit  User A  1d ago 
 
-        app-two prod..dev (2.0.0..2.1.0)
+        app-two
 
-         1443d43  add cli test for when no versions match app filter  User A  30m ago 
-         c536d77  allow filtering apps to run for (#3) commit         User B  1h ago  
-         2ff3e97  allow configuring table style (#2) commit           User A  1d ago
+          prod..dev (2.0.0..2.1.0)
+
+           1443d43  add cli test for when no versions match app filter  User A  30m ago 
+           c536d77  allow filtering apps to run for (#3) commit         User B  1h ago  
+           2ff3e97  allow configuring table style (#2) commit           User A  1d ago
         ");
     }
 
@@ -128,6 +169,7 @@ mod tests {
 
         let log = CommitLog {
             app: "app-two".into(),
+            display_name: None,
             from_env: "prod".into(),
             to_env: "dev".into(),
             from_version: "2.0.0".into(),
@@ -147,6 +189,8 @@ mod tests {
                 },
             ],
             html_url: "https://github.com/org/app-two/compare/2.0.0...2.1.0".to_string(),
+            total_commits: 1,
+            from_cache: false,
         };
 
         // WHEN
@@ -154,9 +198,93 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(result, @r"
-        app-two prod..dev (2.0.0..2.1.0)
+        app-two
+
+          prod..dev (2.0.0..2.1.0)
+
+           1443d43  add cli test for when no application versions match app filter (this commit i...  User A  30m ago
+        ");
+    }
+
+    #[test]
+    fn commit_logs_for_multiple_env_pairs_are_grouped_by_app() {
+        // GIVEN
+        let reference = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+        let commit = |sha: &str, message: &str| Commit {
+            sha: sha.to_string(),
+            commit: CommitDetail {
+                message: message.to_string(),
+                author: Author {
+                    name: "User A".to_string(),
+                    date: Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap(),
+                },
+            },
+            html_url: format!("https://github.com/org/app-one/commit/{sha}"),
+        };
+
+        let logs = vec![
+            CommitLog {
+                app: "app-one".into(),
+                display_name: None,
+                from_env: "prod".into(),
+                to_env: "staging".into(),
+                from_version: "1.0.0".into(),
+                to_version: "1.1.0".into(),
+                commits: vec![commit("ae7de14", "First commit")],
+                html_url: "https://github.com/org/app-one/compare/1.0.0...1.1.0".to_string(),
+                total_commits: 4,
+                from_cache: false,
+            },
+            CommitLog {
+                app: "app-one".into(),
+                display_name: None,
+                from_env: "staging".into(),
+                to_env: "dev".into(),
+                from_version: "1.1.0".into(),
+                to_version: "1.2.0".into(),
+                commits: vec![
+                    commit("1443d43", "Third commit"),
+                    commit("c536d77", "Second commit"),
+                ],
+                html_url: "https://github.com/org/app-one/compare/1.1.0...1.2.0".to_string(),
+                total_commits: 2,
+                from_cache: false,
+            },
+            CommitLog {
+                app: "app-two".into(),
+                display_name: None,
+                from_env: "staging".into(),
+                to_env: "dev".into(),
+                from_version: "2.0.0".into(),
+                to_version: "2.1.0".into(),
+                commits: vec![commit("2ff3e97", "Another commit")],
+                html_url: "https://github.com/org/app-two/compare/2.0.0...2.1.0".to_string(),
+                total_commits: 1,
+                from_cache: false,
+            },
+        ];
+
+        // WHEN
+        let result = render_commit_logs(&logs, reference, true);
+
+        // THEN
+        insta::assert_snapshot!(result, @r"
+        app-one
+
+          prod..staging (1.0.0..1.1.0) [showing 1 of 4 commits]
+
+           ae7de14  First commit  User A  1d ago 
+
+          staging..dev (1.1.0..1.2.0)
+
+           1443d43  Third commit   User A  1d ago 
+           c536d77  Second commit  User A  1d ago 
+
+        app-two
+
+          staging..dev (2.0.0..2.1.0)
 
-         1443d43  add cli test for when no application versions match app filter (this commit i...  User A  30m ago
+           2ff3e97  Another commit  User A  1d ago
         ");
     }
 
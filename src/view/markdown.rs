@@ -0,0 +1,524 @@
+use super::status::{get_out_of_sync_since_text, get_policies_text, get_status_text};
+use crate::domain::{CommitLog, DiffResult, VersionChanges};
+use chrono::{DateTime, Utc};
+
+// GitHub rejects comments, and issue/pull request bodies, longer than this many characters
+pub const GITHUB_COMMENT_MAX_LENGTH: usize = 65536;
+// kept free for the note about what was left out, so that adding it never exceeds the limit
+const OMISSION_NOTE_MAX_LENGTH: usize = 200;
+const SHORT_SHA_LENGTH: usize = 7;
+
+pub fn render_markdown(
+    diff_result: &DiffResult,
+    commit_logs: &[CommitLog],
+    title: &str,
+    now: DateTime<Utc>,
+    max_length: usize,
+) -> String {
+    let mut columns = vec!["app".to_string()];
+    columns.extend(diff_result.envs.iter().map(|e| e.to_string()));
+    columns.push("status".to_string());
+    if diff_result.policies_checked {
+        columns.push("policies".to_string());
+    }
+    if diff_result.history_used {
+        columns.push("out of sync since".to_string());
+    }
+
+    let rows: Vec<Vec<String>> = diff_result
+        .app_results
+        .iter()
+        .map(|app_result| {
+            let mut row = vec![app_result.name().to_string()];
+            for env in &diff_result.envs {
+                row.push(app_result.version_text(env).to_string());
+            }
+            row.push(get_status_text(app_result));
+            if diff_result.policies_checked {
+                row.push(get_policies_text(app_result));
+            }
+            if diff_result.history_used {
+                row.push(get_out_of_sync_since_text(app_result));
+            }
+
+            row
+        })
+        .collect();
+
+    let mut writer = MarkdownWriter::new(max_length);
+    writer.push_header(title, now);
+    writer.push_table(&columns, &rows);
+    writer.push_commit_logs(commit_logs);
+
+    writer.finish()
+}
+
+pub fn render_changes_markdown(
+    changes: &VersionChanges,
+    commit_logs: &[CommitLog],
+    title: &str,
+    now: DateTime<Utc>,
+    max_length: usize,
+) -> String {
+    let columns = ["app", "env", "change", "old", "new"].map(String::from);
+    let rows: Vec<Vec<String>> = changes
+        .changes
+        .iter()
+        .map(|change| {
+            vec![
+                change.name().to_string(),
+                change.env.to_string(),
+                change.kind.to_string(),
+                change
+                    .old_version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                change
+                    .new_version
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let mut writer = MarkdownWriter::new(max_length);
+    writer.push_header(title, now);
+    writer.push_table(&columns, &rows);
+    writer.try_push(&format!("\nUnchanged: {}\n", changes.unchanged));
+    writer.push_commit_logs(commit_logs);
+
+    writer.finish()
+}
+
+// content is added piece by piece, for as long as it fits; whatever doesn't is left out (along
+// with everything after it, so that the output never has gaps), and mentioned in a note at the end
+struct MarkdownWriter {
+    output: String,
+    // in characters, which is what GitHub's limits are in
+    length: usize,
+    max_length: usize,
+    budget: usize,
+    omitted_rows: usize,
+    omitted_logs: usize,
+}
+
+impl MarkdownWriter {
+    fn new(max_length: usize) -> Self {
+        Self {
+            output: String::new(),
+            length: 0,
+            max_length,
+            budget: max_length.saturating_sub(OMISSION_NOTE_MAX_LENGTH),
+            omitted_rows: 0,
+            omitted_logs: 0,
+        }
+    }
+
+    fn try_push(&mut self, text: &str) -> bool {
+        let length = text.chars().count();
+        if self.length + length > self.budget {
+            return false;
+        }
+
+        self.output.push_str(text);
+        self.length += length;
+        true
+    }
+
+    fn push_header(&mut self, title: &str, now: DateTime<Utc>) {
+        self.try_push(&format!(
+            "# {}\n\n_Generated at {}_\n",
+            escape_text(title),
+            now.format("%Y-%m-%dT%H:%M:%SZ")
+        ));
+    }
+
+    fn push_table(&mut self, columns: &[String], rows: &[Vec<String>]) {
+        let header = format!(
+            "\n{}{}",
+            get_table_row(columns),
+            get_table_row(&vec!["---".to_string(); columns.len()])
+        );
+        if !self.try_push(&header) {
+            self.omitted_rows += rows.len();
+            return;
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            if !self.try_push(&get_table_row(row)) {
+                self.omitted_rows += rows.len() - i;
+                return;
+            }
+        }
+    }
+
+    fn push_commit_logs(&mut self, commit_logs: &[CommitLog]) {
+        if commit_logs.is_empty() {
+            return;
+        }
+
+        if self.omitted_rows > 0 || !self.try_push("\n## Commit logs\n") {
+            self.omitted_logs += commit_logs.len();
+            return;
+        }
+
+        for (i, log) in commit_logs.iter().enumerate() {
+            if !self.try_push(&get_commit_log_section(log)) {
+                self.omitted_logs += commit_logs.len() - i;
+                return;
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        let mut omitted = Vec::new();
+        if self.omitted_rows > 0 {
+            omitted.push(format!(
+                "{} {}",
+                self.omitted_rows,
+                if self.omitted_rows == 1 {
+                    "row"
+                } else {
+                    "rows"
+                }
+            ));
+        }
+        if self.omitted_logs > 0 {
+            omitted.push(format!(
+                "{} {}",
+                self.omitted_logs,
+                if self.omitted_logs == 1 {
+                    "commit log"
+                } else {
+                    "commit logs"
+                }
+            ));
+        }
+
+        if !omitted.is_empty() {
+            self.output.push_str(&format!(
+                "\n_{} left out to stay within {} characters_\n",
+                omitted.join(" and "),
+                self.max_length
+            ));
+        }
+
+        self.output.truncate(self.output.trim_end().len());
+        self.output
+    }
+}
+
+fn get_commit_log_section(log: &CommitLog) -> String {
+    // logs of an upgrade within a single env, eg. between two versions files, only have one env
+    // to show
+    let envs = if log.from_env == log.to_env {
+        log.to_env.to_string()
+    } else {
+        format!("{}..{}", log.from_env, log.to_env)
+    };
+
+    let mut section = format!(
+        "\n<details>\n<summary>{}: {} ({}..{})</summary>\n\n",
+        escape_text(log.name()),
+        envs,
+        log.from_version,
+        log.to_version
+    );
+
+    if log.commits.is_empty() {
+        section.push_str("No commits\n");
+    } else {
+        if !log.html_url.is_empty() {
+            section.push_str(&format!(
+                "[Compare {}...{}]({})\n\n",
+                log.from_version, log.to_version, log.html_url
+            ));
+        }
+
+        section.push_str(&get_table_row(
+            &["commit", "message", "author", "date"].map(String::from),
+        ));
+        section.push_str(&get_table_row(&vec!["---".to_string(); 4]));
+        for commit in &log.commits {
+            let short_sha = commit
+                .sha
+                .chars()
+                .take(SHORT_SHA_LENGTH)
+                .collect::<String>();
+            // local commits can't be linked to
+            let sha = if commit.html_url.is_empty() {
+                format!("`{short_sha}`")
+            } else {
+                format!("[`{short_sha}`]({})", commit.html_url)
+            };
+            let message = commit
+                .commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or(&commit.commit.message);
+
+            section.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                sha,
+                escape_cell(message),
+                escape_cell(&commit.commit.author.name),
+                commit.commit.author.date.format("%Y-%m-%d")
+            ));
+        }
+
+        if log.is_truncated() {
+            section.push_str(&format!(
+                "\n_Showing {} of {} commits_\n",
+                log.commits.len(),
+                log.total_commits
+            ));
+        }
+    }
+
+    section.push_str("\n</details>\n");
+
+    section
+}
+
+fn get_table_row(cells: &[String]) -> String {
+    let cells = cells
+        .iter()
+        .map(|cell| escape_cell(cell))
+        .collect::<Vec<_>>();
+
+    format!("| {} |\n", cells.join(" | "))
+}
+
+// keeps text, such as commit messages, from being interpreted as HTML
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// pipes would otherwise end table cells early
+fn escape_cell(text: &str) -> String {
+    escape_text(text).replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testdata::{get_changes_and_commit_logs, get_result_and_commit_logs};
+    use super::*;
+    use chrono::TimeZone;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn markdown_is_rendered_correctly() {
+        // GIVEN
+        let (diff_result, commit_logs) = get_result_and_commit_logs();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let markdown = render_markdown(
+            &diff_result,
+            &commit_logs,
+            "versions",
+            now,
+            GITHUB_COMMENT_MAX_LENGTH,
+        );
+
+        // THEN
+        insta::assert_snapshot!(markdown, @r"
+        # versions
+
+        _Generated at 2025-01-16T12:00:00Z_
+
+        | app | dev | prod | status |
+        | --- | --- | --- | --- |
+        | app-one | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+        | app-two | 2.1.0 | 2.0.0 | prod 1 minor behind dev |
+        | app-three | 1.5.0 | 1.5.0 | ✓ |
+
+        ## Commit logs
+
+        <details>
+        <summary>app-one: prod..dev (1.0.0..1.1.0)</summary>
+
+        [Compare 1.0.0...1.1.0](https://github.com/org/app-one/compare/1.0.0...1.1.0)
+
+        | commit | message | author | date |
+        | --- | --- | --- | --- |
+        | [`ae7de14`](https://github.com/org/app-one/commit/abc1234567890) | First commit | User A | 2025-01-15 |
+
+        </details>
+
+        <details>
+        <summary>app-two: prod..dev (2.0.0..2.1.0)</summary>
+
+        [Compare 2.0.0...2.1.0](https://github.com/org/app-two/compare/2.0.0...2.1.0)
+
+        | commit | message | author | date |
+        | --- | --- | --- | --- |
+        | [`1443d43`](https://github.com/org/app-two/commit/1443d43) | add cli test for when no versions match app filter | User A | 2025-01-16 |
+        | [`c536d77`](https://github.com/org/app-two/commit/c536d77) | allow filtering apps to run for (#3) commit | User B | 2025-01-16 |
+        | [`2ff3e97`](https://github.com/org/app-two/commit/2ff3e97) | allow configuring table style (#2) commit | User A | 2025-01-15 |
+
+        </details>
+        ");
+    }
+
+    #[test]
+    fn changes_markdown_is_rendered_correctly() {
+        // GIVEN
+        let (changes, commit_logs) = get_changes_and_commit_logs();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let markdown = render_changes_markdown(
+            &changes,
+            &commit_logs,
+            "changes",
+            now,
+            GITHUB_COMMENT_MAX_LENGTH,
+        );
+
+        // THEN
+        insta::assert_snapshot!(markdown, @r"
+        # changes
+
+        _Generated at 2025-01-16T12:00:00Z_
+
+        | app | env | change | old | new |
+        | --- | --- | --- | --- | --- |
+        | app-one | prod | upgraded | 1.0.0 | 1.1.0 |
+        | App Three | dev | downgraded | 1.5.1 | 1.5.0 |
+        | app-two | dev | added |  | 2.1.0 |
+        | app-two | prod | removed | 2.0.0 |  |
+
+        Unchanged: 2
+
+        ## Commit logs
+
+        <details>
+        <summary>app-one: prod (1.0.0..1.1.0)</summary>
+
+        [Compare 1.0.0...1.1.0](https://github.com/org/app-one/compare/1.0.0...1.1.0)
+
+        | commit | message | author | date |
+        | --- | --- | --- | --- |
+        | [`ae7de14`](https://github.com/org/app-one/commit/abc1234567890) | First commit | User A | 2025-01-15 |
+
+        </details>
+        ");
+    }
+
+    #[test]
+    fn markdown_escapes_text_that_would_break_tables() {
+        // GIVEN
+        let (diff_result, mut commit_logs) = get_result_and_commit_logs();
+        commit_logs.truncate(1);
+        commit_logs[0].commits[0].commit.message = "use <T> | U for args\n\nmore details".into();
+        commit_logs[0].commits[0].html_url = String::new();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let markdown = render_markdown(
+            &diff_result,
+            &commit_logs,
+            "versions",
+            now,
+            GITHUB_COMMENT_MAX_LENGTH,
+        );
+
+        // THEN
+        insta::assert_snapshot!(markdown, @r"
+        # versions
+
+        _Generated at 2025-01-16T12:00:00Z_
+
+        | app | dev | prod | status |
+        | --- | --- | --- | --- |
+        | app-one | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+        | app-two | 2.1.0 | 2.0.0 | prod 1 minor behind dev |
+        | app-three | 1.5.0 | 1.5.0 | ✓ |
+
+        ## Commit logs
+
+        <details>
+        <summary>app-one: prod..dev (1.0.0..1.1.0)</summary>
+
+        [Compare 1.0.0...1.1.0](https://github.com/org/app-one/compare/1.0.0...1.1.0)
+
+        | commit | message | author | date |
+        | --- | --- | --- | --- |
+        | `ae7de14` | use &lt;T&gt; \| U for args | User A | 2025-01-15 |
+
+        </details>
+        ");
+    }
+
+    #[test]
+    fn markdown_leaves_out_commit_logs_that_do_not_fit() {
+        // GIVEN
+        let (diff_result, commit_logs) = get_result_and_commit_logs();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let markdown = render_markdown(&diff_result, &commit_logs, "versions", now, 800);
+
+        // THEN
+        assert!(markdown.chars().count() <= 800);
+        insta::assert_snapshot!(markdown, @r"
+        # versions
+
+        _Generated at 2025-01-16T12:00:00Z_
+
+        | app | dev | prod | status |
+        | --- | --- | --- | --- |
+        | app-one | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+        | app-two | 2.1.0 | 2.0.0 | prod 1 minor behind dev |
+        | app-three | 1.5.0 | 1.5.0 | ✓ |
+
+        ## Commit logs
+
+        <details>
+        <summary>app-one: prod..dev (1.0.0..1.1.0)</summary>
+
+        [Compare 1.0.0...1.1.0](https://github.com/org/app-one/compare/1.0.0...1.1.0)
+
+        | commit | message | author | date |
+        | --- | --- | --- | --- |
+        | [`ae7de14`](https://github.com/org/app-one/commit/abc1234567890) | First commit | User A | 2025-01-15 |
+
+        </details>
+
+        _1 commit log left out to stay within 800 characters_
+        ");
+    }
+
+    #[test]
+    fn markdown_leaves_out_table_rows_that_do_not_fit() {
+        // GIVEN
+        let (diff_result, commit_logs) = get_result_and_commit_logs();
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let markdown = render_markdown(&diff_result, &commit_logs, "versions", now, 390);
+
+        // THEN
+        assert!(markdown.chars().count() <= 390);
+        insta::assert_snapshot!(markdown, @r"
+        # versions
+
+        _Generated at 2025-01-16T12:00:00Z_
+
+        | app | dev | prod | status |
+        | --- | --- | --- | --- |
+        | app-one | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+
+        _2 rows and 2 commit logs left out to stay within 390 characters_
+        ");
+    }
+}
@@ -1,12 +1,20 @@
 mod date;
 mod html;
+mod json;
 mod log;
+mod markdown;
 mod output;
+mod status;
 mod table;
 #[cfg(test)]
 mod testdata;
 
 pub use html::*;
+pub use json::*;
 pub use log::*;
+pub use markdown::*;
 pub use output::*;
 pub use table::*;
+
+pub(crate) use date::get_humanized_date;
+pub(crate) use status::get_status_text;
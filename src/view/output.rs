@@ -1,5 +1,5 @@
 use crate::config::{Config, OutputType};
-use crate::domain::{CommitLogResults, DiffResult};
+use crate::domain::{CommitLogResults, DiffResult, VersionChanges};
 use chrono::{DateTime, Utc};
 
 pub fn render_output(
@@ -36,6 +36,63 @@ pub fn render_output(
                 now,
             )?
         }
+        OutputType::Json => super::render_json(diff_result, commit_log_results, now)?,
+        OutputType::Markdown(markdown_config) => {
+            let commit_logs = commit_log_results.map(|r| &r.logs[..]).unwrap_or(&[]);
+
+            super::render_markdown(
+                diff_result,
+                commit_logs,
+                &markdown_config.title,
+                now,
+                super::GITHUB_COMMENT_MAX_LENGTH,
+            )
+        }
+    };
+
+    Ok(output)
+}
+
+pub fn render_changes_output(
+    changes: &VersionChanges,
+    commit_log_results: Option<&CommitLogResults>,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> anyhow::Result<String> {
+    let output = match &config.output_type {
+        OutputType::Stdout(stdout_config) => {
+            let mut output = super::render_changes_table(changes, stdout_config);
+
+            if let Some(results) = commit_log_results
+                && !results.logs.is_empty()
+            {
+                output.push_str("\n\n");
+                output.push_str(&super::render_commit_logs(
+                    &results.logs,
+                    now,
+                    stdout_config.plain_output,
+                ));
+            }
+
+            output
+        }
+        OutputType::Html(html_config) => {
+            let commit_logs = commit_log_results.map(|r| &r.logs[..]).unwrap_or(&[]);
+
+            super::render_changes_html(changes, commit_logs, &html_config.title, now)?
+        }
+        OutputType::Json => super::render_changes_json(changes, commit_log_results, now)?,
+        OutputType::Markdown(markdown_config) => {
+            let commit_logs = commit_log_results.map(|r| &r.logs[..]).unwrap_or(&[]);
+
+            super::render_changes_markdown(
+                changes,
+                commit_logs,
+                &markdown_config.title,
+                now,
+                super::GITHUB_COMMENT_MAX_LENGTH,
+            )
+        }
     };
 
     Ok(output)
@@ -43,7 +100,9 @@ pub fn render_output(
 
 #[cfg(test)]
 mod tests {
-    use super::super::testdata::{TEST_HTML_TEMPLATE, get_result_and_commit_logs};
+    use super::super::testdata::{
+        TEST_HTML_TEMPLATE, get_changes_and_commit_logs, get_result_and_commit_logs,
+    };
     use super::*;
     use crate::config::{HtmlConfig, StdoutConfig, TableStyle};
     use crate::domain::{CommitLogFetchErrors, CommitLogResults};
@@ -72,23 +131,27 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(result, @r"
-        +----------+-------+-------+---------+
-        |app       | dev   | prod  | in-sync |
-        +====================================+
-        |app-one   | 1.1.0 | 1.0.0 | ✗       |
-        |app-two   | 2.1.0 | 2.0.0 | ✗       |
-        |app-three | 1.5.0 | 1.5.0 | ✓       |
-        +----------+-------+-------+---------+
+        +----------+-------+-------+-------------------------+
+        |app       | dev   | prod  | status                  |
+        +====================================================+
+        |app-one   | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+        |app-two   | 2.1.0 | 2.0.0 | prod 1 minor behind dev |
+        |app-three | 1.5.0 | 1.5.0 | ✓                       |
+        +----------+-------+-------+-------------------------+
+
+        app-one
+
+          prod..dev (1.0.0..1.1.0)
 
-        app-one prod..dev (1.0.0..1.1.0)
+           ae7de14  First commit  User A  1d ago 
 
-         ae7de14  First commit  User A  1d ago 
+        app-two
 
-        app-two prod..dev (2.0.0..2.1.0)
+          prod..dev (2.0.0..2.1.0)
 
-         1443d43  add cli test for when no versions match app filter  User A  30m ago 
-         c536d77  allow filtering apps to run for (#3) commit         User B  1h ago  
-         2ff3e97  allow configuring table style (#2) commit           User A  1d ago
+           1443d43  add cli test for when no versions match app filter  User A  30m ago 
+           c536d77  allow filtering apps to run for (#3) commit         User B  1h ago  
+           2ff3e97  allow configuring table style (#2) commit           User A  1d ago
         ");
     }
 
@@ -111,13 +174,13 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(result, @r"
-        +----------+-------+-------+---------+
-        |app       | dev   | prod  | in-sync |
-        +====================================+
-        |app-one   | 1.1.0 | 1.0.0 | ✗       |
-        |app-two   | 2.1.0 | 2.0.0 | ✗       |
-        |app-three | 1.5.0 | 1.5.0 | ✓       |
-        +----------+-------+-------+---------+
+        +----------+-------+-------+-------------------------+
+        |app       | dev   | prod  | status                  |
+        +====================================================+
+        |app-one   | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+        |app-two   | 2.1.0 | 2.0.0 | prod 1 minor behind dev |
+        |app-three | 1.5.0 | 1.5.0 | ✓                       |
+        +----------+-------+-------+-------------------------+
         ");
     }
 
@@ -143,13 +206,13 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(result, @r"
-        +----------+-------+-------+---------+
-        |app       | dev   | prod  | in-sync |
-        +====================================+
-        |app-one   | 1.1.0 | 1.0.0 | ✗       |
-        |app-two   | 2.1.0 | 2.0.0 | ✗       |
-        |app-three | 1.5.0 | 1.5.0 | ✓       |
-        +----------+-------+-------+---------+
+        +----------+-------+-------+-------------------------+
+        |app       | dev   | prod  | status                  |
+        +====================================================+
+        |app-one   | 1.1.0 | 1.0.0 | prod 1 minor behind dev |
+        |app-two   | 2.1.0 | 2.0.0 | prod 1 minor behind dev |
+        |app-three | 1.5.0 | 1.5.0 | ✓                       |
+        +----------+-------+-------+-------------------------+
         ");
     }
 
@@ -191,7 +254,7 @@ mod tests {
                 <th>app</th>
                 <th>dev</th>
                 <th>prod</th>
-                <th>in-sync</th>
+                <th>status</th>
               </tr>
             </thead>
             <tbody>
@@ -199,13 +262,13 @@ mod tests {
                 <td>app-one</td>
                 <td>1.1.0</td>
                 <td>1.0.0</td>
-                <td>✗</td>
+                <td>prod 1 minor behind dev</td>
               </tr>
               <tr>
                 <td>app-two</td>
                 <td>2.1.0</td>
                 <td>2.0.0</td>
-                <td>✗</td>
+                <td>prod 1 minor behind dev</td>
               </tr>
               <tr>
                 <td>app-three</td>
@@ -294,7 +357,7 @@ mod tests {
                 <th>app</th>
                 <th>dev</th>
                 <th>prod</th>
-                <th>in-sync</th>
+                <th>status</th>
               </tr>
             </thead>
             <tbody>
@@ -302,13 +365,13 @@ mod tests {
                 <td>app-one</td>
                 <td>1.1.0</td>
                 <td>1.0.0</td>
-                <td>✗</td>
+                <td>prod 1 minor behind dev</td>
               </tr>
               <tr>
                 <td>app-two</td>
                 <td>2.1.0</td>
                 <td>2.0.0</td>
-                <td>✗</td>
+                <td>prod 1 minor behind dev</td>
               </tr>
               <tr>
                 <td>app-three</td>
@@ -322,4 +385,43 @@ mod tests {
         </html>
         ");
     }
+
+    #[test]
+    fn getting_stdout_changes_output_with_commit_logs_works() {
+        // GIVEN
+        let (changes, logs) = get_changes_and_commit_logs();
+        let commit_log_results = Some(CommitLogResults {
+            logs,
+            errors: CommitLogFetchErrors::new(),
+        });
+        let config = Config {
+            output_type: OutputType::Stdout(StdoutConfig {
+                table_style: TableStyle::Ascii,
+                plain_output: true,
+            }),
+        };
+        let now = Utc.with_ymd_and_hms(2025, 1, 16, 12, 0, 0).unwrap();
+
+        // WHEN
+        let result = render_changes_output(&changes, commit_log_results.as_ref(), &config, now)
+            .expect("result should've been Ok");
+
+        // THEN
+        insta::assert_snapshot!(result, @r"
+        +----------+------+------------+-------+-------+
+        |app       | env  | change     | old   | new   |
+        +==============================================+
+        |app-one   | prod | upgraded   | 1.0.0 | 1.1.0 |
+        |App Three | dev  | downgraded | 1.5.1 | 1.5.0 |
+        |app-two   | dev  | added      |       | 2.1.0 |
+        |app-two   | prod | removed    | 2.0.0 |       |
+        +----------+------+------------+-------+-------+
+
+        app-one
+
+          prod (1.0.0..1.1.0)
+
+           ae7de14  First commit  User A  1d ago
+        ");
+    }
 }
@@ -0,0 +1,40 @@
+use crate::domain::{AppResult, SyncStatus};
+
+pub(super) const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+pub(crate) fn get_status_text(app_result: &AppResult) -> String {
+    match app_result.sync_status {
+        SyncStatus::InSync => "✓".to_string(),
+        SyncStatus::NotApplicable => "-".to_string(),
+        SyncStatus::Behind | SyncStatus::Ahead | SyncStatus::OutOfSync => app_result
+            .drifts
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+pub(super) fn get_policies_text(app_result: &AppResult) -> String {
+    if app_result.policy_violations.is_empty() {
+        return "✓".to_string();
+    }
+
+    // violation messages can contain commas themselves
+    app_result
+        .policy_violations
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// apps that aren't out of sync, or haven't been seen to be, are left blank
+pub(super) fn get_out_of_sync_since_text(app_result: &AppResult) -> String {
+    match app_result.out_of_sync_since {
+        Some(since) if app_result.sync_status.is_out_of_sync() => {
+            since.format(DATE_TIME_FORMAT).to_string()
+        }
+        _ => String::new(),
+    }
+}
@@ -1,50 +1,66 @@
+use super::status::{
+    DATE_TIME_FORMAT, get_out_of_sync_since_text, get_policies_text, get_status_text,
+};
 use crate::config::{StdoutConfig, TableStyle};
-use crate::domain::{DiffResult, SyncStatus};
+use crate::domain::{ChangeKind, DiffResult, HistoryReport, SyncStatus, VersionChanges};
 use comfy_table::{Cell, Color, Table, presets};
 
-pub fn render_results_table(result: &DiffResult, config: &StdoutConfig) -> String {
-    let mut table = Table::new();
+const NO_CHANGES: &str = "no changes";
+const NO_RECORDED_CHANGES: &str = "no changes recorded";
 
-    match config.table_style {
-        TableStyle::Ascii => table.load_preset(presets::ASCII_FULL_CONDENSED),
-        TableStyle::Markdown => table.load_preset(presets::ASCII_MARKDOWN),
-        TableStyle::None => table.load_preset(presets::NOTHING),
-        TableStyle::Utf8 => table.load_preset(presets::UTF8_FULL_CONDENSED),
-    };
+pub fn render_results_table(result: &DiffResult, config: &StdoutConfig) -> String {
+    let mut table = new_table(config.table_style);
 
     let mut header = vec!["app".to_string()];
     header.extend(result.envs.iter().map(|e| e.to_string()));
-    header.push("in-sync".to_string());
+    header.push("status".to_string());
+    if result.policies_checked {
+        header.push("policies".to_string());
+    }
+    if result.history_used {
+        header.push("out of sync since".to_string());
+    }
     table.set_header(header);
 
     for row in &result.app_results {
-        let (maybe_color, sync_text) = match row.sync_status {
-            SyncStatus::InSync => (None, "✓"),
-            SyncStatus::OutOfSync => (Some(Color::Red), "✗"),
-            SyncStatus::NotApplicable => (Some(Color::Grey), "-"),
-        };
+        let maybe_color = get_status_color(&row.sync_status);
+        let sync_text = get_status_text(row);
+        let policies_text = result.policies_checked.then(|| get_policies_text(row));
+        let since_text = result.history_used.then(|| get_out_of_sync_since_text(row));
 
         match (config.plain_output, maybe_color) {
             (false, Some(color)) => {
-                let mut cells = vec![Cell::new(&row.app).fg(color)];
+                let mut cells = vec![Cell::new(row.name()).fg(color)];
 
                 for env in &result.envs {
-                    let version = row.values.get(env).map(|v| v.as_ref()).unwrap_or("");
+                    let version = row.version_text(env);
                     cells.push(Cell::new(version).fg(color));
                 }
 
-                cells.push(Cell::new(sync_text).fg(color));
+                cells.push(Cell::new(&sync_text).fg(color));
+                if let Some(policies_text) = &policies_text {
+                    cells.push(Cell::new(policies_text).fg(color));
+                }
+                if let Some(since_text) = &since_text {
+                    cells.push(Cell::new(since_text).fg(color));
+                }
 
                 table.add_row(cells);
             }
             (true, _) | (false, None) => {
-                let mut cells = vec![row.app.to_string()];
+                let mut cells = vec![row.name().to_string()];
                 for env in &result.envs {
-                    let version = row.values.get(env).map(|v| v.as_ref()).unwrap_or("");
+                    let version = row.version_text(env);
                     cells.push(version.to_string());
                 }
 
-                cells.push(sync_text.to_string());
+                cells.push(sync_text);
+                if let Some(policies_text) = policies_text {
+                    cells.push(policies_text);
+                }
+                if let Some(since_text) = since_text {
+                    cells.push(since_text);
+                }
 
                 table.add_row(cells);
             }
@@ -58,11 +74,168 @@ pub fn render_results_table(result: &DiffResult, config: &StdoutConfig) -> Strin
     table.to_string()
 }
 
+pub fn render_changes_table(changes: &VersionChanges, config: &StdoutConfig) -> String {
+    if changes.changes.is_empty() {
+        return NO_CHANGES.to_string();
+    }
+
+    let mut table = new_table(config.table_style);
+    table.set_header(vec!["app", "env", "change", "old", "new"]);
+
+    for change in &changes.changes {
+        let kind = change.kind.to_string();
+        let cells: [&str; 5] = [
+            change.name(),
+            &change.env,
+            &kind,
+            change.old_version.as_ref().map_or("", |v| v),
+            change.new_version.as_ref().map_or("", |v| v),
+        ];
+
+        if config.plain_output {
+            table.add_row(cells);
+        } else {
+            let color = match change.kind {
+                ChangeKind::Added => Color::Green,
+                ChangeKind::Removed => Color::Red,
+                ChangeKind::Upgraded => Color::Cyan,
+                ChangeKind::Downgraded => Color::Yellow,
+                ChangeKind::Changed => Color::Magenta,
+            };
+            table.add_row(cells.map(|cell| Cell::new(cell).fg(color)));
+        }
+    }
+
+    if let Some(column) = table.column_mut(0) {
+        column.set_padding((0, 1));
+    }
+
+    table.to_string()
+}
+
+pub fn render_history_table(report: &HistoryReport, config: &StdoutConfig) -> String {
+    let (Some(first), Some(last)) = (report.first_change_at, report.last_change_at) else {
+        return NO_RECORDED_CHANGES.to_string();
+    };
+
+    let mut apps_table = new_table(config.table_style);
+    apps_table.set_header(vec!["app", "status", "out of sync since", "deployments"]);
+    let mut timeline_table = new_table(config.table_style);
+    timeline_table.set_header(vec!["app", "env", "version", "seen at"]);
+
+    for app in &report.apps {
+        let deployments = app
+            .timelines
+            .iter()
+            .flat_map(|t| &t.deployments)
+            .filter(|d| !d.first_seen)
+            .count()
+            .to_string();
+        let cells = [
+            app.app.clone(),
+            get_sync_status_text(&app.sync_status).to_string(),
+            app.out_of_sync_since
+                .map(|since| since.format(DATE_TIME_FORMAT).to_string())
+                .unwrap_or_default(),
+            deployments,
+        ];
+
+        match (config.plain_output, get_status_color(&app.sync_status)) {
+            (false, Some(color)) => {
+                apps_table.add_row(cells.map(|cell| Cell::new(cell).fg(color)));
+            }
+            (true, _) | (false, None) => {
+                apps_table.add_row(cells);
+            }
+        }
+
+        for timeline in &app.timelines {
+            for deployment in &timeline.deployments {
+                let mut seen_at = deployment.seen_at.format(DATE_TIME_FORMAT).to_string();
+                // the version was deployed at some point before the first run that saw it
+                if deployment.first_seen {
+                    seen_at.push_str(" (first seen)");
+                }
+
+                timeline_table.add_row(vec![
+                    app.app.clone(),
+                    timeline.env.clone(),
+                    deployment.version.clone(),
+                    seen_at,
+                ]);
+            }
+        }
+    }
+
+    let mut envs_table = new_table(config.table_style);
+    envs_table.set_header(vec!["env", "deployments", "per week"]);
+    for env in &report.env_deployments {
+        envs_table.add_row(vec![
+            env.env.clone(),
+            env.deployments.to_string(),
+            env.per_week
+                .map(|per_week| format!("{per_week:.1}"))
+                .unwrap_or("-".to_string()),
+        ]);
+    }
+
+    for table in [&mut apps_table, &mut timeline_table, &mut envs_table] {
+        if let Some(column) = table.column_mut(0) {
+            column.set_padding((0, 1));
+        }
+    }
+
+    format!(
+        "changes: {} ({} to {})\n\n{}\n\n{}\n\n{}",
+        report.changes,
+        first.format(DATE_TIME_FORMAT),
+        last.format(DATE_TIME_FORMAT),
+        apps_table,
+        timeline_table,
+        envs_table
+    )
+}
+
+fn get_status_color(sync_status: &SyncStatus) -> Option<Color> {
+    match sync_status {
+        SyncStatus::InSync => None,
+        SyncStatus::Behind => Some(Color::Yellow),
+        SyncStatus::Ahead => Some(Color::Magenta),
+        SyncStatus::OutOfSync => Some(Color::Red),
+        SyncStatus::NotApplicable => Some(Color::Grey),
+    }
+}
+
+fn get_sync_status_text(sync_status: &SyncStatus) -> &'static str {
+    match sync_status {
+        SyncStatus::InSync => "in sync",
+        SyncStatus::Behind => "behind",
+        SyncStatus::Ahead => "ahead",
+        SyncStatus::OutOfSync => "out of sync",
+        SyncStatus::NotApplicable => "-",
+    }
+}
+
+fn new_table(style: TableStyle) -> Table {
+    let mut table = Table::new();
+
+    match style {
+        TableStyle::Ascii => table.load_preset(presets::ASCII_FULL_CONDENSED),
+        TableStyle::Markdown => table.load_preset(presets::ASCII_MARKDOWN),
+        TableStyle::None => table.load_preset(presets::NOTHING),
+        TableStyle::Utf8 => table.load_preset(presets::UTF8_FULL_CONDENSED),
+    };
+
+    table
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::testdata::{get_changes_and_commit_logs, get_history_report};
     use super::*;
     use crate::config::StdoutConfig;
-    use crate::domain::{AppResult, DiffResult};
+    use crate::domain::{AppResult, DiffResult, DriftAmount, EnvDrift, VersionDrift};
+    use chrono::{TimeZone, Utc};
     use std::collections::HashMap;
 
     #[test]
@@ -79,14 +252,16 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(output, @r"
-        +-----+-------+---------+-------+---------+
-        |app  | qa    | staging | prod  | in-sync |
-        +=========================================+
-        |app1 | 1.0.0 | 1.0.0   | 1.0.0 | ✓       |
-        |app2 | 2.0.0 | 2.0.0   | 1.9.0 | ✗       |
-        |app3 | 0.1.0 | 0.1.0   |       | ✓       |
-        |app4 | 0.1.0 |         |       | -       |
-        +-----+-------+---------+-------+---------+
+        +-----+---------+---------+---------+-----------------------------+
+        |app  | qa      | staging | prod    | status                      |
+        +=================================================================+
+        |app1 | 1.0.0   | 1.0.0   | 1.0.0   | ✓                           |
+        |app2 | 2.0.0   | 2.0.0   | 1.9.0   | prod 1 major behind qa      |
+        |app3 | 0.1.0   | 0.1.0   |         | ✓                           |
+        |app4 | 0.1.0   |         |         | -                           |
+        |app5 | 1.0.0   | 1.0.1   | 1.0.0   | staging 1 patch ahead of qa |
+        |app6 | abc1234 | abc1234 | def5678 | prod differs from qa        |
+        +-----+---------+---------+---------+-----------------------------+
         ");
     }
 
@@ -104,12 +279,14 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(output, @r"
-        |app  | qa    | staging | prod  | in-sync |
-        |-----|-------|---------|-------|---------|
-        |app1 | 1.0.0 | 1.0.0   | 1.0.0 | ✓       |
-        |app2 | 2.0.0 | 2.0.0   | 1.9.0 | ✗       |
-        |app3 | 0.1.0 | 0.1.0   |       | ✓       |
-        |app4 | 0.1.0 |         |       | -       |
+        |app  | qa      | staging | prod    | status                      |
+        |-----|---------|---------|---------|-----------------------------|
+        |app1 | 1.0.0   | 1.0.0   | 1.0.0   | ✓                           |
+        |app2 | 2.0.0   | 2.0.0   | 1.9.0   | prod 1 major behind qa      |
+        |app3 | 0.1.0   | 0.1.0   |         | ✓                           |
+        |app4 | 0.1.0   |         |         | -                           |
+        |app5 | 1.0.0   | 1.0.1   | 1.0.0   | staging 1 patch ahead of qa |
+        |app6 | abc1234 | abc1234 | def5678 | prod differs from qa        |
         ");
     }
 
@@ -127,11 +304,13 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(output, @r"
-        app   qa     staging  prod   in-sync 
-        app1  1.0.0  1.0.0    1.0.0  ✓       
-        app2  2.0.0  2.0.0    1.9.0  ✗       
-        app3  0.1.0  0.1.0           ✓       
-        app4  0.1.0                  -
+        app   qa       staging  prod     status                      
+        app1  1.0.0    1.0.0    1.0.0    ✓                           
+        app2  2.0.0    2.0.0    1.9.0    prod 1 major behind qa      
+        app3  0.1.0    0.1.0             ✓                           
+        app4  0.1.0                      -                           
+        app5  1.0.0    1.0.1    1.0.0    staging 1 patch ahead of qa 
+        app6  abc1234  abc1234  def5678  prod differs from qa
         ");
     }
 
@@ -149,17 +328,160 @@ mod tests {
 
         // THEN
         insta::assert_snapshot!(output, @r"
-        ┌─────┬───────┬─────────┬───────┬─────────┐
-        │app  ┆ qa    ┆ staging ┆ prod  ┆ in-sync │
-        ╞═════╪═══════╪═════════╪═══════╪═════════╡
-        │app1 ┆ 1.0.0 ┆ 1.0.0   ┆ 1.0.0 ┆ ✓       │
-        │app2 ┆ 2.0.0 ┆ 2.0.0   ┆ 1.9.0 ┆ ✗       │
-        │app3 ┆ 0.1.0 ┆ 0.1.0   ┆       ┆ ✓       │
-        │app4 ┆ 0.1.0 ┆         ┆       ┆ -       │
-        └─────┴───────┴─────────┴───────┴─────────┘
+        ┌─────┬─────────┬─────────┬─────────┬─────────────────────────────┐
+        │app  ┆ qa      ┆ staging ┆ prod    ┆ status                      │
+        ╞═════╪═════════╪═════════╪═════════╪═════════════════════════════╡
+        │app1 ┆ 1.0.0   ┆ 1.0.0   ┆ 1.0.0   ┆ ✓                           │
+        │app2 ┆ 2.0.0   ┆ 2.0.0   ┆ 1.9.0   ┆ prod 1 major behind qa      │
+        │app3 ┆ 0.1.0   ┆ 0.1.0   ┆         ┆ ✓                           │
+        │app4 ┆ 0.1.0   ┆         ┆         ┆ -                           │
+        │app5 ┆ 1.0.0   ┆ 1.0.1   ┆ 1.0.0   ┆ staging 1 patch ahead of qa │
+        │app6 ┆ abc1234 ┆ abc1234 ┆ def5678 ┆ prod differs from qa        │
+        └─────┴─────────┴─────────┴─────────┴─────────────────────────────┘
+        ");
+    }
+
+    #[test]
+    fn changes_table_is_rendered_correctly() {
+        // GIVEN
+        let (changes, _) = get_changes_and_commit_logs();
+        let config = StdoutConfig {
+            table_style: TableStyle::Ascii,
+            plain_output: true,
+        };
+
+        // WHEN
+        let output = render_changes_table(&changes, &config);
+
+        // THEN
+        insta::assert_snapshot!(output, @r"
+        +----------+------+------------+-------+-------+
+        |app       | env  | change     | old   | new   |
+        +==============================================+
+        |app-one   | prod | upgraded   | 1.0.0 | 1.1.0 |
+        |App Three | dev  | downgraded | 1.5.1 | 1.5.0 |
+        |app-two   | dev  | added      |       | 2.1.0 |
+        |app-two   | prod | removed    | 2.0.0 |       |
+        +----------+------+------------+-------+-------+
+        ");
+    }
+
+    #[test]
+    fn changes_table_says_so_when_nothing_changed() {
+        // GIVEN
+        let changes = VersionChanges {
+            envs: vec!["dev".into(), "prod".into()],
+            changes: vec![],
+            unchanged: 4,
+        };
+        let config = StdoutConfig {
+            table_style: TableStyle::Utf8,
+            plain_output: true,
+        };
+
+        // WHEN
+        let output = render_changes_table(&changes, &config);
+
+        // THEN
+        assert_eq!(output, "no changes");
+    }
+
+    #[test]
+    fn table_shows_when_apps_got_out_of_sync_if_history_is_used() {
+        // GIVEN
+        let mut result = create_test_diff_result();
+        result.history_used = true;
+        result.app_results[1].out_of_sync_since =
+            Some(Utc.with_ymd_and_hms(2025, 1, 10, 9, 30, 0).unwrap());
+        let config = StdoutConfig {
+            table_style: TableStyle::Ascii,
+            plain_output: true,
+        };
+
+        // WHEN
+        let output = render_results_table(&result, &config);
+
+        // THEN
+        insta::assert_snapshot!(output, @r"
+        +-----+---------+---------+---------+-----------------------------+-------------------+
+        |app  | qa      | staging | prod    | status                      | out of sync since |
+        +=====================================================================================+
+        |app1 | 1.0.0   | 1.0.0   | 1.0.0   | ✓                           |                   |
+        |app2 | 2.0.0   | 2.0.0   | 1.9.0   | prod 1 major behind qa      | 2025-01-10 09:30  |
+        |app3 | 0.1.0   | 0.1.0   |         | ✓                           |                   |
+        |app4 | 0.1.0   |         |         | -                           |                   |
+        |app5 | 1.0.0   | 1.0.1   | 1.0.0   | staging 1 patch ahead of qa |                   |
+        |app6 | abc1234 | abc1234 | def5678 | prod differs from qa        |                   |
+        +-----+---------+---------+---------+-----------------------------+-------------------+
+        ");
+    }
+
+    #[test]
+    fn history_table_is_rendered_correctly() {
+        // GIVEN
+        let report = get_history_report();
+        let config = StdoutConfig {
+            table_style: TableStyle::Ascii,
+            plain_output: true,
+        };
+
+        // WHEN
+        let output = render_history_table(&report, &config);
+
+        // THEN
+        insta::assert_snapshot!(output, @r"
+        changes: 4 (2025-01-01 10:00 to 2025-01-15 10:00)
+
+        +--------+---------+-------------------+-------------+
+        |app     | status  | out of sync since | deployments |
+        +====================================================+
+        |app-one | behind  | 2025-01-08 10:00  | 1           |
+        |app-two | in sync |                   | 2           |
+        +--------+---------+-------------------+-------------+
+
+        +--------+------+---------+-------------------------------+
+        |app     | env  | version | seen at                       |
+        +=========================================================+
+        |app-one | dev  | 1.0.0   | 2025-01-01 10:00 (first seen) |
+        |app-one | dev  | 1.1.0   | 2025-01-08 10:00              |
+        |app-one | prod | 1.0.0   | 2025-01-01 10:00 (first seen) |
+        |app-two | dev  | 2.0.0   | 2025-01-01 10:00 (first seen) |
+        |app-two | dev  | 2.1.0   | 2025-01-02 10:00              |
+        |app-two | prod | 2.0.0   | 2025-01-01 10:00 (first seen) |
+        |app-two | prod | 2.1.0   | 2025-01-15 10:00              |
+        +--------+------+---------+-------------------------------+
+
+        +-----+-------------+----------+
+        |env  | deployments | per week |
+        +==============================+
+        |dev  | 2           | 1.0      |
+        |prod | 1           | 0.5      |
+        +-----+-------------+----------+
         ");
     }
 
+    #[test]
+    fn history_table_says_so_when_no_changes_were_recorded() {
+        // GIVEN
+        let report = HistoryReport {
+            changes: 0,
+            first_change_at: None,
+            last_change_at: None,
+            apps: vec![],
+            env_deployments: vec![],
+        };
+        let config = StdoutConfig {
+            table_style: TableStyle::Ascii,
+            plain_output: true,
+        };
+
+        // WHEN
+        let output = render_history_table(&report, &config);
+
+        // THEN
+        assert_eq!(output, NO_RECORDED_CHANGES);
+    }
+
     fn create_test_diff_result() -> DiffResult {
         let mut app1_values = HashMap::new();
         app1_values.insert("qa".into(), "1.0.0".into());
@@ -178,6 +500,16 @@ mod tests {
         let mut app4_values = HashMap::new();
         app4_values.insert("qa".into(), "0.1.0".into());
 
+        let mut app5_values = HashMap::new();
+        app5_values.insert("qa".into(), "1.0.0".into());
+        app5_values.insert("staging".into(), "1.0.1".into());
+        app5_values.insert("prod".into(), "1.0.0".into());
+
+        let mut app6_values = HashMap::new();
+        app6_values.insert("qa".into(), "abc1234".into());
+        app6_values.insert("staging".into(), "abc1234".into());
+        app6_values.insert("prod".into(), "def5678".into());
+
         DiffResult {
             envs: vec!["qa", "staging", "prod"]
                 .into_iter()
@@ -186,25 +518,79 @@ mod tests {
             app_results: vec![
                 AppResult {
                     app: "app1".into(),
+                    display_name: None,
                     values: app1_values,
                     sync_status: SyncStatus::InSync,
+                    drifts: vec![],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
                 },
                 AppResult {
                     app: "app2".into(),
+                    display_name: None,
                     values: app2_values,
-                    sync_status: SyncStatus::OutOfSync,
+                    sync_status: SyncStatus::Behind,
+                    drifts: vec![EnvDrift {
+                        env: "prod".into(),
+                        reference_env: "qa".into(),
+                        drift: VersionDrift::Behind(DriftAmount::Major(1)),
+                    }],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
                 },
                 AppResult {
                     app: "app3".into(),
+                    display_name: None,
                     values: app3_values,
                     sync_status: SyncStatus::InSync,
+                    drifts: vec![],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
                 },
                 AppResult {
                     app: "app4".into(),
+                    display_name: None,
                     values: app4_values,
                     sync_status: SyncStatus::NotApplicable,
+                    drifts: vec![],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
+                },
+                AppResult {
+                    app: "app5".into(),
+                    display_name: None,
+                    values: app5_values,
+                    sync_status: SyncStatus::Ahead,
+                    drifts: vec![EnvDrift {
+                        env: "staging".into(),
+                        reference_env: "qa".into(),
+                        drift: VersionDrift::Ahead(DriftAmount::Patch(1)),
+                    }],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
+                },
+                AppResult {
+                    app: "app6".into(),
+                    display_name: None,
+                    values: app6_values,
+                    sync_status: SyncStatus::OutOfSync,
+                    drifts: vec![EnvDrift {
+                        env: "prod".into(),
+                        reference_env: "qa".into(),
+                        drift: VersionDrift::Unordered,
+                    }],
+                    policy_violations: vec![],
+                    unknown_envs: vec![],
+                    out_of_sync_since: None,
                 },
             ],
+            policies_checked: false,
+            history_used: false,
         }
     }
 }
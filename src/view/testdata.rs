@@ -1,4 +1,8 @@
-use crate::domain::{AppResult, Author, Commit, CommitDetail, CommitLog, DiffResult, SyncStatus};
+use crate::domain::{
+    AppHistory, AppResult, Author, ChangeKind, Commit, CommitDetail, CommitLog, Deployment,
+    DiffResult, DriftAmount, EnvDeployments, EnvDrift, EnvTimeline, HistoryReport, SyncStatus,
+    VersionChange, VersionChanges, VersionDrift,
+};
 use chrono::{TimeZone, Utc};
 use std::collections::HashMap;
 
@@ -20,24 +24,50 @@ pub(super) fn get_result_and_commit_logs() -> (DiffResult, Vec<CommitLog>) {
         app_results: vec![
             AppResult {
                 app: "app-one".into(),
+                display_name: None,
                 values: app1_values,
-                sync_status: SyncStatus::OutOfSync,
+                sync_status: SyncStatus::Behind,
+                drifts: vec![EnvDrift {
+                    env: "prod".into(),
+                    reference_env: "dev".into(),
+                    drift: VersionDrift::Behind(DriftAmount::Minor(1)),
+                }],
+                policy_violations: vec![],
+                unknown_envs: vec![],
+                out_of_sync_since: None,
             },
             AppResult {
                 app: "app-two".into(),
+                display_name: None,
                 values: app2_values,
-                sync_status: SyncStatus::OutOfSync,
+                sync_status: SyncStatus::Behind,
+                drifts: vec![EnvDrift {
+                    env: "prod".into(),
+                    reference_env: "dev".into(),
+                    drift: VersionDrift::Behind(DriftAmount::Minor(1)),
+                }],
+                policy_violations: vec![],
+                unknown_envs: vec![],
+                out_of_sync_since: None,
             },
             AppResult {
                 app: "app-three".into(),
+                display_name: None,
                 values: app3_values,
                 sync_status: SyncStatus::InSync,
+                drifts: vec![],
+                policy_violations: vec![],
+                unknown_envs: vec![],
+                out_of_sync_since: None,
             },
         ],
+        policies_checked: false,
+        history_used: false,
     };
 
     let log1 = CommitLog {
         app: "app-one".into(),
+        display_name: None,
         from_env: "prod".into(),
         to_env: "dev".into(),
         from_version: "1.0.0".into(),
@@ -54,10 +84,13 @@ pub(super) fn get_result_and_commit_logs() -> (DiffResult, Vec<CommitLog>) {
             html_url: "https://github.com/org/app-one/commit/abc1234567890".to_string(),
         }],
         html_url: "https://github.com/org/app-one/compare/1.0.0...1.1.0".to_string(),
+        total_commits: 1,
+        from_cache: false,
     };
 
     let log2 = CommitLog {
         app: "app-two".into(),
+        display_name: None,
         from_env: "prod".into(),
         to_env: "dev".into(),
         from_version: "2.0.0".into(),
@@ -98,11 +131,79 @@ pub(super) fn get_result_and_commit_logs() -> (DiffResult, Vec<CommitLog>) {
             },
         ],
         html_url: "https://github.com/org/app-two/compare/2.0.0...2.1.0".to_string(),
+        total_commits: 3,
+        from_cache: true,
     };
 
     (diff_result, vec![log1, log2])
 }
 
+pub(super) fn get_changes_and_commit_logs() -> (VersionChanges, Vec<CommitLog>) {
+    let changes = VersionChanges {
+        envs: vec!["dev", "prod"].into_iter().map(Into::into).collect(),
+        changes: vec![
+            VersionChange {
+                app: "app-one".into(),
+                display_name: None,
+                env: "prod".into(),
+                kind: ChangeKind::Upgraded,
+                old_version: Some("1.0.0".into()),
+                new_version: Some("1.1.0".into()),
+            },
+            VersionChange {
+                app: "app-three".into(),
+                display_name: Some("App Three".into()),
+                env: "dev".into(),
+                kind: ChangeKind::Downgraded,
+                old_version: Some("1.5.1".into()),
+                new_version: Some("1.5.0".into()),
+            },
+            VersionChange {
+                app: "app-two".into(),
+                display_name: None,
+                env: "dev".into(),
+                kind: ChangeKind::Added,
+                old_version: None,
+                new_version: Some("2.1.0".into()),
+            },
+            VersionChange {
+                app: "app-two".into(),
+                display_name: None,
+                env: "prod".into(),
+                kind: ChangeKind::Removed,
+                old_version: Some("2.0.0".into()),
+                new_version: None,
+            },
+        ],
+        unchanged: 2,
+    };
+
+    let log = CommitLog {
+        app: "app-one".into(),
+        display_name: None,
+        from_env: "prod".into(),
+        to_env: "prod".into(),
+        from_version: "1.0.0".into(),
+        to_version: "1.1.0".into(),
+        commits: vec![Commit {
+            sha: "ae7de14".to_string(),
+            commit: CommitDetail {
+                message: "First commit".to_string(),
+                author: Author {
+                    name: "User A".to_string(),
+                    date: Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap(),
+                },
+            },
+            html_url: "https://github.com/org/app-one/commit/abc1234567890".to_string(),
+        }],
+        html_url: "https://github.com/org/app-one/compare/1.0.0...1.1.0".to_string(),
+        total_commits: 1,
+        from_cache: false,
+    };
+
+    (changes, vec![log])
+}
+
 pub(super) const TEST_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
 <html>
 <head>
@@ -154,3 +255,71 @@ pub(super) const TEST_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
 </body>
 </html>
 "#;
+
+pub(super) fn get_history_report() -> HistoryReport {
+    let at = |day| Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap();
+    let deployment = |version: &str, day, first_seen| Deployment {
+        version: version.to_string(),
+        seen_at: at(day),
+        first_seen,
+    };
+
+    HistoryReport {
+        changes: 4,
+        first_change_at: Some(at(1)),
+        last_change_at: Some(at(15)),
+        apps: vec![
+            AppHistory {
+                app: "app-one".to_string(),
+                timelines: vec![
+                    EnvTimeline {
+                        env: "dev".to_string(),
+                        deployments: vec![
+                            deployment("1.0.0", 1, true),
+                            deployment("1.1.0", 8, false),
+                        ],
+                    },
+                    EnvTimeline {
+                        env: "prod".to_string(),
+                        deployments: vec![deployment("1.0.0", 1, true)],
+                    },
+                ],
+                sync_status: SyncStatus::Behind,
+                out_of_sync_since: Some(at(8)),
+            },
+            AppHistory {
+                app: "app-two".to_string(),
+                timelines: vec![
+                    EnvTimeline {
+                        env: "dev".to_string(),
+                        deployments: vec![
+                            deployment("2.0.0", 1, true),
+                            deployment("2.1.0", 2, false),
+                        ],
+                    },
+                    EnvTimeline {
+                        env: "prod".to_string(),
+                        deployments: vec![
+                            deployment("2.0.0", 1, true),
+                            deployment("2.1.0", 15, false),
+                        ],
+                    },
+                ],
+                sync_status: SyncStatus::InSync,
+                out_of_sync_since: None,
+            },
+        ],
+        env_deployments: vec![
+            EnvDeployments {
+                env: "dev".to_string(),
+                deployments: 2,
+                per_week: Some(1.0),
+            },
+            EnvDeployments {
+                env: "prod".to_string(),
+                deployments: 1,
+                per_week: Some(0.5),
+            },
+        ],
+    }
+}
@@ -1,17 +1,30 @@
 use insta_cmd::get_cargo_bin;
-use std::{ffi::OsStr, path::PathBuf, process::Command};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use tempfile::TempDir;
 
 pub struct Fixture {
     _bin_path: PathBuf,
+    // keeps tests from reading or polluting the user's commit log cache
+    _cache_dir: TempDir,
+    // keeps tests from picking up the user's envee.toml
+    _config_dir: TempDir,
 }
 
 #[cfg(test)]
 impl Fixture {
     pub fn new() -> Self {
         let bin_path = get_cargo_bin("envee");
+        let cache_dir = TempDir::new().expect("temporary directory should've been created");
+        let config_dir = TempDir::new().expect("temporary directory should've been created");
 
         Self {
             _bin_path: bin_path,
+            _cache_dir: cache_dir,
+            _config_dir: config_dir,
         }
     }
 
@@ -22,6 +35,41 @@ impl Fixture {
     {
         let mut command = Command::new(&self._bin_path);
         command.args(args);
+        command.env("XDG_CACHE_HOME", self._cache_dir.path());
+        command.env("XDG_CONFIG_HOME", self._config_dir.path());
+        // keeps tests run in GitHub Actions from reporting to it
+        command.env_remove("GITHUB_ACTIONS");
+        command.env_remove("GITHUB_STEP_SUMMARY");
+        command.env_remove("GITHUB_OUTPUT");
         command
     }
 }
+
+// not every test binary sets up git repositories
+#[cfg(test)]
+#[allow(dead_code)]
+pub fn init_repo(repo: &Path) {
+    std::fs::create_dir_all(repo).expect("repository directory should've been created");
+    git(repo, &["init", "--quiet"]);
+}
+
+// commits and tags are made by the same author at the same time, so that their details don't
+// change between runs
+#[cfg(test)]
+#[allow(dead_code)]
+pub fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+        .args(args)
+        .env("GIT_AUTHOR_NAME", "User A")
+        .env("GIT_AUTHOR_EMAIL", "user-a@example.com")
+        .env("GIT_AUTHOR_DATE", "2025-01-15T10:00:00+01:00")
+        .env("GIT_COMMITTER_NAME", "User A")
+        .env("GIT_COMMITTER_EMAIL", "user-a@example.com")
+        .env("GIT_COMMITTER_DATE", "2025-01-15T10:00:00+01:00")
+        .status()
+        .expect("git should've run");
+    assert!(status.success(), "git {args:?} should've succeeded");
+}
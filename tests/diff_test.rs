@@ -0,0 +1,456 @@
+mod common;
+
+use common::{Fixture, git, init_repo};
+use insta_cmd::assert_cmd_snapshot;
+use std::path::Path;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn shows_help() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["diff", "--help"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Show what changed between two versions files, eg. yesterday's and today's
+
+    Usage: envee diff [OPTIONS] <OLD> <NEW>
+
+    Arguments:
+      <OLD>  Versions file to compare against, or - for stdin
+      <NEW>  Versions file with the changes, or - for stdin
+
+    Options:
+          --versions-format <FORMAT>     Format of the versions files (detected from the file extension by default) [possible values: toml, yaml, json]
+          --old-git-ref <REV>            Read OLD (and the files it points to) as it was at this git revision
+          --new-git-ref <REV>            Read NEW (and the files it points to) as it was at this git revision
+          --versions-repo <PATH>         Git repository to read versions files from when a git revision is used [default: .]
+      -C, --no-commit-logs               Skip fetching and showing commit logs
+          --debug                        Output debug information without doing anything
+          --max-commits <NUMBER>         Maximum number of commits to fetch per commit log [default: 1000]
+          --no-cache                     Don't read commit logs from, or write them to, the on-disk cache
+          --refresh                      Ignore cached commit logs and fetch them again (the cache is still updated)
+          --cache-ttl <DURATION>         How long cached commit logs stay valid for (eg. "12h", "7d") [default: 7d]
+          --max-attempts <NUMBER>        Maximum number of attempts for each API request (1 turns off retries) [default: 4]
+          --max-retry-wait <DURATION>    Longest time to wait before retrying an API request; requests fail if the API asks to wait longer [default: 60s]
+      -o, --output-format <FORMAT>       Output format [default: stdout] [possible values: stdout, html, json, markdown]
+      -f, --filter <REGEX>               Regex to use for filtering apps
+          --stdout-table-style <STRING>  Table style for stdout output [default: utf8] [possible values: ascii, markdown, none, utf8]
+          --stdout-plain                 Whether to use output text to stdout without color
+          --html-output <PATH>           Path for the HTML output file [default: envee-diff.html]
+          --html-title <STRING>          Title for HTML report [default: "envee diff"]
+          --markdown-title <STRING>      Title for Markdown report [default: "envee diff"]
+      -h, --help                         Print help
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn debug_flag_works_for_defaults() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["diff", "old.toml", "new.toml", "--debug"]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Diff
+    old versions file:                    old.toml
+    new versions file:                    new.toml
+    versions format:                      <NOT PROVIDED>
+    old git ref:                          <NOT PROVIDED>
+    new git ref:                          <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    don't show commit logs:               false
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    output format:                        stdout
+    app filter:                           <NOT PROVIDED>
+    table style:                          utf8
+    plain output:                         false
+
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_stdout_output() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "diff",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "tests/assets/diff/old-versions.toml",
+        "tests/assets/diff/new-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬──────┬────────────┬───────┬───────┐
+    │app    ┆ env  ┆ change     ┆ old   ┆ new   │
+    ╞═══════╪══════╪════════════╪═══════╪═══════╡
+    │repo-b ┆ dev  ┆ upgraded   ┆ 1.2.0 ┆ 1.3.0 │
+    │repo-b ┆ prod ┆ upgraded   ┆ 1.0.0 ┆ 1.2.0 │
+    │repo-c ┆ dev  ┆ downgraded ┆ 2.0.0 ┆ 1.9.1 │
+    │repo-c ┆ prod ┆ removed    ┆ 1.9.0 ┆       │
+    │repo-d ┆ dev  ┆ added      ┆       ┆ 0.1.0 │
+    └───────┴──────┴────────────┴───────┴───────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_markdown_output() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "diff",
+        "--no-commit-logs",
+        "--output-format",
+        "markdown",
+        "tests/assets/diff/old-versions.toml",
+        "tests/assets/diff/new-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"_Generated at .*_", "_Generated at [TIMESTAMP]_");
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        # envee diff
+
+        _Generated at [TIMESTAMP]_
+
+        | app | env | change | old | new |
+        | --- | --- | --- | --- | --- |
+        | repo-b | dev | upgraded | 1.2.0 | 1.3.0 |
+        | repo-b | prod | upgraded | 1.0.0 | 1.2.0 |
+        | repo-c | dev | downgraded | 2.0.0 | 1.9.1 |
+        | repo-c | prod | removed | 1.9.0 |  |
+        | repo-d | dev | added |  | 0.1.0 |
+
+        Unchanged: 2
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn works_when_nothing_changed() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "diff",
+        "--no-commit-logs",
+        "tests/assets/diff/old-versions.toml",
+        "-",
+    ]);
+    let versions = std::fs::read_to_string("tests/assets/diff/old-versions.toml")
+        .expect("versions file should've been read");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd.pass_stdin(versions), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    no changes
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn app_filter_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "diff",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--filter",
+        "repo-(b|d)",
+        "tests/assets/diff/old-versions.toml",
+        "tests/assets/diff/new-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬──────┬──────────┬───────┬───────┐
+    │app    ┆ env  ┆ change   ┆ old   ┆ new   │
+    ╞═══════╪══════╪══════════╪═══════╪═══════╡
+    │repo-b ┆ dev  ┆ upgraded ┆ 1.2.0 ┆ 1.3.0 │
+    │repo-b ┆ prod ┆ upgraded ┆ 1.0.0 ┆ 1.2.0 │
+    │repo-d ┆ dev  ┆ added    ┆       ┆ 0.1.0 │
+    └───────┴──────┴──────────┴───────┴───────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn fetching_commit_logs_for_upgrades_works() {
+    // GIVEN
+    let repos_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    let repo_path = repos_dir.path().join("service-z");
+    init_repo(&repo_path);
+    git(
+        &repo_path,
+        &["commit", "--quiet", "--allow-empty", "-m", "initial commit"],
+    );
+    git(&repo_path, &["tag", "v3.0.0"]);
+    git(
+        &repo_path,
+        &["commit", "--quiet", "--allow-empty", "-m", "add caching"],
+    );
+    git(&repo_path, &["tag", "v3.1.0"]);
+
+    let write_versions = |name: &str, prod_version: &str| {
+        let path = repos_dir.path().join(name);
+        std::fs::write(
+            &path,
+            format!(
+                r#"envs = ["dev", "prod"]
+github_org = "org"
+git_tag_transform = "v{{{{version}}}}"
+provider = "local"
+local_repos_dir = "{}"
+
+[[versions]]
+app = "service-z"
+env = "dev"
+version = "3.1.0"
+
+[[versions]]
+app = "service-z"
+env = "prod"
+version = "{prod_version}"
+"#,
+                repos_dir.path().display()
+            ),
+        )
+        .expect("versions file should've been written");
+        path
+    };
+    let old_path = write_versions("old.toml", "3.0.0");
+    let new_path = write_versions("new.toml", "3.1.0");
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["diff", "--output-format", "json"]);
+    cmd.args([&old_path, &new_path]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+    cmd.env_remove("ENVEE_GITLAB_TOKEN");
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "changes": [
+            {
+              "app": "service-z",
+              "display_name": null,
+              "env": "prod",
+              "kind": "upgraded",
+              "old_version": "3.0.0",
+              "new_version": "3.1.0"
+            }
+          ],
+          "unchanged": 1,
+          "commit_logs": [
+            {
+              "app": "service-z",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "prod",
+              "from_version": "3.0.0",
+              "to_version": "3.1.0",
+              "compare_url": "",
+              "commits": [
+                {
+                  "sha": "44c2bf425246309c4eafea1dc658fc4321f9ee3d",
+                  "message": "add caching",
+                  "author": "User A",
+                  "date": "2025-01-15T09:00:00Z",
+                  "html_url": ""
+                }
+              ],
+              "total_commits": 1,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": []
+        }
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+#[test]
+fn works_for_versions_files_at_git_revisions() {
+    // GIVEN
+    let repo = tempfile::tempdir().expect("temporary directory should've been created");
+    let copy_versions = |name: &str| {
+        std::fs::copy(
+            Path::new("tests/assets/diff").join(name),
+            repo.path().join("versions.toml"),
+        )
+        .expect("versions file should've been copied");
+    };
+    init_repo(repo.path());
+    copy_versions("old-versions.toml");
+    git(repo.path(), &["add", "--all"]);
+    git(
+        repo.path(),
+        &["commit", "--quiet", "-m", "yesterday's versions"],
+    );
+    copy_versions("new-versions.toml");
+    git(
+        repo.path(),
+        &["commit", "--quiet", "--all", "-m", "today's versions"],
+    );
+
+    let fx = Fixture::new();
+    let repo_path = repo.path().to_string_lossy().to_string();
+    let mut cmd = fx.cmd([
+        "diff",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions-repo",
+        &repo_path,
+        "--old-git-ref",
+        "HEAD~1",
+        "--new-git-ref",
+        "HEAD",
+        "versions.toml",
+        "versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬──────┬────────────┬───────┬───────┐
+    │app    ┆ env  ┆ change     ┆ old   ┆ new   │
+    ╞═══════╪══════╪════════════╪═══════╪═══════╡
+    │repo-b ┆ dev  ┆ upgraded   ┆ 1.2.0 ┆ 1.3.0 │
+    │repo-b ┆ prod ┆ upgraded   ┆ 1.0.0 ┆ 1.2.0 │
+    │repo-c ┆ dev  ┆ downgraded ┆ 2.0.0 ┆ 1.9.1 │
+    │repo-c ┆ prod ┆ removed    ┆ 1.9.0 ┆       │
+    │repo-d ┆ dev  ┆ added      ┆       ┆ 0.1.0 │
+    └───────┴──────┴────────────┴───────┴───────┘
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn fails_if_both_versions_files_are_read_from_stdin() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["diff", "-", "-"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: only one of OLD and NEW can be read from stdin
+    ");
+}
+
+#[test]
+fn fails_if_a_versions_file_is_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "diff",
+        "--no-commit-logs",
+        "tests/assets/diff/old-versions.toml",
+        "tests/assets/invalid-data.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't get versions from file "tests/assets/invalid-data.toml"
+
+    Caused by:
+        versions config has errors:
+         - envs array has only 1 element, need at least 2
+         - env "unknown" is not present in any of the versions configured
+         - github_org is empty
+         - git_tag_transform doesn't include the placeholder "{{version}}"
+         - version #0 has errors:
+           - app is empty
+           - env is empty
+           - version is empty
+         - version #2 has errors:
+           - app is empty
+           - version is empty
+    "#);
+}
@@ -0,0 +1,411 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+use tempfile::TempDir;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn shows_help() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["history", "--help"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Show version timelines, drift durations, and deployment frequencies from a history file
+
+    Usage: envee history [OPTIONS] --history <PATH>
+
+    Options:
+          --history <PATH>               History file written to by `run --history` or `serve --history`
+          --since <DURATION>             Only consider changes recorded within this long (eg. "7d", "4weeks")
+      -o, --output-format <FORMAT>       Output format [default: stdout] [possible values: stdout, json]
+      -f, --filter <REGEX>               Regex to use for filtering apps
+          --debug                        Output debug information without doing anything
+          --stdout-table-style <STRING>  Table style for stdout output [default: utf8] [possible values: ascii, markdown, none, utf8]
+          --stdout-plain                 Whether to use output text to stdout without color
+      -h, --help                         Print help
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn debug_flag_works_for_defaults() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["history", "--history", "history.jsonl", "--debug"]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              History
+    history file:                         history.jsonl
+    since:                                <NOT PROVIDED>
+    output format:                        stdout
+    app filter:                           <NOT PROVIDED>
+    table style:                          utf8
+    plain output:                         false
+
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_stdout_output() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "history",
+        "--stdout-plain",
+        "--history",
+        "tests/assets/history/history.jsonl",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    changes: 4 (2025-01-01 10:00 to 2025-01-15 10:00)
+
+    ┌───────┬─────────┬───────────────────┬─────────────┐
+    │app    ┆ status  ┆ out of sync since ┆ deployments │
+    ╞═══════╪═════════╪═══════════════════╪═════════════╡
+    │repo-a ┆ behind  ┆ 2025-01-04 10:00  ┆ 2           │
+    │repo-b ┆ in sync ┆                   ┆ 3           │
+    └───────┴─────────┴───────────────────┴─────────────┘
+
+    ┌───────┬──────┬─────────┬───────────────────────────────┐
+    │app    ┆ env  ┆ version ┆ seen at                       │
+    ╞═══════╪══════╪═════════╪═══════════════════════════════╡
+    │repo-a ┆ dev  ┆ 0.1.0   ┆ 2025-01-01 10:00 (first seen) │
+    │repo-a ┆ dev  ┆ 0.2.0   ┆ 2025-01-04 10:00              │
+    │repo-a ┆ dev  ┆ 0.3.0   ┆ 2025-01-15 10:00              │
+    │repo-a ┆ prod ┆ 0.1.0   ┆ 2025-01-01 10:00 (first seen) │
+    │repo-b ┆ dev  ┆ 1.1.0   ┆ 2025-01-01 10:00 (first seen) │
+    │repo-b ┆ dev  ┆ 1.2.0   ┆ 2025-01-08 10:00              │
+    │repo-b ┆ prod ┆ 1.0.0   ┆ 2025-01-01 10:00 (first seen) │
+    │repo-b ┆ prod ┆ 1.1.0   ┆ 2025-01-04 10:00              │
+    │repo-b ┆ prod ┆ 1.2.0   ┆ 2025-01-15 10:00              │
+    └───────┴──────┴─────────┴───────────────────────────────┘
+
+    ┌─────┬─────────────┬──────────┐
+    │env  ┆ deployments ┆ per week │
+    ╞═════╪═════════════╪══════════╡
+    │dev  ┆ 3           ┆ 1.5      │
+    │prod ┆ 2           ┆ 1.0      │
+    └─────┴─────────────┴──────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn filtering_apps_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "history",
+        "--stdout-plain",
+        "--stdout-table-style",
+        "ascii",
+        "--history",
+        "tests/assets/history/history.jsonl",
+        "--filter",
+        "repo-b",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    changes: 4 (2025-01-01 10:00 to 2025-01-15 10:00)
+
+    +-------+---------+-------------------+-------------+
+    |app    | status  | out of sync since | deployments |
+    +===================================================+
+    |repo-b | in sync |                   | 3           |
+    +-------+---------+-------------------+-------------+
+
+    +-------+------+---------+-------------------------------+
+    |app    | env  | version | seen at                       |
+    +========================================================+
+    |repo-b | dev  | 1.1.0   | 2025-01-01 10:00 (first seen) |
+    |repo-b | dev  | 1.2.0   | 2025-01-08 10:00              |
+    |repo-b | prod | 1.0.0   | 2025-01-01 10:00 (first seen) |
+    |repo-b | prod | 1.1.0   | 2025-01-04 10:00              |
+    |repo-b | prod | 1.2.0   | 2025-01-15 10:00              |
+    +-------+------+---------+-------------------------------+
+
+    +-----+-------------+----------+
+    |env  | deployments | per week |
+    +==============================+
+    |dev  | 1           | 0.5      |
+    |prod | 2           | 1.0      |
+    +-----+-------------+----------+
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn json_output_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "history",
+        "--output-format",
+        "json",
+        "--history",
+        "tests/assets/history/history.jsonl",
+        "--filter",
+        "repo-a",
+    ]);
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "changes": 4,
+          "first_change_at": "2025-01-01T10:00:00Z",
+          "last_change_at": "2025-01-15T10:00:00Z",
+          "apps": [
+            {
+              "app": "repo-a",
+              "sync_status": "behind",
+              "out_of_sync_since": "2025-01-04T10:00:00Z",
+              "timelines": {
+                "dev": [
+                  {
+                    "version": "0.1.0",
+                    "seen_at": "2025-01-01T10:00:00Z",
+                    "first_seen": true
+                  },
+                  {
+                    "version": "0.2.0",
+                    "seen_at": "2025-01-04T10:00:00Z",
+                    "first_seen": false
+                  },
+                  {
+                    "version": "0.3.0",
+                    "seen_at": "2025-01-15T10:00:00Z",
+                    "first_seen": false
+                  }
+                ],
+                "prod": [
+                  {
+                    "version": "0.1.0",
+                    "seen_at": "2025-01-01T10:00:00Z",
+                    "first_seen": true
+                  }
+                ]
+              }
+            }
+          ],
+          "envs": [
+            {
+              "env": "dev",
+              "deployments": 2,
+              "per_week": 1.0
+            },
+            {
+              "env": "prod",
+              "deployments": 0,
+              "per_week": 0.0
+            }
+          ]
+        }
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+#[test]
+fn runs_are_recorded_and_show_how_long_apps_have_been_out_of_sync() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    let history_path = temp_dir.path().join("history.jsonl");
+    let history_path = history_path.to_string_lossy();
+    let run_args = [
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+        "--history",
+        &history_path,
+    ];
+    let mut first_run = fx.cmd(run_args);
+    let mut second_run = fx.cmd(run_args);
+    let mut history_cmd = fx.cmd([
+        "history",
+        "--stdout-plain",
+        "--stdout-table-style",
+        "ascii",
+        "--history",
+        &history_path,
+    ]);
+
+    // WHEN
+    // THEN
+    let first_output = first_run.output().expect("command should've run");
+    assert!(first_output.status.success());
+
+    let mut settings = insta::Settings::clone_current();
+    // keeps tables aligned
+    settings.add_filter(r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}", "YYYY-MM-DD HH:MM");
+    settings.bind(|| {
+        assert_cmd_snapshot!(second_run, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        ┌───────┬───────┬───────┬──────────────────────────┬───────────────────┐
+        │app    ┆ dev   ┆ prod  ┆ status                   ┆ out of sync since │
+        ╞═══════╪═══════╪═══════╪══════════════════════════╪═══════════════════╡
+        │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        ┆                   │
+        │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev ┆ YYYY-MM-DD HH:MM  │
+        │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  ┆ YYYY-MM-DD HH:MM  │
+        └───────┴───────┴───────┴──────────────────────────┴───────────────────┘
+
+        ----- stderr -----
+        ");
+        assert_cmd_snapshot!(history_cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        changes: 1 (YYYY-MM-DD HH:MM to YYYY-MM-DD HH:MM)
+
+        +-------+---------+-------------------+-------------+
+        |app    | status  | out of sync since | deployments |
+        +===================================================+
+        |repo-a | in sync |                   | 0           |
+        |repo-b | behind  | YYYY-MM-DD HH:MM  | 0           |
+        |repo-c | behind  | YYYY-MM-DD HH:MM  | 0           |
+        +-------+---------+-------------------+-------------+
+
+        +-------+------+---------+-------------------------------+
+        |app    | env  | version | seen at                       |
+        +========================================================+
+        |repo-a | dev  | 0.1.0   | YYYY-MM-DD HH:MM (first seen) |
+        |repo-a | prod | 0.1.0   | YYYY-MM-DD HH:MM (first seen) |
+        |repo-b | dev  | 1.2.0   | YYYY-MM-DD HH:MM (first seen) |
+        |repo-b | prod | 1.0.0   | YYYY-MM-DD HH:MM (first seen) |
+        |repo-c | dev  | 2.0.0   | YYYY-MM-DD HH:MM (first seen) |
+        |repo-c | prod | 1.9.0   | YYYY-MM-DD HH:MM (first seen) |
+        +-------+------+---------+-------------------------------+
+
+        +-----+-------------+----------+
+        |env  | deployments | per week |
+        +==============================+
+        |dev  | 0           | -        |
+        |prod | 0           | -        |
+        +-----+-------------+----------+
+
+        ----- stderr -----
+        ");
+    });
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn fails_if_history_file_is_absent() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["history", "--history", "tests/assets/history/absent.jsonl"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: history file "tests/assets/history/absent.jsonl" doesn't exist
+    "#);
+}
+
+#[test]
+fn fails_if_history_file_is_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["history", "--history", "tests/assets/invalid-data.toml"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: line 1 of history file "tests/assets/invalid-data.toml" is invalid
+
+    Caused by:
+        expected value at line 1 column 1
+    "#);
+}
+
+#[test]
+fn fails_if_history_is_used_with_validate_only() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--validate-only",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+        "--history",
+        "history.jsonl",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--validate-only' cannot be used with '--history <PATH>'
+
+    Usage: envee run --validate-only --versions <PATH>
+
+    For more information, try '--help'.
+    ");
+}
@@ -0,0 +1,349 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[cfg(test)]
+fn write_config(dir: &Path, contents: &str) {
+    std::fs::write(dir.join("envee.toml"), contents).expect("config file should've been written");
+}
+
+#[cfg(test)]
+fn get_versions_path() -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/assets/valid-versions.toml")
+        .to_string_lossy()
+        .to_string()
+}
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn config_file_in_the_current_directory_is_used() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(
+        temp_dir.path(),
+        &format!(
+            r#"
+[run]
+versions = ["{}"]
+no_commit_logs = true
+stdout_plain = true
+stdout_table_style = "ascii"
+"#,
+            get_versions_path()
+        ),
+    );
+    let mut cmd = fx.cmd(["run"]);
+    cmd.current_dir(temp_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    +-------+-------+-------+--------------------------+
+    |app    | dev   | prod  | status                   |
+    +==================================================+
+    |repo-a | 0.1.0 | 0.1.0 | ✓                        |
+    |repo-b | 1.2.0 | 1.0.0 | prod 2 minors behind dev |
+    |repo-c | 2.0.0 | 1.9.0 | prod 1 major behind dev  |
+    +-------+-------+-------+--------------------------+
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn flags_take_precedence_over_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(
+        temp_dir.path(),
+        &format!(
+            r#"
+[run]
+versions = ["{}"]
+no_commit_logs = true
+stdout_plain = true
+stdout_table_style = "ascii"
+"#,
+            get_versions_path()
+        ),
+    );
+    let mut cmd = fx.cmd(["run", "--stdout-table-style", "markdown"]);
+    cmd.current_dir(temp_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    |app    | dev   | prod  | status                   |
+    |-------|-------|-------|--------------------------|
+    |repo-a | 0.1.0 | 0.1.0 | ✓                        |
+    |repo-b | 1.2.0 | 1.0.0 | prod 2 minors behind dev |
+    |repo-c | 2.0.0 | 1.9.0 | prod 1 major behind dev  |
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn config_file_in_the_config_directory_is_used() {
+    // GIVEN
+    let fx = Fixture::new();
+    let config_dir = TempDir::new().expect("temporary directory should've been created");
+    let envee_config_dir = config_dir.path().join("envee");
+    std::fs::create_dir(&envee_config_dir).expect("directory should've been created");
+    write_config(
+        &envee_config_dir,
+        r#"
+[run]
+versions = ["tests/assets/valid-versions.toml"]
+no_commit_logs = true
+stdout_plain = true
+"#,
+    );
+    let mut cmd = fx.cmd(["run"]);
+    cmd.env("XDG_CONFIG_HOME", config_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn config_file_in_the_current_directory_takes_precedence_over_the_one_in_the_config_directory() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(
+        temp_dir.path(),
+        &format!(
+            r#"
+[run]
+versions = ["{}"]
+no_commit_logs = true
+stdout_plain = true
+"#,
+            get_versions_path()
+        ),
+    );
+    let config_dir = TempDir::new().expect("temporary directory should've been created");
+    let envee_config_dir = config_dir.path().join("envee");
+    std::fs::create_dir(&envee_config_dir).expect("directory should've been created");
+    write_config(
+        &envee_config_dir,
+        r#"
+[run]
+stdout_table_style = "ascii"
+"#,
+    );
+    let mut cmd = fx.cmd(["run"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.env("XDG_CONFIG_HOME", config_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_shows_options_from_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(
+        temp_dir.path(),
+        r#"
+[run]
+versions = ["versions/", "prod-hotfix.toml"]
+max_commits = 20
+stdout_table_style = "ascii"
+"#,
+    );
+    let mut cmd = fx.cmd(["run", "--debug", "--stdout-table-style", "markdown"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Run
+    config file:                          envee.toml
+    options from config file:             max_commits,versions
+    versions files:                       versions/,prod-hotfix.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    only validate versions file:          false
+    interactive:                          false
+    don't show commit logs:               false
+    max commits per log:                  20
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    output format:                        stdout
+    app filter:                           <NOT PROVIDED>
+    status filter:                        <NOT PROVIDED>
+    fail on:                              <NOT PROVIDED>
+    sort by:                              app
+    history file:                         <NOT PROVIDED>
+    github actions:                       false
+    table style:                          markdown
+    plain output:                         false
+
+    GitHub API quota:                     unknown (ENVEE_GH_TOKEN is not set)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn config_file_is_only_read_for_run() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(temp_dir.path(), "[run]\nunknown = true\n");
+    let mut cmd = fx.cmd(["history", "--history", "history.jsonl", "--debug"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              History
+    history file:                         history.jsonl
+    since:                                <NOT PROVIDED>
+    output format:                        stdout
+    app filter:                           <NOT PROVIDED>
+    table style:                          utf8
+    plain output:                         false
+
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn fails_for_unknown_options_in_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(temp_dir.path(), "[run]\nstdout_table_styles = \"ascii\"\n");
+    let mut cmd = fx.cmd(["run"]);
+    cmd.current_dir(temp_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: config file "envee.toml" has an unknown option "stdout_table_styles" in [run]
+    "#);
+}
+
+#[test]
+fn fails_for_invalid_values_in_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(temp_dir.path(), "[run]\nmax_commits = -1\n");
+    let mut cmd = fx.cmd(["run"]);
+    cmd.current_dir(temp_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: config file "envee.toml" has an invalid value for "max_commits" in [run]: invalid value '-1' for '--max-commits <NUMBER>': -1 is not in 1..=4294967295
+    "#);
+}
+
+#[test]
+fn fails_for_config_files_that_are_not_valid_toml() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = TempDir::new().expect("temporary directory should've been created");
+    write_config(temp_dir.path(), "[run\n");
+    let mut cmd = fx.cmd(["run"]);
+    cmd.current_dir(temp_dir.path());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: config file "envee.toml" is invalid
+
+    Caused by:
+        TOML parse error at line 1, column 5
+          |
+        1 | [run
+          |     ^
+        unclosed table, expected `]`
+    "#);
+}
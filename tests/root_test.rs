@@ -24,8 +24,11 @@ fn shows_help() {
     Usage: envee [OPTIONS] <COMMAND>
 
     Commands:
-      run   Show results based on a versions file
-      help  Print this message or the help of the given subcommand(s)
+      run      Show results based on a versions file
+      serve    Serve an HTML report based on a versions file, refreshing it periodically
+      diff     Show what changed between two versions files, eg. yesterday's and today's
+      history  Show version timelines, drift durations, and deployment frequencies from a history file
+      help     Print this message or the help of the given subcommand(s)
 
     Options:
           --debug  Output debug information without doing anything
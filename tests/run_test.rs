@@ -1,6 +1,6 @@
 mod common;
 
-use common::Fixture;
+use common::{Fixture, git, init_repo};
 use insta_cmd::assert_cmd_snapshot;
 
 //-------------//
@@ -13,39 +13,2117 @@ fn shows_help() {
     let fx = Fixture::new();
     let mut cmd = fx.cmd(["run", "--help"]);
 
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Show results based on a versions file
+
+    Usage: envee run [OPTIONS]
+
+    Options:
+      -V, --versions <PATH>              Path to a versions file, a directory of them, or - for stdin (repeatable; files are merged) [default: versions.toml]
+          --envs <ENV>                   Order of environments (comma separated), overriding envs in the versions files
+          --versions-format <FORMAT>     Format of the versions files (detected from the file extension by default) [possible values: toml, yaml, json]
+          --versions-git-ref <REV>       Read versions files (and the files they point to) as they were at this git revision
+          --debug                        Output debug information without doing anything
+          --versions-repo <PATH>         Git repository to read versions files from when --versions-git-ref is used [default: .]
+          --validate-only                Only validate versions file
+      -i, --interactive                  Browse results and commit logs in an interactive terminal UI
+      -C, --no-commit-logs               Skip fetching and showing commit logs
+          --max-commits <NUMBER>         Maximum number of commits to fetch per commit log [default: 1000]
+          --no-cache                     Don't read commit logs from, or write them to, the on-disk cache
+          --refresh                      Ignore cached commit logs and fetch them again (the cache is still updated)
+          --cache-ttl <DURATION>         How long cached commit logs stay valid for (eg. "12h", "7d") [default: 7d]
+          --max-attempts <NUMBER>        Maximum number of attempts for each API request (1 turns off retries) [default: 4]
+          --max-retry-wait <DURATION>    Longest time to wait before retrying an API request; requests fail if the API asks to wait longer [default: 60s]
+      -o, --output-format <FORMAT>       Output format [default: stdout] [possible values: stdout, html, json, markdown]
+      -f, --filter <REGEX>               Regex to use for filtering apps
+          --status <STATUS>              Only show apps with these sync statuses (comma separated) [possible values: in-sync, behind, ahead, out-of-sync, not-applicable]
+          --fail-on <CONDITION>          Exit with a non-zero code if any of these conditions are met (comma separated) [possible values: behind, ahead, out-of-sync, missing, fetch-error, policy]
+          --sort-by <ATTRIBUTE>          Attribute to sort apps by [default: app] [possible values: app, status]
+          --history <PATH>               Record the results in this history file, and show how long apps have been out of sync
+          --github-actions               Add a job summary, annotations, and step outputs when running in GitHub Actions (turned on automatically if GITHUB_ACTIONS is "true")
+          --stdout-table-style <STRING>  Table style for stdout output [default: utf8] [possible values: ascii, markdown, none, utf8]
+          --stdout-plain                 Whether to use output text to stdout without color
+          --html-output <PATH>           Path for the HTML output file [default: envee-report.html]
+          --html-title <STRING>          Title for HTML report [default: envee]
+          --html-template <PATH>         Path to custom HTML template file
+          --markdown-title <STRING>      Title for Markdown report [default: envee]
+      -h, --help                         Print help
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn debug_flag_works_for_defaults() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--debug"]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Run
+    config file:                          <NOT PROVIDED>
+    options from config file:             <NOT PROVIDED>
+    versions files:                       versions.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    only validate versions file:          false
+    interactive:                          false
+    don't show commit logs:               false
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    output format:                        stdout
+    app filter:                           <NOT PROVIDED>
+    status filter:                        <NOT PROVIDED>
+    fail on:                              <NOT PROVIDED>
+    sort by:                              app
+    history file:                         <NOT PROVIDED>
+    github actions:                       false
+    table style:                          utf8
+    plain output:                         false
+
+    GitHub API quota:                     unknown (ENVEE_GH_TOKEN is not set)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_works_with_overridden_flags_for_stdout_output() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--debug",
+        "--filter",
+        "repo",
+        "--status",
+        "behind,ahead",
+        "--sort-by",
+        "status",
+        "--output-format",
+        "stdout",
+        "--stdout-plain",
+        "--stdout-table-style",
+        "ascii",
+        "--validate-only",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Run
+    config file:                          <NOT PROVIDED>
+    options from config file:             <NOT PROVIDED>
+    versions files:                       tests/assets/valid-versions.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    only validate versions file:          true
+    interactive:                          false
+    don't show commit logs:               false
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    output format:                        stdout
+    app filter:                           repo
+    status filter:                        behind,ahead
+    fail on:                              <NOT PROVIDED>
+    sort by:                              status
+    history file:                         <NOT PROVIDED>
+    github actions:                       false
+    table style:                          ascii
+    plain output:                         true
+
+    GitHub API quota:                     unknown (ENVEE_GH_TOKEN is not set)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_works_with_overridden_flags_for_html_output() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--debug",
+        "--filter",
+        "repo",
+        "--html-output",
+        "output.html",
+        "--html-template",
+        "tests/assets/absent.html",
+        "--html-title",
+        "versions",
+        "--output-format",
+        "html",
+        "--validate-only",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Run
+    config file:                          <NOT PROVIDED>
+    options from config file:             <NOT PROVIDED>
+    versions files:                       tests/assets/valid-versions.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    only validate versions file:          true
+    interactive:                          false
+    don't show commit logs:               false
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    output format:                        html
+    app filter:                           repo
+    status filter:                        <NOT PROVIDED>
+    fail on:                              <NOT PROVIDED>
+    sort by:                              app
+    history file:                         <NOT PROVIDED>
+    github actions:                       false
+    output path:                          output.html
+    title:                                versions
+    template path:                        tests/assets/absent.html
+
+    GitHub API quota:                     unknown (ENVEE_GH_TOKEN is not set)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_valid_versions_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_a_directory_of_versions_files() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/split",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_multiple_versions_files_with_envs_flag() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/split/dev.toml",
+        "--versions",
+        "tests/assets/split/prod.toml",
+        "--versions",
+        "tests/assets/split/envee.toml",
+        "--envs",
+        "prod,dev",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬────────────────────────────┐
+    │app    ┆ prod  ┆ dev   ┆ status                     │
+    ╞═══════╪═══════╪═══════╪════════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                          │
+    │repo-b ┆ 1.0.0 ┆ 1.2.0 ┆ dev 2 minors ahead of prod │
+    │repo-c ┆ 1.9.0 ┆ 2.0.0 ┆ dev 1 major ahead of prod  │
+    └───────┴───────┴───────┴────────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_versions_read_from_stdin() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "-",
+    ]);
+    let versions = r#"{
+  "envs": ["dev", "prod"],
+  "github_org": "dhth",
+  "versions": [
+    { "app": "repo-a", "env": "dev", "version": "0.2.0" },
+    { "app": "repo-a", "env": "prod", "version": "0.1.0" }
+  ]
+}"#;
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd.pass_stdin(versions), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬─────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                  │
+    ╞═══════╪═══════╪═══════╪═════════════════════════╡
+    │repo-a ┆ 0.2.0 ┆ 0.1.0 ┆ prod 1 minor behind dev │
+    └───────┴───────┴───────┴─────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_versions_from_kubernetes_manifests() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/kubernetes/versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬─────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                  │
+    ╞═══════╪═══════╪═══════╪═════════════════════════╡
+    │repo-a ┆ 0.2.0 ┆ 0.1.0 ┆ prod 1 minor behind dev │
+    │repo-b ┆ 1.2.0 ┆ 1.2.0 ┆ ✓                       │
+    └───────┴───────┴───────┴─────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_versions_from_argocd_applications_and_helm_values() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/gitops/versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬─────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                  │
+    ╞═══════╪═══════╪═══════╪═════════════════════════╡
+    │repo-a ┆ 0.2.0 ┆ 0.1.0 ┆ prod 1 minor behind dev │
+    │repo-b ┆ 1.2.0 ┆ 1.2.0 ┆ ✓                       │
+    └───────┴───────┴───────┴─────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn works_for_versions_from_endpoints() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    server
+        .mock("GET", "/repo-a/dev/actuator/info")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"build": {"version": "0.2.0"}}"#)
+        .create();
+    server
+        .mock("GET", "/repo-a/prod/version")
+        .with_status(200)
+        .with_body("repo-a 0.1.0")
+        .create();
+    server
+        .mock("GET", "/repo-b/prod/version")
+        .with_status(502)
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "-",
+    ]);
+    let url = server.url();
+    let versions = format!(
+        r#"
+envs = ["dev", "prod"]
+github_org = "dhth"
+
+[[versions]]
+app = "repo-b"
+env = "dev"
+version = "1.2.0"
+
+[endpoints.repo-a.dev]
+url = "{url}/repo-a/dev/actuator/info"
+json_pointer = "/build/version"
+
+[endpoints.repo-a.prod]
+url = "{url}/repo-a/prod/version"
+pattern = '(?P<version>\d+\.\d+\.\d+)'
+
+[endpoints.repo-b.prod]
+url = "{url}/repo-b/prod/version"
+json_pointer = "/version"
+"#
+    );
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"http://127\.0\.0\.1:\d+", "[SERVER]");
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd.pass_stdin(versions), @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        ┌───────┬───────┬─────────┬─────────────────────────┐
+        │app    ┆ dev   ┆ prod    ┆ status                  │
+        ╞═══════╪═══════╪═════════╪═════════════════════════╡
+        │repo-a ┆ 0.2.0 ┆ 0.1.0   ┆ prod 1 minor behind dev │
+        │repo-b ┆ 1.2.0 ┆ unknown ┆ -                       │
+        └───────┴───────┴─────────┴─────────────────────────┘
+
+        ----- stderr -----
+        Warning: couldn't fetch versions from endpoints:
+         - repo-b (prod): [SERVER]/repo-b/prod/version responded with status 502 Bad Gateway
+        ");
+    });
+}
+
+#[test]
+fn works_for_versions_at_a_git_revision() {
+    // GIVEN
+    let repo = tempfile::tempdir().expect("temporary directory should've been created");
+    let write_manifest = |env: &str, version: &str| {
+        let dir = repo.path().join("manifests").join(env);
+        std::fs::create_dir_all(&dir).expect("directory should've been created");
+        std::fs::write(
+            dir.join("repo-a.yaml"),
+            format!(
+                "kind: Deployment\nspec:\n  template:\n    spec:\n      containers:\n        - image: ghcr.io/dhth/repo-a:{version}\n"
+            ),
+        )
+        .expect("manifest should've been written");
+    };
+    std::fs::write(
+        repo.path().join("versions.toml"),
+        r#"envs = ["dev", "prod"]
+github_org = "dhth"
+
+[sources.kubernetes.manifests]
+dev = "manifests/dev"
+prod = "manifests/prod"
+"#,
+    )
+    .expect("versions file should've been written");
+    init_repo(repo.path());
+    write_manifest("dev", "0.2.0");
+    write_manifest("prod", "0.1.0");
+    git(repo.path(), &["add", "--all"]);
+    git(
+        repo.path(),
+        &["commit", "--quiet", "-m", "deploy 0.2.0 to dev"],
+    );
+    write_manifest("prod", "0.2.0");
+    git(
+        repo.path(),
+        &["commit", "--quiet", "--all", "-m", "deploy 0.2.0 to prod"],
+    );
+
+    let fx = Fixture::new();
+    let repo_path = repo.path().to_string_lossy().to_string();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions-repo",
+        &repo_path,
+        "--versions-git-ref",
+        "HEAD~1",
+        "--versions",
+        "versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬─────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                  │
+    ╞═══════╪═══════╪═══════╪═════════════════════════╡
+    │repo-a ┆ 0.2.0 ┆ 0.1.0 ┆ prod 1 minor behind dev │
+    └───────┴───────┴───────┴─────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn fails_if_versions_at_a_git_revision_are_read_from_stdin() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--versions-git-ref",
+        "HEAD",
+        "--versions",
+        "-",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: --versions needs to be a path relative to the repository when --versions-git-ref is used
+    ");
+}
+
+#[test]
+fn filtering_by_sync_status_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--status",
+        "behind",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn json_output_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--output-format",
+        "json",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "repo-a",
+              "display_name": null,
+              "versions": {
+                "dev": "0.1.0",
+                "prod": "0.1.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "in_sync",
+              "drifts": [],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            },
+            {
+              "app": "repo-b",
+              "display_name": null,
+              "versions": {
+                "dev": "1.2.0",
+                "prod": "1.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 2,
+                  "description": "prod 2 minors behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            },
+            {
+              "app": "repo-c",
+              "display_name": null,
+              "versions": {
+                "dev": "2.0.0",
+                "prod": "1.9.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "major",
+                  "count": 1,
+                  "description": "prod 1 major behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": null,
+          "commit_log_errors": null
+        }
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+#[test]
+fn markdown_output_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--output-format",
+        "markdown",
+        "--markdown-title",
+        "versions",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"_Generated at .*_", "_Generated at [TIMESTAMP]_");
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        # versions
+
+        _Generated at [TIMESTAMP]_
+
+        | app | dev | prod | status |
+        | --- | --- | --- | --- |
+        | repo-a | 0.1.0 | 0.1.0 | ✓ |
+        | repo-b | 1.2.0 | 1.0.0 | prod 2 minors behind dev |
+        | repo-c | 2.0.0 | 1.9.0 | prod 1 major behind dev |
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn fetching_commit_logs_from_custom_github_api_url_works() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "per_page".into(),
+            "100".into(),
+        ))
+        .match_header("authorization", "Bearer test-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "html_url": "https://github.example.com/dhth/repo-b/compare/v1.0.0...v1.2.0",
+  "total_commits": 2,
+  "commits": [
+    {
+      "sha": "c536d77",
+      "html_url": "https://github.example.com/dhth/repo-b/commit/c536d77",
+      "commit": {
+        "message": "allow filtering apps",
+        "author": { "name": "User A", "date": "2025-01-15T10:00:00Z" }
+      }
+    },
+    {
+      "sha": "1443d43",
+      "html_url": "https://github.example.com/dhth/repo-b/commit/1443d43",
+      "commit": {
+        "message": "add cli test",
+        "author": { "name": "User B", "date": "2025-01-16T11:30:00Z" }
+      }
+    }
+  ]
+}"#,
+        )
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--output-format",
+        "json",
+        "--filter",
+        "repo-b",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "repo-b",
+              "display_name": null,
+              "versions": {
+                "dev": "1.2.0",
+                "prod": "1.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 2,
+                  "description": "prod 2 minors behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": [
+            {
+              "app": "repo-b",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "dev",
+              "from_version": "1.0.0",
+              "to_version": "1.2.0",
+              "compare_url": "https://github.example.com/dhth/repo-b/compare/v1.0.0...v1.2.0",
+              "commits": [
+                {
+                  "sha": "1443d43",
+                  "message": "add cli test",
+                  "author": "User B",
+                  "date": "2025-01-16T11:30:00Z",
+                  "html_url": "https://github.example.com/dhth/repo-b/commit/1443d43"
+                },
+                {
+                  "sha": "c536d77",
+                  "message": "allow filtering apps",
+                  "author": "User A",
+                  "date": "2025-01-15T10:00:00Z",
+                  "html_url": "https://github.example.com/dhth/repo-b/commit/c536d77"
+                }
+              ],
+              "total_commits": 2,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": []
+        }
+
+        ----- stderr -----
+        "#);
+    });
+    mock.assert();
+}
+
+#[test]
+fn fetching_commit_logs_uses_app_level_settings() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock(
+            "GET",
+            "/repos/other-org/monorepo/compare/service-x/v1.0.0...service-x/v1.1.0",
+        )
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "html_url": "https://github.com/other-org/monorepo/compare/service-x/v1.0.0...service-x/v1.1.0",
+  "total_commits": 1,
+  "commits": [
+    {
+      "sha": "c536d77",
+      "html_url": "https://github.com/other-org/monorepo/commit/c536d77",
+      "commit": {
+        "message": "service-x: add endpoint",
+        "author": { "name": "User A", "date": "2025-01-15T10:00:00Z" }
+      }
+    }
+  ]
+}"#,
+        )
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--output-format",
+        "json",
+        "--versions",
+        "tests/assets/app-overrides.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "service-x",
+              "display_name": "Service X",
+              "versions": {
+                "dev": "1.1.0",
+                "prod": "1.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": [
+            {
+              "app": "service-x",
+              "display_name": "Service X",
+              "from_env": "prod",
+              "to_env": "dev",
+              "from_version": "1.0.0",
+              "to_version": "1.1.0",
+              "compare_url": "https://github.com/other-org/monorepo/compare/service-x/v1.0.0...service-x/v1.1.0",
+              "commits": [
+                {
+                  "sha": "c536d77",
+                  "message": "service-x: add endpoint",
+                  "author": "User A",
+                  "date": "2025-01-15T10:00:00Z",
+                  "html_url": "https://github.com/other-org/monorepo/commit/c536d77"
+                }
+              ],
+              "total_commits": 1,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": []
+        }
+
+        ----- stderr -----
+        "#);
+    });
+    mock.assert();
+}
+
+#[test]
+fn fetching_commit_logs_from_gitlab_works() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/projects/group%2Fservice-y/repository/compare")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("from".into(), "v2.0.0".into()),
+            mockito::Matcher::UrlEncoded("to".into(), "v2.0.1".into()),
+        ]))
+        .match_header("private-token", "test-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "web_url": "https://gitlab.example.com/group/service-y/-/compare/v2.0.0...v2.0.1",
+  "commits": [
+    {
+      "id": "8f2a1c94b7e3d5a6f0c1e2d3b4a5968778695a4b",
+      "short_id": "8f2a1c94",
+      "title": "fix retries",
+      "message": "fix retries\n\nmore details",
+      "author_name": "User A",
+      "authored_date": "2025-01-15T10:00:00.000+01:00",
+      "web_url": "https://gitlab.example.com/group/service-y/-/commit/8f2a1c94b7e3d5a6f0c1e2d3b4a5968778695a4b"
+    }
+  ]
+}"#,
+        )
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--output-format",
+        "json",
+        "--versions",
+        "tests/assets/gitlab-versions.toml",
+    ]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+    cmd.env("ENVEE_GITLAB_TOKEN", "test-token");
+    cmd.env("ENVEE_GITLAB_API_URL", server.url());
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "service-y",
+              "display_name": null,
+              "versions": {
+                "dev": "2.0.1",
+                "prod": "2.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "patch",
+                  "count": 1,
+                  "description": "prod 1 patch behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": [
+            {
+              "app": "service-y",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "dev",
+              "from_version": "2.0.0",
+              "to_version": "2.0.1",
+              "compare_url": "https://gitlab.example.com/group/service-y/-/compare/v2.0.0...v2.0.1",
+              "commits": [
+                {
+                  "sha": "8f2a1c94b7e3d5a6f0c1e2d3b4a5968778695a4b",
+                  "message": "fix retries\n\nmore details",
+                  "author": "User A",
+                  "date": "2025-01-15T09:00:00Z",
+                  "html_url": "https://gitlab.example.com/group/service-y/-/commit/8f2a1c94b7e3d5a6f0c1e2d3b4a5968778695a4b"
+                }
+              ],
+              "total_commits": 1,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": []
+        }
+
+        ----- stderr -----
+        "#);
+    });
+    mock.assert();
+}
+
+#[test]
+fn commit_logs_are_served_from_the_cache_on_subsequent_runs() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = gitlab_compare_mock(&mut server).expect(1).create();
+    let fx = Fixture::new();
+    let run = || {
+        let mut cmd = fx.cmd([
+            "run",
+            "--stdout-plain",
+            "--versions",
+            "tests/assets/gitlab-versions.toml",
+        ]);
+        cmd.env("ENVEE_GITLAB_TOKEN", "test-token");
+        cmd.env("ENVEE_GITLAB_API_URL", server.url());
+        cmd.output().expect("command should've run")
+    };
+
+    // WHEN
+    let first = run();
+    let second = run();
+
+    // THEN
+    assert!(first.status.success());
+    assert!(second.status.success());
+    assert_eq!(first.stdout, second.stdout);
+    mock.assert();
+}
+
+#[test]
+fn refreshing_the_cache_fetches_commit_logs_again() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = gitlab_compare_mock(&mut server).expect(2).create();
+    let fx = Fixture::new();
+    let run = |extra_args: &[&str]| {
+        let mut cmd = fx.cmd(["run", "--versions", "tests/assets/gitlab-versions.toml"]);
+        cmd.args(extra_args);
+        cmd.env("ENVEE_GITLAB_TOKEN", "test-token");
+        cmd.env("ENVEE_GITLAB_API_URL", server.url());
+        cmd.output().expect("command should've run")
+    };
+
+    // WHEN
+    let first = run(&[]);
+    let second = run(&["--refresh"]);
+    let third = run(&[]);
+
+    // THEN
+    assert!(first.status.success());
+    assert!(second.status.success());
+    assert!(third.status.success());
+    mock.assert();
+}
+
+#[test]
+fn the_cache_is_skipped_when_disabled() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = gitlab_compare_mock(&mut server).expect(2).create();
+    let fx = Fixture::new();
+    let run = || {
+        let mut cmd = fx.cmd([
+            "run",
+            "--no-cache",
+            "--versions",
+            "tests/assets/gitlab-versions.toml",
+        ]);
+        cmd.env("ENVEE_GITLAB_TOKEN", "test-token");
+        cmd.env("ENVEE_GITLAB_API_URL", server.url());
+        cmd.output().expect("command should've run")
+    };
+
+    // WHEN
+    let first = run();
+    let second = run();
+
+    // THEN
+    assert!(first.status.success());
+    assert!(second.status.success());
+    mock.assert();
+}
+
+#[test]
+fn cache_entries_past_their_ttl_are_ignored() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = gitlab_compare_mock(&mut server).expect(2).create();
+    let fx = Fixture::new();
+    let run = || {
+        let mut cmd = fx.cmd([
+            "run",
+            "--cache-ttl",
+            "0s",
+            "--versions",
+            "tests/assets/gitlab-versions.toml",
+        ]);
+        cmd.env("ENVEE_GITLAB_TOKEN", "test-token");
+        cmd.env("ENVEE_GITLAB_API_URL", server.url());
+        cmd.output().expect("command should've run")
+    };
+
+    // WHEN
+    let first = run();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    let second = run();
+
+    // THEN
+    assert!(first.status.success());
+    assert!(second.status.success());
+    mock.assert();
+}
+
+fn gitlab_compare_mock(server: &mut mockito::Server) -> mockito::Mock {
+    server
+        .mock("GET", "/projects/group%2Fservice-y/repository/compare")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "web_url": "https://gitlab.example.com/group/service-y/-/compare/v2.0.0...v2.0.1",
+  "commits": [
+    {
+      "id": "8f2a1c94b7e3d5a6f0c1e2d3b4a5968778695a4b",
+      "message": "fix retries",
+      "author_name": "User A",
+      "authored_date": "2025-01-15T10:00:00.000+01:00",
+      "web_url": "https://gitlab.example.com/group/service-y/-/commit/8f2a1c94b7e3d5a6f0c1e2d3b4a5968778695a4b"
+    }
+  ]
+}"#,
+        )
+}
+
+#[test]
+fn commit_logs_are_shown_in_the_order_of_display_names() {
+    // GIVEN
+    let repos_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    for app in ["app-a", "app-b"] {
+        let repo_path = repos_dir.path().join(app);
+        init_repo(&repo_path);
+        git(
+            &repo_path,
+            &["commit", "--quiet", "--allow-empty", "-m", "initial commit"],
+        );
+        git(&repo_path, &["tag", "v1.0.0"]);
+        git(
+            &repo_path,
+            &[
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                &format!("{app}: add caching"),
+            ],
+        );
+        git(&repo_path, &["tag", "v1.1.0"]);
+    }
+
+    let versions_path = repos_dir.path().join("versions.toml");
+    std::fs::write(
+        &versions_path,
+        format!(
+            r#"envs = ["dev", "prod"]
+github_org = "org"
+git_tag_transform = "v{{{{version}}}}"
+provider = "local"
+local_repos_dir = "{}"
+
+versions = [
+    {{ app = "app-a", env = "dev", version = "1.1.0" }},
+    {{ app = "app-a", env = "prod", version = "1.0.0" }},
+    {{ app = "app-b", env = "dev", version = "1.1.0" }},
+    {{ app = "app-b", env = "prod", version = "1.0.0" }},
+]
+
+[apps.app-a]
+display_name = "Zeta"
+
+[apps.app-b]
+display_name = "Alpha"
+"#,
+            repos_dir.path().display()
+        ),
+    )
+    .expect("versions file should've been written");
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--stdout-plain", "--versions"]);
+    cmd.arg(&versions_path);
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\d+[a-z]+ ago", "[AGE] ago");
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        ┌──────┬───────┬───────┬─────────────────────────┐
+        │app   ┆ dev   ┆ prod  ┆ status                  │
+        ╞══════╪═══════╪═══════╪═════════════════════════╡
+        │Alpha ┆ 1.1.0 ┆ 1.0.0 ┆ prod 1 minor behind dev │
+        │Zeta  ┆ 1.1.0 ┆ 1.0.0 ┆ prod 1 minor behind dev │
+        └──────┴───────┴───────┴─────────────────────────┘
+
+        Alpha
+
+          prod..dev (1.0.0..1.1.0)
+
+           779824d  app-b: add caching  User A  [AGE] ago 
+
+        Zeta
+
+          prod..dev (1.0.0..1.1.0)
+
+           3751383  app-a: add caching  User A  [AGE] ago 
+
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn fetching_commit_logs_from_local_git_repositories_works() {
+    // GIVEN
+    let repos_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    let repo_path = repos_dir.path().join("service-z");
+    init_repo(&repo_path);
+    git(
+        &repo_path,
+        &["commit", "--quiet", "--allow-empty", "-m", "initial commit"],
+    );
+    git(&repo_path, &["tag", "v3.0.0"]);
+    git(
+        &repo_path,
+        &["commit", "--quiet", "--allow-empty", "-m", "add caching"],
+    );
+    git(
+        &repo_path,
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            "fix retries\n\nmore details",
+        ],
+    );
+    git(&repo_path, &["tag", "v3.1.0"]);
+
+    let versions_path = repos_dir.path().join("versions.toml");
+    std::fs::write(
+        &versions_path,
+        format!(
+            r#"envs = ["dev", "prod"]
+github_org = "org"
+git_tag_transform = "v{{{{version}}}}"
+provider = "local"
+local_repos_dir = "{}"
+
+[[versions]]
+app = "service-z"
+env = "prod"
+version = "3.0.0"
+
+[[versions]]
+app = "service-z"
+env = "dev"
+version = "3.1.0"
+"#,
+            repos_dir.path().display()
+        ),
+    )
+    .expect("versions file should've been written");
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--output-format", "json", "--versions"]);
+    cmd.arg(&versions_path);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+    cmd.env_remove("ENVEE_GITLAB_TOKEN");
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "service-z",
+              "display_name": null,
+              "versions": {
+                "dev": "3.1.0",
+                "prod": "3.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": [
+            {
+              "app": "service-z",
+              "display_name": null,
+              "from_env": "prod",
+              "to_env": "dev",
+              "from_version": "3.0.0",
+              "to_version": "3.1.0",
+              "compare_url": "",
+              "commits": [
+                {
+                  "sha": "d1459aa3add12173a51d6b6ddb388bf1986d056b",
+                  "message": "fix retries\n\nmore details",
+                  "author": "User A",
+                  "date": "2025-01-15T09:00:00Z",
+                  "html_url": ""
+                },
+                {
+                  "sha": "44c2bf425246309c4eafea1dc658fc4321f9ee3d",
+                  "message": "add caching",
+                  "author": "User A",
+                  "date": "2025-01-15T09:00:00Z",
+                  "html_url": ""
+                }
+              ],
+              "total_commits": 2,
+              "truncated": false
+            }
+          ],
+          "commit_log_errors": []
+        }
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+#[test]
+fn fetching_commit_logs_from_local_git_repositories_fails_for_missing_tags() {
+    // GIVEN
+    let repos_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    let repo_path = repos_dir.path().join("service-z");
+    init_repo(&repo_path);
+
+    let versions_path = repos_dir.path().join("versions.toml");
+    std::fs::write(
+        &versions_path,
+        format!(
+            r#"envs = ["dev", "prod"]
+github_org = "org"
+provider = "local"
+local_repos_dir = "{}"
+
+[[versions]]
+app = "service-z"
+env = "prod"
+version = "3.0.0"
+
+[[versions]]
+app = "service-z"
+env = "dev"
+version = "3.1.0"
+"#,
+            repos_dir.path().display()
+        ),
+    )
+    .expect("versions file should've been written");
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--output-format", "json", "--versions"]);
+    cmd.arg(&versions_path);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(
+        r#""generated_at": ".*""#,
+        r#""generated_at": "[TIMESTAMP]""#,
+    );
+    settings.add_filter(
+        &regex::escape(&repos_dir.path().display().to_string()),
+        "[REPOS_DIR]",
+    );
+    settings.bind(|| {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: false
+        exit_code: 1
+        ----- stdout -----
+        {
+          "schema_version": 1,
+          "generated_at": "[TIMESTAMP]",
+          "envs": [
+            "dev",
+            "prod"
+          ],
+          "apps": [
+            {
+              "app": "service-z",
+              "display_name": null,
+              "versions": {
+                "dev": "3.1.0",
+                "prod": "3.0.0"
+              },
+              "unknown_envs": [],
+              "sync_status": "behind",
+              "drifts": [
+                {
+                  "env": "prod",
+                  "reference_env": "dev",
+                  "direction": "behind",
+                  "component": "minor",
+                  "count": 1,
+                  "description": "prod 1 minor behind dev"
+                }
+              ],
+              "policy_violations": null,
+              "out_of_sync_since": null
+            }
+          ],
+          "commit_logs": [],
+          "commit_log_errors": [
+            {
+              "kind": "app",
+              "app": "service-z",
+              "from_env": "prod",
+              "to_env": "dev",
+              "message": "couldn't resolve \"3.0.0\" in local repository \"[REPOS_DIR]/service-z\": git rev-parse failed with exit status: 1"
+            }
+          ]
+        }
+
+        ----- stderr -----
+        Error: couldn't fetch commit logs for some apps:
+         - service-z (prod..dev): couldn't resolve "3.0.0" in local repository "[REPOS_DIR]/service-z"
+        "#);
+    });
+}
+
+#[test]
+fn debug_flag_shows_github_api_quota() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/rate_limit")
+        .match_header("authorization", "Bearer test-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "resources": {},
+  "rate": { "limit": 5000, "used": 10, "remaining": 4990, "reset": 1736935200 }
+}"#,
+        )
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--debug",
+        "--no-commit-logs",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
+
     // WHEN
     // THEN
     assert_cmd_snapshot!(cmd, @r"
     success: true
     exit_code: 0
     ----- stdout -----
-    Show results based on a versions file
+    DEBUG INFO
+
+    command:                              Run
+    config file:                          <NOT PROVIDED>
+    options from config file:             <NOT PROVIDED>
+    versions files:                       tests/assets/valid-versions.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    only validate versions file:          false
+    interactive:                          false
+    don't show commit logs:               true
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    output format:                        stdout
+    app filter:                           <NOT PROVIDED>
+    status filter:                        <NOT PROVIDED>
+    fail on:                              <NOT PROVIDED>
+    sort by:                              app
+    history file:                         <NOT PROVIDED>
+    github actions:                       false
+    table style:                          utf8
+    plain output:                         false
+
+    GitHub API quota:                     4990/5000 requests remaining, resets at 2025-01-15 10:00:00 UTC
+
+    ----- stderr -----
+    ");
+    mock.assert();
+}
+
+#[test]
+fn debug_flag_looks_up_github_api_quota_at_the_versions_files_api_url() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/rate_limit")
+        .match_header("authorization", "Bearer test-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "resources": {},
+  "rate": { "limit": 5000, "used": 10, "remaining": 4990, "reset": 1736935200 }
+}"#,
+        )
+        .create();
+
+    let temp_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    let versions_path = temp_dir.path().join("versions.toml");
+    std::fs::write(
+        &versions_path,
+        format!(
+            r#"envs = ["dev", "prod"]
+github_org = "dhth"
+github_api_url = "{}"
+
+[[versions]]
+app = "repo-a"
+env = "dev"
+version = "0.2.0"
+
+[[versions]]
+app = "repo-a"
+env = "prod"
+version = "0.1.0"
+"#,
+            server.url()
+        ),
+    )
+    .expect("versions file should've been written");
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--debug", "--no-commit-logs", "--versions"]);
+    cmd.arg(&versions_path);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+
+    // WHEN
+    let output = cmd.output().expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let quota_line = stdout
+        .lines()
+        .find(|line| line.starts_with("GitHub API quota:"))
+        .expect("quota line should've been printed");
+    insta::assert_snapshot!(quota_line, @"GitHub API quota:                     4990/5000 requests remaining, resets at 2025-01-15 10:00:00 UTC");
+    mock.assert();
+}
+
+#[test]
+fn debug_flag_reports_invalid_github_api_url_in_place_of_quota() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--debug",
+        "--no-commit-logs",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", "not a url");
+
+    // WHEN
+    let output = cmd.output().expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let quota_line = stdout
+        .lines()
+        .find(|line| line.starts_with("GitHub API quota:"))
+        .expect("quota line should've been printed");
+    insta::assert_snapshot!(quota_line, @r#"GitHub API quota:                     unknown (couldn't get the GitHub API URL: ENVEE_GH_API_URL is invalid: github_api_url "not a url" needs to start with "https://" or "http://")"#);
+}
+
+#[test]
+fn debug_flag_does_not_read_versions_from_stdin_to_look_up_github_api_quota() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--debug", "--no-commit-logs", "--versions", "-"]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+
+    // WHEN
+    let output = cmd.output().expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let quota_line = stdout
+        .lines()
+        .find(|line| line.starts_with("GitHub API quota:"))
+        .expect("quota line should've been printed");
+    insta::assert_snapshot!(quota_line, @"GitHub API quota:                     unknown (couldn't get the GitHub API URL: versions aren't read from stdin in debug mode)");
+}
+
+#[test]
+fn debug_flag_does_not_read_versions_at_a_git_revision_to_look_up_github_api_quota() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--debug",
+        "--no-commit-logs",
+        "--versions-git-ref",
+        "HEAD",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+
+    // WHEN
+    let output = cmd.output().expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let quota_line = stdout
+        .lines()
+        .find(|line| line.starts_with("GitHub API quota:"))
+        .expect("quota line should've been printed");
+    insta::assert_snapshot!(quota_line, @"GitHub API quota:                     unknown (couldn't get the GitHub API URL: versions files at a git revision aren't read in debug mode)");
+}
+
+#[test]
+fn failed_requests_are_retried() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let path = "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0";
+    let failing_mock = server
+        .mock("GET", path)
+        .match_query(mockito::Matcher::Any)
+        .with_status(502)
+        .with_body("bad gateway")
+        .expect(2)
+        .create();
+    let mock = server
+        .mock("GET", path)
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "html_url": "https://github.example.com/dhth/repo-b/compare/v1.0.0...v1.2.0",
+  "total_commits": 1,
+  "commits": [
+    {
+      "sha": "c536d77",
+      "html_url": "https://github.example.com/dhth/repo-b/commit/c536d77",
+      "commit": {
+        "message": "allow filtering apps",
+        "author": { "name": "User A", "date": "2025-01-15T10:00:00Z" }
+      }
+    }
+  ]
+}"#,
+        )
+        .expect(1)
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--stdout-plain",
+        "--filter",
+        "repo-b",
+        "--max-retry-wait",
+        "10ms",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
+
+    // WHEN
+    let output = cmd.output().expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    failing_mock.assert();
+    mock.assert();
+}
+
+#[test]
+fn requests_are_given_up_on_after_max_attempts() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0")
+        .match_query(mockito::Matcher::Any)
+        .with_status(503)
+        .with_body("service unavailable")
+        .expect(2)
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--stdout-plain",
+        "--filter",
+        "repo-b",
+        "--max-attempts",
+        "2",
+        "--max-retry-wait",
+        "10ms",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    Error: couldn't fetch commit logs for some apps:
+     - repo-b (prod..dev): GitHub API request failed with status 503 Service Unavailable: service unavailable (gave up after 2 attempts)
+    ");
+    mock.assert();
+}
+
+#[test]
+fn rate_limited_requests_fail_if_the_wait_is_too_long() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0")
+        .match_query(mockito::Matcher::Any)
+        .with_status(429)
+        .with_header("retry-after", "120")
+        .with_body("slow down")
+        .expect(1)
+        .create();
+
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--stdout-plain",
+        "--filter",
+        "repo-b",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    Error: couldn't fetch commit logs for some apps:
+     - repo-b (prod..dev): GitHub API request failed with status 429 Too Many Requests: slow down (not retrying, since the GitHub API asked to wait for 120s, which is longer than the maximum wait of 60s)
+    ");
+    mock.assert();
+}
+
+#[test]
+fn fail_on_exits_with_the_code_for_the_condition_met() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--status",
+        "in-sync",
+        "--fail-on",
+        "ahead,behind,missing",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 3
+    ----- stdout -----
+    ┌───────┬───────┬───────┬────────┐
+    │app    ┆ dev   ┆ prod  ┆ status │
+    ╞═══════╪═══════╪═══════╪════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓      │
+    └───────┴───────┴───────┴────────┘
+
+    ----- stderr -----
+    Error: conditions passed to --fail-on were met:
+     - behind: repo-b, repo-c
+    ");
+}
+
+#[test]
+fn fail_on_exits_with_zero_if_no_conditions_are_met() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--fail-on",
+        "ahead,missing",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn fail_on_out_of_sync_is_met_by_apps_that_are_behind() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--status",
+        "behind",
+        "--fail-on",
+        "out-of-sync",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 5
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
+
+    ----- stderr -----
+    Error: conditions passed to --fail-on were met:
+     - out-of-sync: repo-b, repo-c
+    ");
+}
+
+#[test]
+fn fail_on_fetch_error_exits_with_its_code() {
+    // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0")
+        .match_query(mockito::Matcher::Any)
+        .with_status(404)
+        .with_body("not found")
+        .create();
 
-    Usage: envee run [OPTIONS]
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--stdout-plain",
+        "--filter",
+        "repo-b",
+        "--fail-on",
+        "fetch-error",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
 
-    Options:
-      -V, --versions <PATH>              Path to the versions file [default: versions.toml]
-          --debug                        Output debug information without doing anything
-          --validate-only                Only validate versions file
-      -C, --no-commit-logs               Skip fetching and showing commit logs
-      -o, --output-format <FORMAT>       Output format [default: stdout] [possible values: stdout, html]
-      -f, --filter <REGEX>               Regex to use for filtering apps
-          --stdout-table-style <STRING>  Table style for stdout output [default: utf8] [possible values: ascii, markdown, none, utf8]
-          --stdout-plain                 Whether to use output text to stdout without color
-          --html-output <PATH>           Path for the HTML output file [default: envee-report.html]
-          --html-title <STRING>          Title for HTML report [default: envee]
-          --html-template <PATH>         Path to custom HTML template file
-      -h, --help                         Print help
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 7
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    └───────┴───────┴───────┴──────────────────────────┘
 
     ----- stderr -----
+    Error: couldn't fetch commit logs for some apps:
+     - repo-b (prod..dev): GitHub API request failed with status 404 Not Found: not found
+    Error: conditions passed to --fail-on were met:
+     - fetch-error: repo-b
     ");
+    mock.assert();
 }
 
 #[test]
-fn debug_flag_works_for_defaults() {
+fn fetch_errors_are_only_warned_about_if_not_passed_to_fail_on() {
     // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0")
+        .match_query(mockito::Matcher::Any)
+        .with_status(404)
+        .with_body("not found")
+        .create();
+
     let fx = Fixture::new();
-    let mut cmd = fx.cmd(["run", "--debug"]);
+    let mut cmd = fx.cmd([
+        "run",
+        "--stdout-plain",
+        "--filter",
+        "repo-b",
+        "--fail-on",
+        "ahead",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
 
     // WHEN
     // THEN
@@ -53,40 +2131,115 @@ fn debug_flag_works_for_defaults() {
     success: true
     exit_code: 0
     ----- stdout -----
-    DEBUG INFO
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    └───────┴───────┴───────┴──────────────────────────┘
 
-    command:                              Run
-    versions file:                        versions.toml
-    only validate versions file:          false
-    don't show commit logs:               false
-    output format:                        stdout
-    app filter:                           <NOT PROVIDED>
-    table style:                          utf8
-    plain output:                         false
+    ----- stderr -----
+    Warning: couldn't fetch commit logs for some apps:
+     - repo-b (prod..dev): GitHub API request failed with status 404 Not Found: not found
+    ");
+    mock.assert();
+}
+
+#[test]
+fn github_actions_are_reported_to_when_running_in_them() {
+    // GIVEN
+    let fx = Fixture::new();
+    let temp_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    let summary_path = temp_dir.path().join("summary.md");
+    let output_path = temp_dir.path().join("output");
+    std::fs::write(&summary_path, "# Earlier step\n\n")
+        .expect("summary file should've been written");
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--fail-on",
+        "behind",
+        "--markdown-title",
+        "versions",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("GITHUB_ACTIONS", "true");
+    cmd.env("GITHUB_STEP_SUMMARY", &summary_path);
+    cmd.env("GITHUB_OUTPUT", &output_path);
 
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 3
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
 
     ----- stderr -----
+    ::error title=repo-b is out of sync::prod 2 minors behind dev
+    ::error title=repo-c is out of sync::prod 1 major behind dev
+    Error: conditions passed to --fail-on were met:
+     - behind: repo-b, repo-c
+    ");
+    let summary = std::fs::read_to_string(&summary_path).expect("summary file should've been read");
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"_Generated at .*_", "_Generated at [TIMESTAMP]_");
+    settings.bind(|| {
+        insta::assert_snapshot!(summary, @r"
+        # Earlier step
+
+        # versions
+
+        _Generated at [TIMESTAMP]_
+
+        | app | dev | prod | status |
+        | --- | --- | --- | --- |
+        | repo-a | 0.1.0 | 0.1.0 | ✓ |
+        | repo-b | 1.2.0 | 1.0.0 | prod 2 minors behind dev |
+        | repo-c | 2.0.0 | 1.9.0 | prod 1 major behind dev |
+        ");
+    });
+    let output = std::fs::read_to_string(&output_path).expect("output file should've been read");
+    insta::assert_snapshot!(output, @r"
+    app_count=3
+    in_sync_count=1
+    out_of_sync_count=2
+    commit_log_error_count=0
     ");
 }
 
 #[test]
-fn debug_flag_works_with_overridden_flags_for_stdout_output() {
+fn github_actions_flag_reports_fetch_errors() {
     // GIVEN
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/repos/dhth/repo-b/compare/v1.0.0...v1.2.0")
+        .match_query(mockito::Matcher::Any)
+        .with_status(404)
+        .with_body("not found")
+        .create();
+
     let fx = Fixture::new();
     let mut cmd = fx.cmd([
         "run",
-        "--debug",
-        "--filter",
-        "repo",
-        "--output-format",
-        "stdout",
+        "--github-actions",
         "--stdout-plain",
-        "--stdout-table-style",
-        "ascii",
-        "--validate-only",
+        "--filter",
+        "repo-b",
+        "--fail-on",
+        "ahead",
         "--versions",
         "tests/assets/valid-versions.toml",
     ]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env("ENVEE_GH_API_URL", server.url());
 
     // WHEN
     // THEN
@@ -94,39 +2247,58 @@ fn debug_flag_works_with_overridden_flags_for_stdout_output() {
     success: true
     exit_code: 0
     ----- stdout -----
-    DEBUG INFO
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    └───────┴───────┴───────┴──────────────────────────┘
 
-    command:                              Run
-    versions file:                        tests/assets/valid-versions.toml
-    only validate versions file:          true
-    don't show commit logs:               false
-    output format:                        stdout
-    app filter:                           repo
-    table style:                          ascii
-    plain output:                         true
+    ----- stderr -----
+    ::warning title=repo-b is out of sync::prod 2 minors behind dev
+    ::warning title=couldn't fetch commit log for repo-b (prod..dev)::GitHub API request failed with status 404 Not Found: not found
+    Warning: couldn't fetch commit logs for some apps:
+     - repo-b (prod..dev): GitHub API request failed with status 404 Not Found: not found
+    ");
+    mock.assert();
+}
+
+#[test]
+fn github_actions_are_not_reported_to_outside_of_them() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("GITHUB_ACTIONS", "false");
 
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ┌───────┬───────┬───────┬──────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                   │
+    ╞═══════╪═══════╪═══════╪══════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                        │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev │
+    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ prod 1 major behind dev  │
+    └───────┴───────┴───────┴──────────────────────────┘
 
     ----- stderr -----
     ");
 }
 
 #[test]
-fn debug_flag_works_with_overridden_flags_for_html_output() {
+fn validating_versions_file_works() {
     // GIVEN
     let fx = Fixture::new();
     let mut cmd = fx.cmd([
         "run",
-        "--debug",
-        "--filter",
-        "repo",
-        "--html-output",
-        "output.html",
-        "--html-template",
-        "tests/assets/absent.html",
-        "--html-title",
-        "versions",
-        "--output-format",
-        "html",
         "--validate-only",
         "--versions",
         "tests/assets/valid-versions.toml",
@@ -138,25 +2310,14 @@ fn debug_flag_works_with_overridden_flags_for_html_output() {
     success: true
     exit_code: 0
     ----- stdout -----
-    DEBUG INFO
-
-    command:                              Run
-    versions file:                        tests/assets/valid-versions.toml
-    only validate versions file:          true
-    don't show commit logs:               false
-    output format:                        html
-    app filter:                           repo
-    output path:                          output.html
-    title:                                versions
-    template path:                        tests/assets/absent.html
-
+    versions file is valid ✅
 
     ----- stderr -----
     ");
 }
 
 #[test]
-fn works_for_valid_versions_file() {
+fn policy_violations_are_shown() {
     // GIVEN
     let fx = Fixture::new();
     let mut cmd = fx.cmd([
@@ -164,7 +2325,7 @@ fn works_for_valid_versions_file() {
         "--no-commit-logs",
         "--stdout-plain",
         "--versions",
-        "tests/assets/valid-versions.toml",
+        "tests/assets/policies.toml",
     ]);
 
     // WHEN
@@ -173,27 +2334,27 @@ fn works_for_valid_versions_file() {
     success: true
     exit_code: 0
     ----- stdout -----
-    ┌───────┬───────┬───────┬─────────┐
-    │app    ┆ dev   ┆ prod  ┆ in-sync │
-    ╞═══════╪═══════╪═══════╪═════════╡
-    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓       │
-    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ ✗       │
-    │repo-c ┆ 2.0.0 ┆ 1.9.0 ┆ ✗       │
-    └───────┴───────┴───────┴─────────┘
+    ┌───────┬───────┬───────┬───────────────────────────┬──────────────────────────────────────────────────────────────────────┐
+    │app    ┆ dev   ┆ prod  ┆ status                    ┆ policies                                                             │
+    ╞═══════╪═══════╪═══════╪═══════════════════════════╪══════════════════════════════════════════════════════════════════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓                         ┆ ✓                                                                    │
+    │repo-b ┆ 1.2.0 ┆ 1.0.0 ┆ prod 2 minors behind dev  ┆ prod-lag: prod is 2 minors behind dev, more than the 1 minor allowed │
+    │repo-c ┆ 2.0.0 ┆ 2.0.1 ┆ prod 1 patch ahead of dev ┆ no-ahead: prod is 1 patch ahead of dev                               │
+    └───────┴───────┴───────┴───────────────────────────┴──────────────────────────────────────────────────────────────────────┘
 
     ----- stderr -----
     ");
 }
 
 #[test]
-fn validating_versions_file_works() {
+fn validating_versions_file_does_not_check_policies() {
     // GIVEN
     let fx = Fixture::new();
     let mut cmd = fx.cmd([
         "run",
         "--validate-only",
         "--versions",
-        "tests/assets/valid-versions.toml",
+        "tests/assets/policies.toml",
     ]);
 
     // WHEN
@@ -208,10 +2369,68 @@ fn validating_versions_file_works() {
     ");
 }
 
+#[test]
+fn fail_on_policy_exits_with_its_code() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--stdout-plain",
+        "--status",
+        "in-sync",
+        "--fail-on",
+        "policy",
+        "--versions",
+        "tests/assets/policies.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 8
+    ----- stdout -----
+    ┌───────┬───────┬───────┬────────┬──────────┐
+    │app    ┆ dev   ┆ prod  ┆ status ┆ policies │
+    ╞═══════╪═══════╪═══════╪════════╪══════════╡
+    │repo-a ┆ 0.1.0 ┆ 0.1.0 ┆ ✓      ┆ ✓        │
+    └───────┴───────┴───────┴────────┴──────────┘
+
+    ----- stderr -----
+    Error: conditions passed to --fail-on were met:
+     - policy: repo-b, repo-c
+    ");
+}
+
 //-------------//
 //  FAILURES   //
 //-------------//
 
+#[test]
+fn interactive_mode_fails_without_a_terminal() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--interactive",
+        "--no-commit-logs",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: --interactive needs stdout to be a terminal
+    ");
+}
+
 #[test]
 fn fails_if_provided_with_absent_versions_file() {
     // GIVEN
@@ -239,6 +2458,70 @@ fn fails_if_provided_with_absent_versions_file() {
     "#);
 }
 
+#[test]
+fn fails_if_versions_files_have_duplicate_versions() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--versions",
+        "tests/assets/split",
+        "--versions",
+        "tests/assets/prod-hotfix.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't get versions from "tests/assets/split", "tests/assets/prod-hotfix.toml"
+
+    Caused by:
+        versions config has errors:
+         - app "repo-b" has more than one version for env "prod" (in "tests/assets/split/prod.toml" and "tests/assets/prod-hotfix.toml")
+    "#);
+}
+
+#[test]
+fn fails_if_versions_from_stdin_are_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--versions",
+        "-",
+        "--versions-format",
+        "yaml",
+    ]);
+    let versions = r#"
+envs: [dev]
+github_org: dhth
+versions:
+  - app: repo-a
+    env: dev
+"#;
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd.pass_stdin(versions), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't get versions from stdin
+
+    Caused by:
+        versions[0]: missing field `version` at line 5 column 5
+    ");
+}
+
 #[test]
 fn fails_if_provided_with_invalid_versions_schema() {
     // GIVEN
@@ -392,11 +2675,7 @@ fn fails_if_no_versions_defined() {
     Error: couldn't get versions from file "tests/assets/no-versions.toml"
 
     Caused by:
-        TOML parse error at line 1, column 1
-          |
-        1 | envs = ["dev", "prod"]
-          | ^
-        missing field `versions`
+        no versions found; add [[versions]] entries to the versions file, configure a source under [sources], or configure [endpoints]
     "#);
 }
 
@@ -447,6 +2726,50 @@ fn fails_if_no_gh_token_is_provided() {
     ");
 }
 
+#[test]
+fn fails_if_no_gitlab_token_is_provided() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["run", "--versions", "tests/assets/gitlab-versions.toml"]);
+    cmd.env("ENVEE_GH_TOKEN", "test-token");
+    cmd.env_remove("ENVEE_GITLAB_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: ENVEE_GITLAB_TOKEN needs to be set to fetch commit logs from GitLab
+    ");
+}
+
+#[test]
+fn fails_if_provided_invalid_github_api_url() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "run",
+        "--no-commit-logs",
+        "--versions",
+        "tests/assets/valid-versions.toml",
+    ]);
+    cmd.env("ENVEE_GH_API_URL", "github.example.com/api/v3");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: ENVEE_GH_API_URL is invalid: github_api_url "github.example.com/api/v3" needs to start with "https://" or "http://"
+    "#);
+}
+
 #[test]
 fn fails_if_provided_with_absent_html_template_file() {
     // GIVEN
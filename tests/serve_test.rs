@@ -0,0 +1,225 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn shows_help() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "--help"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Serve an HTML report based on a versions file, refreshing it periodically
+
+    Usage: envee serve [OPTIONS]
+
+    Options:
+      -V, --versions <PATH>              Path to a versions file, a directory of them, or - for stdin (repeatable; files are merged) [default: versions.toml]
+          --envs <ENV>                   Order of environments (comma separated), overriding envs in the versions files
+          --versions-format <FORMAT>     Format of the versions files (detected from the file extension by default) [possible values: toml, yaml, json]
+          --versions-git-ref <REV>       Read versions files (and the files they point to) as they were at this git revision
+          --debug                        Output debug information without doing anything
+          --versions-repo <PATH>         Git repository to read versions files from when --versions-git-ref is used [default: .]
+          --watch                        Also refresh the report as soon as any of the versions files change
+          --refresh-interval <DURATION>  How often to refresh the report (eg. "30s", "5m") [default: 5m]
+          --host <IP>                    Address to listen on [default: 127.0.0.1]
+      -p, --port <NUMBER>                Port to listen on [default: 8080]
+      -C, --no-commit-logs               Skip fetching and showing commit logs
+          --max-commits <NUMBER>         Maximum number of commits to fetch per commit log [default: 1000]
+          --no-cache                     Don't read commit logs from, or write them to, the on-disk cache
+          --refresh                      Ignore cached commit logs and fetch them again (the cache is still updated)
+          --cache-ttl <DURATION>         How long cached commit logs stay valid for (eg. "12h", "7d") [default: 7d]
+          --max-attempts <NUMBER>        Maximum number of attempts for each API request (1 turns off retries) [default: 4]
+          --max-retry-wait <DURATION>    Longest time to wait before retrying an API request; requests fail if the API asks to wait longer [default: 60s]
+      -f, --filter <REGEX>               Regex to use for filtering apps
+          --status <STATUS>              Only show apps with these sync statuses (comma separated) [possible values: in-sync, behind, ahead, out-of-sync, not-applicable]
+          --sort-by <ATTRIBUTE>          Attribute to sort apps by [default: app] [possible values: app, status]
+          --history <PATH>               Record the results of every refresh in this history file, and show how long apps have been out of sync
+          --html-title <STRING>          Title for HTML report [default: envee]
+          --html-template <PATH>         Path to custom HTML template file
+      -h, --help                         Print help
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn debug_flag_works_for_defaults() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "--debug"]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Serve
+    versions files:                       versions.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    watch versions file:                  false
+    refresh interval:                     5m
+    address:                              127.0.0.1:8080
+    don't show commit logs:               false
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    app filter:                           <NOT PROVIDED>
+    status filter:                        <NOT PROVIDED>
+    sort by:                              app
+    history file:                         <NOT PROVIDED>
+    title:                                envee
+    template path:                        <NOT PROVIDED>
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_works_with_overridden_flags() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "serve",
+        "--debug",
+        "--watch",
+        "--refresh-interval",
+        "30s",
+        "--host",
+        "0.0.0.0",
+        "--port",
+        "9000",
+        "--no-commit-logs",
+        "--filter",
+        "repo",
+        "--status",
+        "behind",
+        "--html-title",
+        "versions",
+        "--html-template",
+        "tests/assets/absent.html",
+    ]);
+    cmd.env_remove("ENVEE_GH_TOKEN");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                              Serve
+    versions files:                       versions.toml
+    envs:                                 <NOT PROVIDED>
+    versions format:                      <NOT PROVIDED>
+    versions git ref:                     <NOT PROVIDED>
+    versions repo:                        <NOT PROVIDED>
+    watch versions file:                  true
+    refresh interval:                     30s
+    address:                              0.0.0.0:9000
+    don't show commit logs:               true
+    max commits per log:                  1000
+    don't use cache:                      false
+    refresh cache:                        false
+    cache TTL:                            7days
+    max attempts per request:             4
+    max wait before retrying:             1m
+    app filter:                           repo
+    status filter:                        behind
+    sort by:                              app
+    history file:                         <NOT PROVIDED>
+    title:                                versions
+    template path:                        tests/assets/absent.html
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn fails_before_listening_if_the_report_cannot_be_built() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "serve",
+        "--no-commit-logs",
+        "--versions",
+        "tests/assets/absent.toml",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't read file "tests/assets/absent.toml"
+
+    Caused by:
+        No such file or directory (os error 2)
+    "#);
+}
+
+#[test]
+fn fails_if_versions_are_to_be_read_from_stdin() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "--no-commit-logs", "--versions", "-"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: serve can't read versions from stdin
+    ");
+}
+
+#[test]
+fn fails_if_provided_with_invalid_refresh_interval() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["serve", "--refresh-interval", "soon"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'soon' for '--refresh-interval <DURATION>': expected number at 0
+
+    For more information, try '--help'.
+    ");
+}